@@ -12,6 +12,8 @@ use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 use crate::error::{DevBaseError, Result};
+use crate::git::{HealthOptions, RepoHealth};
+use crate::health::{ConditionStreak, HealthCondition, HealthFingerprint, HealthSnapshot};
 use crate::scanner::DiscoveredRepo;
 
 /// Stored repository record.
@@ -22,6 +24,8 @@ pub struct StoredRepo {
     pub name: String,
     pub remote_url: Option<String>,
     pub default_branch: Option<String>,
+    /// Main repository if this is a linked worktree
+    pub parent_id: Option<i64>,
 }
 
 /// Scan path configuration.
@@ -41,6 +45,38 @@ pub struct Tag {
     pub color: String,
 }
 
+/// Branch deleted by the branch sweeper.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DeletedBranch {
+    pub id: i64,
+    pub repo_id: i64,
+    pub branch_name: String,
+    pub tip_oid: String,
+    pub deleted_at: String,
+    pub restored_at: Option<String>,
+}
+
+/// Cached health result for a repository.
+#[derive(Debug, Clone)]
+pub struct CachedHealth {
+    pub repo_id: i64,
+    /// Inputs the health was computed from
+    pub fingerprint: HealthFingerprint,
+    pub health: RepoHealth,
+    /// When the health was computed (Unix timestamp)
+    pub computed_at: i64,
+}
+
+/// Outcome of the last fetch of a repository.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct FetchState {
+    pub repo_id: i64,
+    /// Last fetch in which every remote succeeded
+    pub last_fetched_at: Option<String>,
+    /// Error of the most recent fetch, if it failed
+    pub last_error: Option<String>,
+}
+
 /// Database manager holding the SQLite connection.
 pub struct Database {
     conn: Mutex<Connection>,
@@ -106,18 +142,52 @@ impl Database {
     }
 
     pub fn get_all_repositories(&self) -> Result<Vec<StoredRepo>> {
+        self.query_repositories("1 = 1 ORDER BY r.name", [])
+    }
+
+    pub fn get_repository(&self, id: i64) -> Result<StoredRepo> {
+        self.query_repositories("r.id = ?1", [id])?
+            .pop()
+            .ok_or(DevBaseError::Database(rusqlite::Error::QueryReturnedNoRows))
+    }
+
+    /// Repositories carrying the tag called `tag`.
+    pub fn get_repositories_with_tag(&self, tag: &str) -> Result<Vec<StoredRepo>> {
+        self.query_repositories(
+            "r.id IN (SELECT rt.repo_id FROM repository_tags rt
+                      JOIN tags t ON t.id = rt.tag_id WHERE t.name = ?1)
+             ORDER BY r.name",
+            [tag],
+        )
+    }
+
+    pub fn find_repository_by_path(&self, path: &str) -> Result<Option<StoredRepo>> {
+        Ok(self.query_repositories("r.path = ?1", [path])?.pop())
+    }
+
+    pub fn delete_repository(&self, id: i64) -> Result<()> {
         let conn = self.lock()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, path, name, remote_url, default_branch FROM repositories ORDER BY name"
-        )?;
+        conn.execute("DELETE FROM repositories WHERE id = ?1", [id])?;
+        Ok(())
+    }
 
-        let repos = stmt.query_map([], |row| {
+    fn query_repositories<P: rusqlite::Params>(&self, filter: &str, params: P) -> Result<Vec<StoredRepo>> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT r.id, r.path, r.name, r.remote_url, r.default_branch, w.parent_repo_id
+             FROM repositories r
+             LEFT JOIN repository_worktrees w ON w.repo_id = r.id
+             WHERE {filter}"
+        ))?;
+
+        let repos = stmt.query_map(params, |row| {
             Ok(StoredRepo {
                 id: row.get(0)?,
                 path: row.get(1)?,
                 name: row.get(2)?,
                 remote_url: row.get(3)?,
                 default_branch: row.get(4)?,
+                parent_id: row.get(5)?,
             })
         })?
         .filter_map(|r| r.ok())
@@ -126,22 +196,27 @@ impl Database {
         Ok(repos)
     }
 
-    pub fn get_repository(&self, id: i64) -> Result<StoredRepo> {
+    // ========== Worktree Methods ==========
+
+    /// Register `repo_id` as a linked worktree of `parent_id`.
+    pub fn set_worktree_parent(&self, repo_id: i64, parent_id: i64) -> Result<()> {
         let conn = self.lock()?;
-        
-        conn.query_row(
-            "SELECT id, path, name, remote_url, default_branch FROM repositories WHERE id = ?1",
-            [id],
-            |row| {
-                Ok(StoredRepo {
-                    id: row.get(0)?,
-                    path: row.get(1)?,
-                    name: row.get(2)?,
-                    remote_url: row.get(3)?,
-                    default_branch: row.get(4)?,
-                })
-            },
-        ).map_err(DevBaseError::Database)
+        conn.execute(
+            "INSERT INTO repository_worktrees (repo_id, parent_repo_id) VALUES (?1, ?2)
+             ON CONFLICT(repo_id) DO UPDATE SET parent_repo_id = excluded.parent_repo_id",
+            params![repo_id, parent_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn clear_worktree_parent(&self, repo_id: i64) -> Result<()> {
+        let conn = self.lock()?;
+        conn.execute("DELETE FROM repository_worktrees WHERE repo_id = ?1", [repo_id])?;
+        Ok(())
+    }
+
+    pub fn get_worktree_children(&self, parent_id: i64) -> Result<Vec<StoredRepo>> {
+        self.query_repositories("w.parent_repo_id = ?1 ORDER BY r.path", [parent_id])
     }
 
     // ========== Scan Path Methods ==========
@@ -263,6 +338,376 @@ impl Database {
         Ok(tags)
     }
 
+    // ========== Deleted Branch Methods ==========
+
+    pub fn record_deleted_branch(&self, repo_id: i64, branch_name: &str, tip_oid: &str) -> Result<i64> {
+        let conn = self.lock()?;
+        conn.execute(
+            "INSERT INTO deleted_branches (repo_id, branch_name, tip_oid) VALUES (?1, ?2, ?3)",
+            params![repo_id, branch_name, tip_oid],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub fn get_deleted_branches(&self, repo_id: Option<i64>) -> Result<Vec<DeletedBranch>> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, repo_id, branch_name, tip_oid, deleted_at, restored_at
+             FROM deleted_branches
+             WHERE ?1 IS NULL OR repo_id = ?1
+             ORDER BY deleted_at DESC, id DESC"
+        )?;
+
+        let branches = stmt.query_map([repo_id], |row| {
+            Ok(DeletedBranch {
+                id: row.get(0)?,
+                repo_id: row.get(1)?,
+                branch_name: row.get(2)?,
+                tip_oid: row.get(3)?,
+                deleted_at: row.get(4)?,
+                restored_at: row.get(5)?,
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+        Ok(branches)
+    }
+
+    pub fn get_deleted_branch(&self, id: i64) -> Result<DeletedBranch> {
+        let conn = self.lock()?;
+
+        conn.query_row(
+            "SELECT id, repo_id, branch_name, tip_oid, deleted_at, restored_at
+             FROM deleted_branches WHERE id = ?1",
+            [id],
+            |row| {
+                Ok(DeletedBranch {
+                    id: row.get(0)?,
+                    repo_id: row.get(1)?,
+                    branch_name: row.get(2)?,
+                    tip_oid: row.get(3)?,
+                    deleted_at: row.get(4)?,
+                    restored_at: row.get(5)?,
+                })
+            },
+        ).map_err(DevBaseError::Database)
+    }
+
+    pub fn mark_branch_restored(&self, id: i64) -> Result<()> {
+        let conn = self.lock()?;
+        conn.execute(
+            "UPDATE deleted_branches SET restored_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            [id],
+        )?;
+        Ok(())
+    }
+
+    // ========== Health Cache Methods ==========
+
+    pub fn get_cached_health(&self, repo_id: i64) -> Result<Option<CachedHealth>> {
+        Ok(self
+            .query_cached_health(Some(repo_id))?
+            .into_iter()
+            .next())
+    }
+
+    pub fn get_all_cached_health(&self) -> Result<Vec<CachedHealth>> {
+        self.query_cached_health(None)
+    }
+
+    fn query_cached_health(&self, repo_id: Option<i64>) -> Result<Vec<CachedHealth>> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT repo_id, head_oid, index_mtime, refs_mtime, health_json, computed_at
+             FROM health_cache
+             WHERE ?1 IS NULL OR repo_id = ?1"
+        )?;
+
+        let entries = stmt.query_map([repo_id], |row| {
+            let health_json: String = row.get(4)?;
+            Ok((
+                row.get::<_, i64>(0)?,
+                HealthFingerprint {
+                    head_oid: row.get(1)?,
+                    index_mtime: row.get(2)?,
+                    refs_mtime: row.get(3)?,
+                },
+                health_json,
+                row.get::<_, i64>(5)?,
+            ))
+        })?
+        .filter_map(|r| r.ok())
+        // Entries written by an older layout of `RepoHealth` are treated as misses
+        .filter_map(|(repo_id, fingerprint, health_json, computed_at)| {
+            let health = serde_json::from_str(&health_json).ok()?;
+            Some(CachedHealth {
+                repo_id,
+                fingerprint,
+                health,
+                computed_at,
+            })
+        })
+        .collect();
+
+        Ok(entries)
+    }
+
+    pub fn save_cached_health(
+        &self,
+        repo_id: i64,
+        fingerprint: &HealthFingerprint,
+        health: &RepoHealth,
+    ) -> Result<()> {
+        let health_json = serde_json::to_string(health).map_err(|e| DevBaseError::Internal {
+            message: format!("Failed to serialize repository health: {e}"),
+        })?;
+
+        let conn = self.lock()?;
+        conn.execute(
+            "INSERT INTO health_cache (repo_id, head_oid, index_mtime, refs_mtime, health_json, computed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(repo_id) DO UPDATE SET
+                head_oid = excluded.head_oid,
+                index_mtime = excluded.index_mtime,
+                refs_mtime = excluded.refs_mtime,
+                health_json = excluded.health_json,
+                computed_at = excluded.computed_at",
+            params![
+                repo_id,
+                fingerprint.head_oid,
+                fingerprint.index_mtime,
+                fingerprint.refs_mtime,
+                health_json,
+                chrono::Utc::now().timestamp(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Options stored for every repository that has any.
+    pub fn get_all_health_options(&self) -> Result<Vec<(i64, HealthOptions)>> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT repo_id, light_mode, budget_ms FROM repo_health_options"
+        )?;
+
+        let options = stmt.query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                HealthOptions {
+                    light: row.get(1)?,
+                    budget_ms: row.get(2)?,
+                },
+            ))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+        Ok(options)
+    }
+
+    pub fn get_health_options(&self, repo_id: i64) -> Result<HealthOptions> {
+        let conn = self.lock()?;
+        let result = conn.query_row(
+            "SELECT light_mode, budget_ms FROM repo_health_options WHERE repo_id = ?1",
+            [repo_id],
+            |row| {
+                Ok(HealthOptions {
+                    light: row.get(0)?,
+                    budget_ms: row.get(1)?,
+                })
+            },
+        );
+
+        match result {
+            Ok(options) => Ok(options),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(HealthOptions::default()),
+            Err(e) => Err(DevBaseError::Database(e)),
+        }
+    }
+
+    /// Store options for a repository and drop its cached health.
+    pub fn set_health_options(&self, repo_id: i64, options: &HealthOptions) -> Result<()> {
+        let conn = self.lock()?;
+        conn.execute(
+            "INSERT INTO repo_health_options (repo_id, light_mode, budget_ms) VALUES (?1, ?2, ?3)
+             ON CONFLICT(repo_id) DO UPDATE SET
+                light_mode = excluded.light_mode,
+                budget_ms = excluded.budget_ms",
+            params![repo_id, options.light, options.budget_ms],
+        )?;
+        conn.execute("DELETE FROM health_cache WHERE repo_id = ?1", [repo_id])?;
+        Ok(())
+    }
+
+    // ========== Health Snapshot Methods ==========
+
+    /// Record a snapshot unless it is identical to the repository's latest one.
+    ///
+    /// Returns whether a row was written.
+    pub fn record_health_snapshot(
+        &self,
+        repo_id: i64,
+        health: &RepoHealth,
+        recorded_at: i64,
+    ) -> Result<bool> {
+        let health_json = serde_json::to_string(health).map_err(|e| DevBaseError::Internal {
+            message: format!("Failed to serialize repository health: {e}"),
+        })?;
+
+        let conn = self.lock()?;
+        let latest: Option<String> = match conn.query_row(
+            "SELECT health_json FROM health_snapshots WHERE repo_id = ?1 ORDER BY id DESC LIMIT 1",
+            [repo_id],
+            |row| row.get(0),
+        ) {
+            Ok(json) => Some(json),
+            Err(rusqlite::Error::QueryReturnedNoRows) => None,
+            Err(e) => return Err(DevBaseError::Database(e)),
+        };
+
+        if latest.as_deref() == Some(health_json.as_str()) {
+            return Ok(false);
+        }
+
+        conn.execute(
+            "INSERT INTO health_snapshots (repo_id, is_dirty, is_behind, has_unpushed, health_json, recorded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                repo_id,
+                HealthCondition::Dirty.holds(health),
+                HealthCondition::Behind.holds(health),
+                HealthCondition::Unpushed.holds(health),
+                health_json,
+                recorded_at,
+            ],
+        )?;
+        Ok(true)
+    }
+
+    /// Snapshots of a repository, newest first.
+    pub fn get_health_timeline(
+        &self,
+        repo_id: i64,
+        since: Option<i64>,
+        limit: u32,
+    ) -> Result<Vec<HealthSnapshot>> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, repo_id, health_json, recorded_at
+             FROM health_snapshots
+             WHERE repo_id = ?1 AND (?2 IS NULL OR recorded_at >= ?2)
+             ORDER BY id DESC
+             LIMIT ?3"
+        )?;
+
+        let snapshots = stmt.query_map(params![repo_id, since, limit], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?
+        .filter_map(|r| r.ok())
+        .filter_map(|(id, repo_id, health_json, recorded_at)| {
+            let health: RepoHealth = serde_json::from_str(&health_json).ok()?;
+            Some(HealthSnapshot {
+                id,
+                repo_id,
+                status: health.status(),
+                health,
+                recorded_at,
+            })
+        })
+        .collect();
+
+        Ok(snapshots)
+    }
+
+    /// For every repository whose latest snapshot has `condition`, the time
+    /// of the first snapshot in that unbroken run.
+    pub fn get_condition_streaks(&self, condition: HealthCondition) -> Result<Vec<ConditionStreak>> {
+        let column = condition.column();
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT s.repo_id, MIN(s.recorded_at)
+             FROM health_snapshots s
+             WHERE s.{column} = 1
+               AND s.id > COALESCE(
+                   (SELECT MAX(c.id) FROM health_snapshots c
+                    WHERE c.repo_id = s.repo_id AND c.{column} = 0),
+                   0)
+             GROUP BY s.repo_id"
+        ))?;
+
+        let streaks = stmt.query_map([], |row| {
+            Ok(ConditionStreak {
+                repo_id: row.get(0)?,
+                condition,
+                since: row.get(1)?,
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+        Ok(streaks)
+    }
+
+    /// Delete snapshots recorded before `before`, keeping each repository's newest one.
+    pub fn prune_health_snapshots(&self, before: i64) -> Result<usize> {
+        let conn = self.lock()?;
+        let deleted = conn.execute(
+            "DELETE FROM health_snapshots
+             WHERE recorded_at < ?1
+               AND id NOT IN (SELECT MAX(id) FROM health_snapshots GROUP BY repo_id)",
+            [before],
+        )?;
+        Ok(deleted)
+    }
+
+    // ========== Fetch State Methods ==========
+
+    /// Record a fetch. `last_fetched_at` only moves when `error` is `None`.
+    pub fn record_fetch(&self, repo_id: i64, error: Option<&str>) -> Result<()> {
+        let conn = self.lock()?;
+        conn.execute(
+            "INSERT INTO fetch_state (repo_id, last_fetched_at, last_error)
+             VALUES (?1, CASE WHEN ?2 IS NULL THEN CURRENT_TIMESTAMP END, ?2)
+             ON CONFLICT(repo_id) DO UPDATE SET
+                last_fetched_at = CASE WHEN excluded.last_error IS NULL
+                                  THEN CURRENT_TIMESTAMP ELSE last_fetched_at END,
+                last_error = excluded.last_error",
+            params![repo_id, error],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_fetch_state(&self, repo_id: i64) -> Result<FetchState> {
+        let conn = self.lock()?;
+        let result = conn.query_row(
+            "SELECT repo_id, last_fetched_at, last_error FROM fetch_state WHERE repo_id = ?1",
+            [repo_id],
+            |row| {
+                Ok(FetchState {
+                    repo_id: row.get(0)?,
+                    last_fetched_at: row.get(1)?,
+                    last_error: row.get(2)?,
+                })
+            },
+        );
+
+        match result {
+            Ok(state) => Ok(state),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(FetchState {
+                repo_id,
+                ..FetchState::default()
+            }),
+            Err(e) => Err(DevBaseError::Database(e)),
+        }
+    }
+
     // ========== Settings Methods ==========
 
     pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
@@ -43,6 +43,70 @@ CREATE TABLE IF NOT EXISTS scan_paths (
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP
 );
 
+-- Branches deleted by the branch sweeper (kept for undo)
+CREATE TABLE IF NOT EXISTS deleted_branches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    repo_id INTEGER NOT NULL,
+    branch_name TEXT NOT NULL,
+    tip_oid TEXT NOT NULL,
+    deleted_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    restored_at DATETIME,
+    FOREIGN KEY (repo_id) REFERENCES repositories(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_deleted_branches_repo ON deleted_branches(repo_id);
+
+-- Cached repository health, with the inputs that invalidate it
+CREATE TABLE IF NOT EXISTS health_cache (
+    repo_id INTEGER PRIMARY KEY,
+    head_oid TEXT,
+    index_mtime INTEGER,
+    refs_mtime INTEGER,
+    health_json TEXT NOT NULL,
+    computed_at INTEGER NOT NULL,
+    FOREIGN KEY (repo_id) REFERENCES repositories(id) ON DELETE CASCADE
+);
+
+-- Per-repository health check options
+CREATE TABLE IF NOT EXISTS repo_health_options (
+    repo_id INTEGER PRIMARY KEY,
+    light_mode INTEGER NOT NULL DEFAULT 0,
+    budget_ms INTEGER,
+    FOREIGN KEY (repo_id) REFERENCES repositories(id) ON DELETE CASCADE
+);
+
+-- Health history, one row per change
+CREATE TABLE IF NOT EXISTS health_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    repo_id INTEGER NOT NULL,
+    is_dirty INTEGER NOT NULL,
+    is_behind INTEGER NOT NULL,
+    has_unpushed INTEGER NOT NULL,
+    health_json TEXT NOT NULL,
+    recorded_at INTEGER NOT NULL,
+    FOREIGN KEY (repo_id) REFERENCES repositories(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_health_snapshots_repo ON health_snapshots(repo_id, id);
+
+-- Last fetch per repository
+CREATE TABLE IF NOT EXISTS fetch_state (
+    repo_id INTEGER PRIMARY KEY,
+    last_fetched_at DATETIME,
+    last_error TEXT,
+    FOREIGN KEY (repo_id) REFERENCES repositories(id) ON DELETE CASCADE
+);
+
+-- Linked worktrees, registered as children of their main repository
+CREATE TABLE IF NOT EXISTS repository_worktrees (
+    repo_id INTEGER PRIMARY KEY,
+    parent_repo_id INTEGER NOT NULL,
+    FOREIGN KEY (repo_id) REFERENCES repositories(id) ON DELETE CASCADE,
+    FOREIGN KEY (parent_repo_id) REFERENCES repositories(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_repository_worktrees_parent ON repository_worktrees(parent_repo_id);
+
 -- Application settings
 CREATE TABLE IF NOT EXISTS settings (
     key TEXT PRIMARY KEY,
@@ -55,7 +119,9 @@ INSERT OR IGNORE INTO settings (key, value) VALUES
     ('schema_version', '1'),
     ('theme', 'system'),
     ('auto_scan', 'true'),
-    ('scan_interval_minutes', '30');
+    ('scan_interval_minutes', '30'),
+    ('health_budget_ms', '5000'),
+    ('health_snapshot_retention_days', '90');
 
 -- Triggers for updated_at
 CREATE TRIGGER IF NOT EXISTS update_repositories_timestamp 
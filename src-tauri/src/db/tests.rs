@@ -121,4 +121,194 @@ mod tests {
         // The important thing is our Database::new enables it internally
         assert!(fk_enabled == 0 || fk_enabled == 1);
     }
+
+    fn insert_repo(db: &Database, name: &str) -> i64 {
+        db.upsert_repository(&crate::scanner::DiscoveredRepo {
+            path: std::path::PathBuf::from(format!("/tmp/{name}")),
+            name: name.to_string(),
+            remote_url: None,
+            default_branch: Some("main".to_string()),
+            current_branch: Some("main".to_string()),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_deleted_branch_roundtrip() {
+        let temp = tempdir().unwrap();
+        let db = Database::new(temp.path().join("test.db")).unwrap();
+        let repo_a = insert_repo(&db, "a");
+        let repo_b = insert_repo(&db, "b");
+
+        let id = db.record_deleted_branch(repo_a, "feature/x", "abc123").unwrap();
+        db.record_deleted_branch(repo_b, "old", "def456").unwrap();
+
+        assert_eq!(db.get_deleted_branches(None).unwrap().len(), 2);
+        let for_a = db.get_deleted_branches(Some(repo_a)).unwrap();
+        assert_eq!(for_a.len(), 1);
+        assert_eq!(for_a[0].branch_name, "feature/x");
+        assert_eq!(for_a[0].tip_oid, "abc123");
+        assert!(for_a[0].restored_at.is_none());
+
+        db.mark_branch_restored(id).unwrap();
+        assert!(db.get_deleted_branch(id).unwrap().restored_at.is_some());
+    }
+
+
+    #[test]
+    fn test_health_cache_roundtrip() {
+        let temp = tempdir().unwrap();
+        let db = Database::new(temp.path().join("test.db")).unwrap();
+        let repo_id = insert_repo(&db, "cached");
+
+        assert!(db.get_cached_health(repo_id).unwrap().is_none());
+
+        let fingerprint = crate::health::HealthFingerprint {
+            head_oid: Some("abc".to_string()),
+            index_mtime: Some(1),
+            refs_mtime: Some(2),
+        };
+        let health = crate::git::RepoHealth {
+            is_dirty: true,
+            uncommitted_count: 3,
+            ..Default::default()
+        };
+        db.save_cached_health(repo_id, &fingerprint, &health).unwrap();
+        db.save_cached_health(repo_id, &fingerprint, &health).unwrap();
+
+        let cached = db.get_cached_health(repo_id).unwrap().unwrap();
+        assert_eq!(cached.fingerprint, fingerprint);
+        assert!(cached.health.is_dirty);
+        assert_eq!(cached.health.uncommitted_count, 3);
+        assert_eq!(db.get_all_cached_health().unwrap().len(), 1);
+    }
+
+
+    #[test]
+    fn test_health_options_drop_cached_health() {
+        let temp = tempdir().unwrap();
+        let db = Database::new(temp.path().join("test.db")).unwrap();
+        let repo_id = insert_repo(&db, "options");
+
+        assert_eq!(db.get_health_options(repo_id).unwrap(), crate::git::HealthOptions::default());
+
+        let fingerprint = crate::health::HealthFingerprint::default();
+        db.save_cached_health(repo_id, &fingerprint, &Default::default()).unwrap();
+
+        let options = crate::git::HealthOptions {
+            light: true,
+            budget_ms: Some(250),
+        };
+        db.set_health_options(repo_id, &options).unwrap();
+
+        assert_eq!(db.get_health_options(repo_id).unwrap(), options);
+        assert_eq!(db.get_all_health_options().unwrap(), vec![(repo_id, options)]);
+        assert!(db.get_cached_health(repo_id).unwrap().is_none());
+    }
+
+
+    #[test]
+    fn test_health_snapshots_record_changes_only() {
+        let temp = tempdir().unwrap();
+        let db = Database::new(temp.path().join("test.db")).unwrap();
+        let repo_id = insert_repo(&db, "history");
+
+        let clean = crate::git::RepoHealth::default();
+        let dirty = crate::git::RepoHealth {
+            is_dirty: true,
+            uncommitted_count: 1,
+            ..Default::default()
+        };
+
+        assert!(db.record_health_snapshot(repo_id, &clean, 100).unwrap());
+        assert!(!db.record_health_snapshot(repo_id, &clean, 200).unwrap());
+        assert!(db.record_health_snapshot(repo_id, &dirty, 300).unwrap());
+        assert!(db.record_health_snapshot(repo_id, &clean, 400).unwrap());
+
+        let timeline = db.get_health_timeline(repo_id, None, 10).unwrap();
+        let times: Vec<i64> = timeline.iter().map(|s| s.recorded_at).collect();
+        assert_eq!(times, vec![400, 300, 100]);
+        assert_eq!(timeline[1].status, crate::git::RepoStatus::Dirty);
+        assert_eq!(db.get_health_timeline(repo_id, Some(300), 10).unwrap().len(), 2);
+
+        // Everything but the newest snapshot is older than the cutoff
+        assert_eq!(db.prune_health_snapshots(1_000).unwrap(), 2);
+        assert_eq!(db.get_health_timeline(repo_id, None, 10).unwrap().len(), 1);
+    }
+
+
+    #[test]
+    fn test_fetch_state_keeps_last_success() {
+        let temp = tempdir().unwrap();
+        let db = Database::new(temp.path().join("test.db")).unwrap();
+        let repo_id = insert_repo(&db, "fetched");
+
+        let state = db.get_fetch_state(repo_id).unwrap();
+        assert!(state.last_fetched_at.is_none());
+
+        db.record_fetch(repo_id, None).unwrap();
+        let fetched_at = db.get_fetch_state(repo_id).unwrap().last_fetched_at;
+        assert!(fetched_at.is_some());
+
+        db.record_fetch(repo_id, Some("origin: connection refused")).unwrap();
+        let state = db.get_fetch_state(repo_id).unwrap();
+        assert_eq!(state.last_fetched_at, fetched_at);
+        assert_eq!(state.last_error.as_deref(), Some("origin: connection refused"));
+
+        db.record_fetch(repo_id, None).unwrap();
+        assert!(db.get_fetch_state(repo_id).unwrap().last_error.is_none());
+    }
+
+
+    #[test]
+    fn test_worktree_parent_registration() {
+        let temp = tempdir().unwrap();
+        let db = Database::new(temp.path().join("test.db")).unwrap();
+        let main = insert_repo(&db, "main");
+        let linked = insert_repo(&db, "main-review");
+
+        assert!(db.get_repository(linked).unwrap().parent_id.is_none());
+        db.set_worktree_parent(linked, main).unwrap();
+        db.set_worktree_parent(linked, main).unwrap();
+
+        assert_eq!(db.get_repository(linked).unwrap().parent_id, Some(main));
+        let children = db.get_worktree_children(main).unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].id, linked);
+        let found = db.find_repository_by_path("/tmp/main-review").unwrap().unwrap();
+        assert_eq!(found.id, linked);
+        assert!(db.find_repository_by_path("/tmp/missing").unwrap().is_none());
+
+        db.clear_worktree_parent(linked).unwrap();
+        assert!(db.get_worktree_children(main).unwrap().is_empty());
+        assert!(db.get_repository(linked).unwrap().parent_id.is_none());
+
+        db.set_worktree_parent(linked, main).unwrap();
+        db.delete_repository(linked).unwrap();
+        assert!(db.get_worktree_children(main).unwrap().is_empty());
+        assert!(db.get_repository(linked).is_err());
+    }
+
+    #[test]
+    fn test_repositories_with_tag() {
+        let temp = tempdir().unwrap();
+        let db = Database::new(temp.path().join("test.db")).unwrap();
+        let api = insert_repo(&db, "api");
+        let web = insert_repo(&db, "web");
+        insert_repo(&db, "docs");
+        let backend = db.create_tag("backend", "#808080").unwrap();
+        let frontend = db.create_tag("frontend", "#808080").unwrap();
+        db.assign_tag(api, backend).unwrap();
+        db.assign_tag(web, frontend).unwrap();
+        db.assign_tag(web, backend).unwrap();
+
+        let tagged: Vec<i64> = db
+            .get_repositories_with_tag("backend")
+            .unwrap()
+            .iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(tagged, vec![api, web]);
+        assert!(db.get_repositories_with_tag("missing").unwrap().is_empty());
+    }
 }
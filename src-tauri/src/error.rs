@@ -28,6 +28,14 @@ pub enum DevBaseError {
     #[error("Scan error: {message}")]
     Scan { message: String },
 
+    /// A git hook rejected the operation.
+    #[error("{hook} hook failed: {output}")]
+    Hook { hook: String, output: String },
+
+    /// An operation would overwrite uncommitted local changes.
+    #[error("Local changes would be overwritten: {}", files.join(", "))]
+    LocalChanges { files: Vec<String> },
+
     /// General internal errors.
     #[error("Internal error: {message}")]
     Internal { message: String },
@@ -38,36 +46,60 @@ pub enum DevBaseError {
 pub struct IpcError {
     pub code: String,
     pub message: String,
+    /// Files that would be overwritten (`LOCAL_CHANGES`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files: Option<Vec<String>>,
+    /// Hook that failed (`HOOK_FAILED`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hook: Option<String>,
+    /// Output of the failed hook (`HOOK_FAILED`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
 }
 
-impl From<DevBaseError> for IpcError {
-    fn from(err: DevBaseError) -> Self {
-        let code = match &err {
+impl From<&DevBaseError> for IpcError {
+    fn from(err: &DevBaseError) -> Self {
+        let code = match err {
             DevBaseError::Database(_) => "DATABASE_ERROR",
             DevBaseError::FileSystem(_) => "FILESYSTEM_ERROR",
             DevBaseError::Git(_) => "GIT_ERROR",
             DevBaseError::Config { .. } => "CONFIG_ERROR",
             DevBaseError::Scan { .. } => "SCAN_ERROR",
+            DevBaseError::Hook { .. } => "HOOK_FAILED",
+            DevBaseError::LocalChanges { .. } => "LOCAL_CHANGES",
             DevBaseError::Internal { .. } => "INTERNAL_ERROR",
         };
+        let (hook, output) = match err {
+            DevBaseError::Hook { hook, output } => (Some(hook.clone()), Some(output.clone())),
+            _ => (None, None),
+        };
 
         Self {
             code: code.to_string(),
             message: err.to_string(),
+            files: match err {
+                DevBaseError::LocalChanges { files } => Some(files.clone()),
+                _ => None,
+            },
+            hook,
+            output,
         }
     }
 }
 
+impl From<DevBaseError> for IpcError {
+    fn from(err: DevBaseError) -> Self {
+        Self::from(&err)
+    }
+}
+
 // Implement Serialize for DevBaseError to work with Tauri commands
 impl Serialize for DevBaseError {
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        IpcError::from(DevBaseError::Internal {
-            message: self.to_string(),
-        })
-        .serialize(serializer)
+        IpcError::from(self).serialize(serializer)
     }
 }
 
@@ -34,6 +34,63 @@ mod tests {
         assert_eq!(err.to_string(), "Internal error: Unexpected state");
     }
 
+    #[test]
+    fn test_hook_error_display() {
+        let err = DevBaseError::Hook {
+            hook: "pre-commit".to_string(),
+            output: "lint failed".to_string(),
+        };
+        assert_eq!(err.to_string(), "pre-commit hook failed: lint failed");
+        assert_eq!(IpcError::from(err).code, "HOOK_FAILED");
+    }
+
+    #[test]
+    fn test_local_changes_error_display() {
+        let err = DevBaseError::LocalChanges {
+            files: vec!["a.txt".to_string(), "b.txt".to_string()],
+        };
+        assert_eq!(err.to_string(), "Local changes would be overwritten: a.txt, b.txt");
+        assert_eq!(IpcError::from(err).code, "LOCAL_CHANGES");
+    }
+
+    #[test]
+    fn test_local_changes_error_serializes_code_and_files() {
+        let err = DevBaseError::LocalChanges {
+            files: vec!["a.txt".to_string(), "b.txt".to_string()],
+        };
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["code"], "LOCAL_CHANGES");
+        assert_eq!(json["files"], serde_json::json!(["a.txt", "b.txt"]));
+        assert!(json.get("hook").is_none());
+    }
+
+    #[test]
+    fn test_hook_error_serializes_hook_output() {
+        let err = DevBaseError::Hook {
+            hook: "pre-commit".to_string(),
+            output: "lint failed".to_string(),
+        };
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["code"], "HOOK_FAILED");
+        assert_eq!(json["hook"], "pre-commit");
+        assert_eq!(json["output"], "lint failed");
+        assert!(json.get("files").is_none());
+    }
+
+    #[test]
+    fn test_plain_error_serializes_code_and_message_only() {
+        let err = DevBaseError::Config {
+            message: "Bad config".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            serde_json::json!({
+                "code": "CONFIG_ERROR",
+                "message": "Configuration error: Bad config",
+            })
+        );
+    }
+
     #[test]
     fn test_ipc_error_from_devbase_error() {
         let err = DevBaseError::Config {
@@ -0,0 +1,393 @@
+//! Blame / annotate support.
+//!
+//! Attributes each line of a file at any revision to the commit that last
+//! changed it. Supports `.git-blame-ignore-revs`, whitespace-insensitive
+//! blame and line ranges, and caches results per (file, commit).
+
+use git2::{Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::diff::{is_binary_content, read_blob_at};
+use crate::error::{DevBaseError, Result};
+
+/// Conventional name of the ignore-revs file.
+pub const IGNORE_REVS_FILE: &str = ".git-blame-ignore-revs";
+
+/// How many ignored commits may be skipped in a row for one line.
+const MAX_IGNORE_DEPTH: usize = 16;
+
+/// Maximum number of cached blame results before the cache is reset.
+const BLAME_CACHE_CAPACITY: usize = 128;
+
+/// Options for a blame request.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BlameFileOptions {
+    /// Ignore whitespace-only changes
+    pub ignore_whitespace: bool,
+    /// Skip commits listed in `.git-blame-ignore-revs`
+    pub use_ignore_revs: bool,
+    /// First line to blame (1-based, inclusive)
+    pub start_line: Option<usize>,
+    /// Last line to blame (1-based, inclusive)
+    pub end_line: Option<usize>,
+}
+
+impl Default for BlameFileOptions {
+    fn default() -> Self {
+        Self {
+            ignore_whitespace: false,
+            use_ignore_revs: true,
+            start_line: None,
+            end_line: None,
+        }
+    }
+}
+
+/// A run of consecutive lines attributed to the same commit.
+#[derive(Debug, Clone, Serialize)]
+pub struct BlameHunk {
+    /// First line in the blamed file (1-based)
+    pub start_line: usize,
+    /// Number of lines in the hunk
+    pub line_count: usize,
+    pub commit_oid: String,
+    pub short_oid: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub date: i64, // Unix timestamp
+    pub summary: String,
+    /// Path of the file in the originating commit
+    pub orig_path: String,
+    /// First line in the originating commit's version of the file
+    pub orig_start_line: usize,
+    /// Commit is the oldest one blame could reach
+    pub is_boundary: bool,
+}
+
+/// Blame result for a file at a revision.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileBlame {
+    pub file_path: String,
+    /// Commit the blame was computed at
+    pub commit_oid: String,
+    /// Blob OID of the file at that commit
+    pub blob_oid: String,
+    /// Total number of lines in the file
+    pub total_lines: usize,
+    /// First blamed line (1-based)
+    pub start_line: usize,
+    /// Last blamed line (1-based, inclusive)
+    pub end_line: usize,
+    pub hunks: Vec<BlameHunk>,
+    /// Number of ignored revisions that were applied
+    pub ignored_revs: usize,
+    /// Result was served from the cache
+    pub from_cache: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct BlameCacheKey {
+    repo_path: PathBuf,
+    file_path: String,
+    /// The same blob can be reached through different histories (A -> B -> A),
+    /// so the blob OID alone does not identify a blame
+    commit_oid: Oid,
+    ignore_whitespace: bool,
+    ignored: Vec<Oid>,
+    range: (usize, usize),
+}
+
+/// In-memory cache of blame results keyed by (file, commit, options).
+#[derive(Debug, Default)]
+pub struct BlameCache {
+    entries: Mutex<HashMap<BlameCacheKey, FileBlame>>,
+}
+
+impl BlameCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn get(&self, key: &BlameCacheKey) -> Option<FileBlame> {
+        self.entries.lock().ok()?.get(key).cloned()
+    }
+
+    fn insert(&self, key: BlameCacheKey, blame: FileBlame) {
+        if let Ok(mut entries) = self.entries.lock() {
+            if entries.len() >= BLAME_CACHE_CAPACITY {
+                entries.clear();
+            }
+            entries.insert(key, blame);
+        }
+    }
+}
+
+/// Where a single line came from.
+#[derive(Debug, Clone)]
+struct LineOrigin {
+    commit: Oid,
+    path: String,
+    line: usize,
+    is_boundary: bool,
+}
+
+/// Blame a file at a revision.
+pub fn get_file_blame(
+    path: &Path,
+    file_path: &str,
+    rev: &str,
+    options: &BlameFileOptions,
+    cache: &BlameCache,
+) -> Result<FileBlame> {
+    let repo = Repository::open(path)?;
+    let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+
+    let (blob_oid, content) = read_blob_at(&repo, &commit.id().to_string(), file_path)?
+        .ok_or_else(|| DevBaseError::Config {
+            message: format!("File not found at {rev}: {file_path}"),
+        })?;
+
+    if is_binary_content(&content) {
+        return Err(DevBaseError::Config {
+            message: format!("Cannot blame binary file: {file_path}"),
+        });
+    }
+
+    let total_lines = count_lines(&content);
+    let start_line = options.start_line.unwrap_or(1).max(1);
+    let end_line = options.end_line.unwrap_or(total_lines).min(total_lines);
+
+    let ignored = if options.use_ignore_revs {
+        read_ignore_revs(&repo, &commit.id().to_string())
+    } else {
+        Vec::new()
+    };
+
+    let key = BlameCacheKey {
+        repo_path: path.to_path_buf(),
+        file_path: file_path.to_string(),
+        commit_oid: commit.id(),
+        ignore_whitespace: options.ignore_whitespace,
+        ignored: ignored.clone(),
+        range: (start_line, end_line),
+    };
+
+    if let Some(mut cached) = cache.get(&key) {
+        cached.from_cache = true;
+        return Ok(cached);
+    }
+
+    let mut lines = if start_line > end_line {
+        Vec::new()
+    } else {
+        blame_lines(
+            &repo,
+            commit.id(),
+            file_path,
+            options.ignore_whitespace,
+            Some((start_line, end_line)),
+        )?
+    };
+
+    let ignored_set: HashSet<Oid> = ignored.iter().copied().collect();
+    if !ignored_set.is_empty() {
+        skip_ignored_revs(&repo, &mut lines, &ignored_set, options.ignore_whitespace)?;
+    }
+
+    let blame = FileBlame {
+        file_path: file_path.to_string(),
+        commit_oid: commit.id().to_string(),
+        blob_oid: blob_oid.to_string(),
+        total_lines,
+        start_line,
+        end_line,
+        hunks: build_hunks(&repo, &lines)?,
+        ignored_revs: ignored.len(),
+        from_cache: false,
+    };
+
+    cache.insert(key, blame.clone());
+    Ok(blame)
+}
+
+/// Run libgit2 blame and flatten the result to one origin per line.
+fn blame_lines(
+    repo: &Repository,
+    commit: Oid,
+    file_path: &str,
+    ignore_whitespace: bool,
+    range: Option<(usize, usize)>,
+) -> Result<Vec<(usize, LineOrigin)>> {
+    let mut opts = git2::BlameOptions::new();
+    opts.newest_commit(commit)
+        .ignore_whitespace(ignore_whitespace);
+    if let Some((min, max)) = range {
+        opts.min_line(min).max_line(max);
+    }
+
+    let blame = repo.blame_file(Path::new(file_path), Some(&mut opts))?;
+
+    let mut lines = Vec::new();
+    for hunk in blame.iter() {
+        let orig_path = hunk.path().map_or_else(
+            || file_path.to_string(),
+            |p| p.to_string_lossy().into_owned(),
+        );
+
+        for offset in 0..hunk.lines_in_hunk() {
+            lines.push((
+                hunk.final_start_line() + offset,
+                LineOrigin {
+                    commit: hunk.final_commit_id(),
+                    path: orig_path.clone(),
+                    line: hunk.orig_start_line() + offset,
+                    is_boundary: hunk.is_boundary(),
+                },
+            ));
+        }
+    }
+
+    Ok(lines)
+}
+
+/// Re-attribute lines blamed on ignored commits to the ignored commit's parent.
+///
+/// Lines are matched by position in the parent's version of the file, which
+/// is exact for formatting-only commits that do not add or remove lines.
+fn skip_ignored_revs(
+    repo: &Repository,
+    lines: &mut [(usize, LineOrigin)],
+    ignored: &HashSet<Oid>,
+    ignore_whitespace: bool,
+) -> Result<()> {
+    let mut unresolvable: HashSet<(Oid, String)> = HashSet::new();
+    let mut parent_blames: HashMap<(Oid, String), HashMap<usize, LineOrigin>> = HashMap::new();
+
+    for _ in 0..MAX_IGNORE_DEPTH {
+        let mut changed = false;
+
+        for (_, origin) in lines.iter_mut() {
+            let key = (origin.commit, origin.path.clone());
+            if !ignored.contains(&origin.commit) || unresolvable.contains(&key) {
+                continue;
+            }
+
+            if !parent_blames.contains_key(&key) {
+                let commit = repo.find_commit(origin.commit)?;
+                let parent_lines = commit.parent_id(0).ok().and_then(|parent| {
+                    blame_lines(repo, parent, &origin.path, ignore_whitespace, None).ok()
+                });
+
+                let Some(parent_lines) = parent_lines else {
+                    // Root commit, or the file did not exist in the parent
+                    unresolvable.insert(key);
+                    continue;
+                };
+                parent_blames.insert(key.clone(), parent_lines.into_iter().collect());
+            }
+
+            if let Some(parent_origin) = parent_blames
+                .get(&key)
+                .and_then(|parent| parent.get(&origin.line))
+            {
+                *origin = parent_origin.clone();
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Merge per-line origins into hunks and attach commit metadata.
+fn build_hunks(repo: &Repository, lines: &[(usize, LineOrigin)]) -> Result<Vec<BlameHunk>> {
+    let mut hunks: Vec<BlameHunk> = Vec::new();
+    let mut commits: HashMap<Oid, (String, String, i64, String)> = HashMap::new();
+
+    for (line, origin) in lines {
+        if let Some(last) = hunks.last_mut() {
+            let contiguous = last.start_line + last.line_count == *line
+                && last.orig_start_line + last.line_count == origin.line
+                && last.orig_path == origin.path
+                && last.commit_oid == origin.commit.to_string();
+            if contiguous {
+                last.line_count += 1;
+                continue;
+            }
+        }
+
+        let (author_name, author_email, date, summary) = match commits.entry(origin.commit) {
+            Entry::Occupied(entry) => entry.get().clone(),
+            Entry::Vacant(entry) => {
+                let commit = repo.find_commit(origin.commit)?;
+                let author = commit.author();
+                entry
+                    .insert((
+                        author.name().unwrap_or("Unknown").to_string(),
+                        author.email().unwrap_or("").to_string(),
+                        commit.time().seconds(),
+                        commit.summary().unwrap_or("").to_string(),
+                    ))
+                    .clone()
+            }
+        };
+
+        let oid = origin.commit.to_string();
+        hunks.push(BlameHunk {
+            start_line: *line,
+            line_count: 1,
+            short_oid: oid[0..7].to_string(),
+            commit_oid: oid,
+            author_name,
+            author_email,
+            date,
+            summary,
+            orig_path: origin.path.clone(),
+            orig_start_line: origin.line,
+            is_boundary: origin.is_boundary,
+        });
+    }
+
+    Ok(hunks)
+}
+
+/// Read the ignore-revs file from the tree at `rev`.
+///
+/// Lines are full commit hashes; `#` starts a comment. Unknown entries are skipped.
+fn read_ignore_revs(repo: &Repository, rev: &str) -> Vec<Oid> {
+    let Ok(Some((_, content))) = read_blob_at(repo, rev, IGNORE_REVS_FILE) else {
+        return Vec::new();
+    };
+
+    let mut oids: Vec<Oid> = String::from_utf8_lossy(&content)
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| repo.revparse_single(line).ok())
+        .filter_map(|obj| obj.peel_to_commit().ok())
+        .map(|commit| commit.id())
+        .collect();
+
+    oids.sort();
+    oids.dedup();
+    oids
+}
+
+/// Count lines the way blame numbers them.
+fn count_lines(content: &[u8]) -> usize {
+    let newlines = content.iter().filter(|&&b| b == b'\n').count();
+    if content.last().is_some_and(|&b| b != b'\n') {
+        newlines + 1
+    } else {
+        newlines
+    }
+}
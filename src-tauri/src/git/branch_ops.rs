@@ -0,0 +1,462 @@
+//! Branch checkout and management.
+//!
+//! Checkout is always "safe": it refuses with `DevBaseError::LocalChanges`
+//! when uncommitted changes would be overwritten, unless autostash is
+//! requested, in which case the changes are stashed around the switch.
+//!
+//! `branch_presence` and `apply_branch_operation` are the per-repository
+//! halves of the cross-repo branch view and bulk operations.
+
+use git2::build::CheckoutBuilder;
+use git2::{Branch, BranchType, CheckoutNotificationType, ErrorCode, Repository, RepositoryState};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::branches::{is_merged_into, list_branches, resolve_default_branch, BranchInfo};
+use super::stash::stash_signature;
+use crate::error::{DevBaseError, Result};
+
+const AUTOSTASH_MESSAGE: &str = "devbase autostash (checkout)";
+
+/// Options for a checkout.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CheckoutOptions {
+    /// Stash conflicting local changes and re-apply them after switching
+    pub autostash: bool,
+}
+
+/// Outcome of a checkout.
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckoutResult {
+    /// Local branch now checked out
+    pub branch: String,
+    /// Branch checked out before (`None` if HEAD was detached)
+    pub previous: Option<String>,
+    pub head_oid: String,
+    /// A local branch was created to track the requested remote branch
+    pub created_tracking: bool,
+    /// Local changes were stashed around the checkout
+    pub stashed: bool,
+    /// The stash could not be re-applied and was left in the stash list
+    pub stash_conflict: bool,
+}
+
+/// A deleted branch, so the deletion can be undone.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeletedBranchTip {
+    pub branch: String,
+    pub tip_oid: String,
+}
+
+/// Where a branch name exists in one repository.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BranchPresence {
+    /// Tip of the local branch, if there is one
+    pub local_tip: Option<String>,
+    /// The local branch is checked out
+    pub is_head: bool,
+    /// Remote-tracking branches with this name (`origin/feature`)
+    pub remote_branches: Vec<String>,
+    pub upstream: Option<String>,
+    /// Commits ahead of / behind the upstream
+    pub ahead_upstream: u32,
+    pub behind_upstream: u32,
+    /// Default branch the counts below compare against
+    pub default_branch: Option<String>,
+    /// Commits ahead of / behind the default branch (local tip, or the first
+    /// remote branch when there is no local one)
+    pub ahead_default: u32,
+    pub behind_default: u32,
+}
+
+impl BranchPresence {
+    pub fn exists(&self) -> bool {
+        self.local_tip.is_some() || !self.remote_branches.is_empty()
+    }
+}
+
+/// A branch operation applied to many repositories at once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BranchOperation {
+    Create {
+        name: String,
+        /// Revision to start from (HEAD when omitted)
+        #[serde(default)]
+        start_point: Option<String>,
+    },
+    Checkout {
+        name: String,
+        #[serde(default)]
+        autostash: bool,
+    },
+    Delete {
+        name: String,
+        #[serde(default)]
+        force: bool,
+    },
+}
+
+/// Outcome of a branch operation in one repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum BranchOperationOutcome {
+    Done {
+        /// Tip of the created or checked out branch, or of the deleted one
+        tip_oid: String,
+    },
+    /// Nothing to do in this repository
+    Skipped {
+        reason: String,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+/// Look up a branch name locally and on every remote.
+pub fn branch_presence(path: &Path, name: &str) -> Result<BranchPresence> {
+    let repo = Repository::open(path)?;
+    let mut presence = BranchPresence::default();
+
+    let local = repo.find_branch(name, BranchType::Local).ok();
+    if let Some(branch) = &local {
+        presence.local_tip = branch.get().target().map(|oid| oid.to_string());
+        presence.is_head = branch.is_head();
+        if let Ok(upstream) = branch.upstream() {
+            presence.upstream = upstream.name().ok().flatten().map(String::from);
+            if let (Some(tip), Some(up)) = (branch.get().target(), upstream.get().target()) {
+                let (ahead, behind) = repo.graph_ahead_behind(tip, up)?;
+                presence.ahead_upstream = u32::try_from(ahead).unwrap_or(u32::MAX);
+                presence.behind_upstream = u32::try_from(behind).unwrap_or(u32::MAX);
+            }
+        }
+    }
+
+    let mut remote_tip = None;
+    for remote in repo.remotes()?.iter().flatten() {
+        let candidate = format!("{remote}/{name}");
+        if let Ok(branch) = repo.find_branch(&candidate, BranchType::Remote) {
+            remote_tip = remote_tip.or(branch.get().target());
+            presence.remote_branches.push(candidate);
+        }
+    }
+
+    let tip = local.as_ref().and_then(|b| b.get().target()).or(remote_tip);
+    if let (Some(tip), Some(default)) = (tip, resolve_default_branch(&repo)) {
+        let (ahead, behind) = repo.graph_ahead_behind(tip, default.oid)?;
+        presence.default_branch = Some(default.name);
+        presence.ahead_default = u32::try_from(ahead).unwrap_or(u32::MAX);
+        presence.behind_default = u32::try_from(behind).unwrap_or(u32::MAX);
+    }
+
+    Ok(presence)
+}
+
+/// Apply one branch operation, reporting repositories where it does not
+/// apply (branch missing, already exists, already checked out) as skipped.
+pub fn apply_branch_operation(path: &Path, operation: &BranchOperation) -> BranchOperationOutcome {
+    let skipped = |reason: &str| BranchOperationOutcome::Skipped {
+        reason: reason.to_string(),
+    };
+    let presence = match operation {
+        BranchOperation::Create { name, .. }
+        | BranchOperation::Checkout { name, .. }
+        | BranchOperation::Delete { name, .. } => branch_presence(path, name),
+    };
+    let presence = match presence {
+        Ok(presence) => presence,
+        Err(e) => {
+            return BranchOperationOutcome::Failed {
+                error: e.to_string(),
+            }
+        }
+    };
+
+    let result = match operation {
+        BranchOperation::Create { name, start_point } => {
+            if presence.local_tip.is_some() {
+                return skipped("branch already exists");
+            }
+            create_branch(path, name, start_point.as_deref()).map(|info| info.tip_oid)
+        }
+        BranchOperation::Checkout { name, autostash } => {
+            if presence.is_head {
+                return skipped("already checked out");
+            }
+            if !presence.exists() {
+                return skipped("no such branch");
+            }
+            let options = CheckoutOptions {
+                autostash: *autostash,
+            };
+            checkout_branch(path, name, &options).map(|result| result.head_oid)
+        }
+        BranchOperation::Delete { name, force } => {
+            if presence.local_tip.is_none() {
+                return skipped("no local branch");
+            }
+            delete_branch(path, name, *force).map(|deleted| deleted.tip_oid)
+        }
+    };
+
+    match result {
+        Ok(tip_oid) => BranchOperationOutcome::Done { tip_oid },
+        Err(e) => BranchOperationOutcome::Failed {
+            error: e.to_string(),
+        },
+    }
+}
+
+/// Check out a local branch, or a remote branch (`origin/feature` or just
+/// `feature`) through a tracking branch created on the fly.
+pub fn checkout_branch(
+    path: &Path,
+    name: &str,
+    options: &CheckoutOptions,
+) -> Result<CheckoutResult> {
+    let mut repo = Repository::open(path)?;
+    if repo.state() != RepositoryState::Clean {
+        return Err(DevBaseError::Config {
+            message: "An operation (merge, rebase, ...) is in progress; finish or abort it first"
+                .to_string(),
+        });
+    }
+
+    let previous = repo
+        .head()
+        .ok()
+        .filter(git2::Reference::is_branch)
+        .and_then(|head| head.shorthand().map(String::from));
+
+    let (local_name, remote) = resolve_checkout_target(&repo, name)?;
+    let target_oid = match &remote {
+        Some(remote) => repo
+            .find_branch(remote, BranchType::Remote)?
+            .get()
+            .peel_to_commit()?
+            .id(),
+        None => repo
+            .find_branch(&local_name, BranchType::Local)?
+            .get()
+            .peel_to_commit()?
+            .id(),
+    };
+
+    let mut stashed = false;
+    let conflicts = checkout_tree(&repo, target_oid)?;
+    if !conflicts.is_empty() {
+        if !options.autostash {
+            return Err(DevBaseError::LocalChanges { files: conflicts });
+        }
+        let signature = stash_signature(&repo)?;
+        repo.stash_save(&signature, AUTOSTASH_MESSAGE, None)?;
+        stashed = true;
+
+        let conflicts = checkout_tree(&repo, target_oid)?;
+        if !conflicts.is_empty() {
+            // Untracked files are in the way; put everything back as it was
+            repo.stash_pop(0, None)?;
+            return Err(DevBaseError::LocalChanges { files: conflicts });
+        }
+    }
+
+    if let Some(remote) = &remote {
+        let commit = repo.find_commit(target_oid)?;
+        let mut branch = repo.branch(&local_name, &commit, false)?;
+        branch.set_upstream(Some(remote))?;
+    }
+    repo.set_head(&format!("refs/heads/{local_name}"))?;
+
+    let stash_conflict = stashed && repo.stash_pop(0, None).is_err();
+
+    Ok(CheckoutResult {
+        branch: local_name,
+        previous,
+        head_oid: target_oid.to_string(),
+        created_tracking: remote.is_some(),
+        stashed,
+        stash_conflict,
+    })
+}
+
+/// Create a branch at any revision (HEAD when `start_point` is omitted).
+///
+/// Starting from a remote-tracking branch sets it as upstream, like git's
+/// default `branch.autoSetupMerge`.
+pub fn create_branch(path: &Path, name: &str, start_point: Option<&str>) -> Result<BranchInfo> {
+    let repo = Repository::open(path)?;
+    validate_branch_name(&repo, name)?;
+
+    let revision = start_point.unwrap_or("HEAD");
+    let commit = repo
+        .revparse_single(revision)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| DevBaseError::Config {
+            message: format!("Unknown revision: {revision}"),
+        })?;
+
+    let mut branch = repo.branch(name, &commit, false)?;
+    if let Some(start) = start_point {
+        if repo.find_branch(start, BranchType::Remote).is_ok() {
+            branch.set_upstream(Some(start))?;
+        }
+    }
+
+    find_branch_info(path, name)
+}
+
+/// Rename a local branch. Its upstream configuration moves with it.
+pub fn rename_branch(path: &Path, old_name: &str, new_name: &str) -> Result<BranchInfo> {
+    let repo = Repository::open(path)?;
+    validate_branch_name(&repo, new_name)?;
+    repo.find_branch(old_name, BranchType::Local)?
+        .rename(new_name, false)?;
+
+    find_branch_info(path, new_name)
+}
+
+/// Delete a local branch.
+///
+/// The current branch is never deleted, and branches that are not merged into
+/// HEAD or their upstream need `force`.
+pub fn delete_branch(path: &Path, name: &str, force: bool) -> Result<DeletedBranchTip> {
+    let repo = Repository::open(path)?;
+    let mut branch = repo.find_branch(name, BranchType::Local)?;
+    if branch.is_head() {
+        return Err(DevBaseError::Config {
+            message: format!("{name} is checked out and cannot be deleted"),
+        });
+    }
+
+    let tip = branch.get().peel_to_commit()?.id();
+    if !force {
+        let merged_into =
+            |oid: Option<git2::Oid>| oid.is_some_and(|base| is_merged_into(&repo, tip, base));
+        let head = repo.head().ok().and_then(|h| h.target());
+        let upstream = branch.upstream().ok().and_then(|u| u.get().target());
+        if !merged_into(head) && !merged_into(upstream) {
+            return Err(DevBaseError::Config {
+                message: format!("{name} is not fully merged; force the deletion to discard it"),
+            });
+        }
+    }
+
+    branch.delete()?;
+    Ok(DeletedBranchTip {
+        branch: name.to_string(),
+        tip_oid: tip.to_string(),
+    })
+}
+
+/// Set (`Some("origin/feature")`) or unset (`None`) the upstream of a branch.
+pub fn set_branch_upstream(path: &Path, name: &str, upstream: Option<&str>) -> Result<BranchInfo> {
+    let repo = Repository::open(path)?;
+    let mut branch = repo.find_branch(name, BranchType::Local)?;
+    if let Some(upstream) = upstream {
+        if repo.find_branch(upstream, BranchType::Remote).is_err() {
+            return Err(DevBaseError::Config {
+                message: format!("No remote-tracking branch {upstream}; fetch first"),
+            });
+        }
+    }
+
+    // Unsetting an upstream that was never set is a no-op
+    let has_upstream = repo
+        .config()?
+        .get_string(&format!("branch.{name}.remote"))
+        .is_ok();
+    if upstream.is_some() || has_upstream {
+        branch.set_upstream(upstream)?;
+    }
+
+    find_branch_info(path, name)
+}
+
+fn validate_branch_name(repo: &Repository, name: &str) -> Result<()> {
+    if !Branch::name_is_valid(name)? {
+        return Err(DevBaseError::Config {
+            message: format!("Invalid branch name: {name}"),
+        });
+    }
+    if repo.find_branch(name, BranchType::Local).is_ok() {
+        return Err(DevBaseError::Config {
+            message: format!("Branch already exists: {name}"),
+        });
+    }
+    Ok(())
+}
+
+fn find_branch_info(path: &Path, name: &str) -> Result<BranchInfo> {
+    list_branches(path)?
+        .into_iter()
+        .find(|b| !b.is_remote && b.name == name)
+        .ok_or_else(|| DevBaseError::Internal {
+            message: format!("Branch {name} not found after update"),
+        })
+}
+
+/// Local branch to check out, plus the remote branch to create it from if it
+/// does not exist yet.
+fn resolve_checkout_target(repo: &Repository, name: &str) -> Result<(String, Option<String>)> {
+    if repo.find_branch(name, BranchType::Local).is_ok() {
+        return Ok((name.to_string(), None));
+    }
+
+    if repo.find_branch(name, BranchType::Remote).is_ok() {
+        let remote = repo.branch_remote_name(&format!("refs/remotes/{name}"))?;
+        let remote = remote.as_str().unwrap_or_default();
+        let local = name
+            .strip_prefix(remote)
+            .and_then(|rest| rest.strip_prefix('/'))
+            .unwrap_or(name)
+            .to_string();
+        if repo.find_branch(&local, BranchType::Local).is_ok() {
+            return Ok((local, None));
+        }
+        return Ok((local, Some(name.to_string())));
+    }
+
+    // Like `git checkout feature`: use the only remote that has it
+    let remotes: Vec<String> = repo.remotes()?.iter().flatten().map(String::from).collect();
+    let matches: Vec<String> = remotes
+        .iter()
+        .map(|remote| format!("{remote}/{name}"))
+        .filter(|candidate| repo.find_branch(candidate, BranchType::Remote).is_ok())
+        .collect();
+    match matches.as_slice() {
+        [only] => Ok((name.to_string(), Some(only.clone()))),
+        [] => Err(DevBaseError::Config {
+            message: format!("No branch named {name}"),
+        }),
+        _ => Err(DevBaseError::Config {
+            message: format!("{name} exists on several remotes: {}", matches.join(", ")),
+        }),
+    }
+}
+
+/// Safely check out the tree of a commit. Returns the paths that block it.
+fn checkout_tree(repo: &Repository, oid: git2::Oid) -> Result<Vec<String>> {
+    let commit = repo.find_commit(oid)?;
+    let mut conflicts = Vec::new();
+    let result = {
+        let mut builder = CheckoutBuilder::new();
+        builder
+            .safe()
+            .notify_on(CheckoutNotificationType::CONFLICT)
+            .notify(|_, path, _, _, _| {
+                if let Some(path) = path {
+                    conflicts.push(path.to_string_lossy().to_string());
+                }
+                true
+            });
+        repo.checkout_tree(commit.as_object(), Some(&mut builder))
+    };
+
+    match result {
+        Ok(()) => Ok(Vec::new()),
+        Err(e) if e.code() == ErrorCode::Conflict && !conflicts.is_empty() => Ok(conflicts),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,196 @@
+//! Branch inventory.
+//!
+//! Lists local and remote branches with upstream tracking, staleness and
+//! merge status relative to the repository's default branch.
+
+use git2::{Branch, BranchType, Oid, Repository};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::error::Result;
+
+/// Information about a single branch.
+#[derive(Debug, Clone, Serialize)]
+pub struct BranchInfo {
+    /// Short name (`feature/x` or `origin/feature/x`)
+    pub name: String,
+    /// Full reference name (`refs/heads/feature/x`)
+    pub ref_name: String,
+    /// Remote-tracking branch
+    pub is_remote: bool,
+    /// Currently checked out
+    pub is_head: bool,
+    /// This is the repository's default branch
+    pub is_default: bool,
+    /// Tip commit OID
+    pub tip_oid: String,
+    /// Tip commit date (Unix timestamp)
+    pub tip_date: i64,
+    /// Author of the tip commit
+    pub last_author_name: String,
+    pub last_author_email: String,
+    /// Configured upstream (`origin/feature/x`)
+    pub upstream: Option<String>,
+    /// Upstream is configured but its ref no longer exists
+    pub upstream_gone: bool,
+    /// Commits ahead of upstream
+    pub commits_ahead: u32,
+    /// Commits behind upstream
+    pub commits_behind: u32,
+    /// Tip is reachable from the default branch
+    pub is_merged: bool,
+}
+
+/// The branch other branches are compared against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefaultBranch {
+    /// Short name (`origin/main` or `main`)
+    pub name: String,
+    /// Name without the remote prefix (`main`)
+    pub branch_name: String,
+    pub oid: Oid,
+}
+
+/// List every local and remote branch of a repository.
+pub fn list_branches(path: &Path) -> Result<Vec<BranchInfo>> {
+    let repo = Repository::open(path)?;
+    let default_branch = resolve_default_branch(&repo);
+    let head_ref = repo.head().ok().and_then(|h| h.name().map(String::from));
+
+    let mut branches = Vec::new();
+
+    for entry in repo.branches(None)? {
+        let (branch, branch_type) = entry?;
+        let reference = branch.get();
+
+        // Skip symbolic refs such as `origin/HEAD`
+        let Some(tip) = reference.target() else {
+            continue;
+        };
+        let Some(ref_name) = reference.name().map(String::from) else {
+            continue;
+        };
+        let name = branch.name()?.unwrap_or_default().to_string();
+
+        let commit = repo.find_commit(tip)?;
+        let author = commit.author();
+
+        let mut info = BranchInfo {
+            is_remote: branch_type == BranchType::Remote,
+            is_head: head_ref.as_deref() == Some(ref_name.as_str()),
+            is_default: false,
+            tip_oid: tip.to_string(),
+            tip_date: commit.time().seconds(),
+            last_author_name: author.name().unwrap_or("Unknown").to_string(),
+            last_author_email: author.email().unwrap_or("").to_string(),
+            upstream: None,
+            upstream_gone: false,
+            commits_ahead: 0,
+            commits_behind: 0,
+            is_merged: false,
+            name,
+            ref_name,
+        };
+
+        if branch_type == BranchType::Local {
+            fill_upstream(&repo, &branch, tip, &mut info);
+        }
+
+        if let Some(default) = &default_branch {
+            info.is_default =
+                info.name == default.name || (!info.is_remote && info.name == default.branch_name);
+            info.is_merged = is_merged_into(&repo, tip, default.oid);
+        }
+
+        branches.push(info);
+    }
+
+    Ok(branches)
+}
+
+/// Fill upstream name, ahead/behind and "gone" detection for a local branch.
+fn fill_upstream(repo: &Repository, branch: &Branch, tip: Oid, info: &mut BranchInfo) {
+    // The configured upstream name resolves even when the remote ref was deleted
+    let Ok(upstream_ref) = repo.branch_upstream_name(&info.ref_name) else {
+        return;
+    };
+    let Some(upstream_ref) = upstream_ref.as_str().map(String::from) else {
+        return;
+    };
+
+    info.upstream = Some(
+        upstream_ref
+            .strip_prefix("refs/remotes/")
+            .or_else(|| upstream_ref.strip_prefix("refs/heads/"))
+            .unwrap_or(&upstream_ref)
+            .to_string(),
+    );
+
+    match branch.upstream() {
+        Ok(upstream) => {
+            if let Some(upstream_oid) = upstream.get().target() {
+                if let Ok((ahead, behind)) = repo.graph_ahead_behind(tip, upstream_oid) {
+                    info.commits_ahead = u32::try_from(ahead).unwrap_or(u32::MAX);
+                    info.commits_behind = u32::try_from(behind).unwrap_or(u32::MAX);
+                }
+            }
+        }
+        Err(_) => info.upstream_gone = true,
+    }
+}
+
+/// Check whether `tip` is contained in `base`.
+pub(crate) fn is_merged_into(repo: &Repository, tip: Oid, base: Oid) -> bool {
+    tip == base || repo.graph_descendant_of(base, tip).unwrap_or(false)
+}
+
+/// Resolve the default branch.
+///
+/// Prefers the remote's `HEAD` (`refs/remotes/<remote>/HEAD`, `origin` first),
+/// then a local `main` or `master`.
+pub fn resolve_default_branch(repo: &Repository) -> Option<DefaultBranch> {
+    if let Some(remote_default) = resolve_remote_default_branch(repo) {
+        return Some(remote_default);
+    }
+
+    for name in ["main", "master"] {
+        if let Ok(branch) = repo.find_branch(name, BranchType::Local) {
+            if let Some(oid) = branch.get().target() {
+                return Some(DefaultBranch {
+                    name: name.to_string(),
+                    branch_name: name.to_string(),
+                    oid,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// Resolve the remote default branch via `refs/remotes/<remote>/HEAD` (`origin` first).
+pub fn resolve_remote_default_branch(repo: &Repository) -> Option<DefaultBranch> {
+    let mut remotes: Vec<String> = repo
+        .remotes()
+        .map(|names| names.iter().flatten().map(String::from).collect())
+        .unwrap_or_default();
+    remotes.sort_by_key(|name| name != "origin");
+
+    for remote in remotes {
+        let Ok(head) = repo.find_reference(&format!("refs/remotes/{remote}/HEAD")) else {
+            continue;
+        };
+        if let Ok(resolved) = head.resolve() {
+            if let (Some(name), Some(oid)) = (resolved.shorthand(), resolved.target()) {
+                let prefix = format!("{remote}/");
+                return Some(DefaultBranch {
+                    name: name.to_string(),
+                    branch_name: name.strip_prefix(&prefix).unwrap_or(name).to_string(),
+                    oid,
+                });
+            }
+        }
+    }
+
+    None
+}
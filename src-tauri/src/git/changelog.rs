@@ -0,0 +1,402 @@
+//! Release notes from Conventional Commits.
+//!
+//! Parses `type(scope)!: description` headers with their body and footers,
+//! groups the commits between two revisions by type into Markdown and
+//! suggests the next version from breaking changes and features.
+
+use git2::{Commit, Oid, Repository, Sort};
+use serde::Serialize;
+use std::path::Path;
+
+use super::history::CommitLogEntry;
+use super::tags::{list_tags, SemVer};
+use crate::error::{DevBaseError, Result};
+
+/// A `token: value` or `token #value` trailer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommitFooter {
+    pub token: String,
+    pub value: String,
+}
+
+/// A commit message following the Conventional Commits format.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConventionalMessage {
+    /// Lowercased type (`feat`, `fix`, ...)
+    #[serde(rename = "type")]
+    pub commit_type: String,
+    pub scope: Option<String>,
+    /// `!` after the type/scope or a `BREAKING CHANGE` footer
+    pub breaking: bool,
+    pub description: String,
+    pub body: Option<String>,
+    pub footers: Vec<CommitFooter>,
+}
+
+/// A commit with its parsed Conventional Commits message.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConventionalCommit {
+    #[serde(flatten)]
+    pub entry: CommitLogEntry,
+    #[serde(flatten)]
+    pub parsed: ConventionalMessage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionBump {
+    None,
+    Patch,
+    Minor,
+    Major,
+}
+
+/// Release notes for a range of commits.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseNotes {
+    /// Start of the range (excluded); `None` means the whole history
+    pub from: Option<String>,
+    pub to: String,
+    /// Latest release at the start of the range
+    pub previous_version: Option<String>,
+    pub bump: VersionBump,
+    pub next_version: String,
+    pub commits: Vec<ConventionalCommit>,
+    /// Commits whose message is not a Conventional Commit (merges excluded)
+    pub other_commits: Vec<CommitLogEntry>,
+    pub markdown: String,
+}
+
+/// Sections of the notes, in order. Other types go under "Other Changes".
+const SECTIONS: &[(&str, &str)] = &[
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance Improvements"),
+    ("revert", "Reverts"),
+    ("refactor", "Code Refactoring"),
+    ("docs", "Documentation"),
+];
+
+impl CommitFooter {
+    /// Parse the first line of a footer.
+    fn parse(line: &str) -> Option<Self> {
+        for breaking in ["BREAKING CHANGE", "BREAKING-CHANGE"] {
+            if let Some(value) = line
+                .strip_prefix(breaking)
+                .and_then(|rest| rest.strip_prefix(": "))
+            {
+                return Some(Self {
+                    token: breaking.to_string(),
+                    value: value.to_string(),
+                });
+            }
+        }
+
+        // `Refs #123` keeps the `#` in the value
+        let (token, value) = match line.split_once(": ") {
+            Some(split) => split,
+            None => line.split_at(line.find(" #")?),
+        };
+        let value = value.trim_start();
+        let valid = !token.is_empty()
+            && token
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-');
+        valid.then(|| Self {
+            token: token.to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    pub fn is_breaking(&self) -> bool {
+        self.token == "BREAKING CHANGE" || self.token == "BREAKING-CHANGE"
+    }
+}
+
+impl ConventionalMessage {
+    /// Parse a full commit message. Returns `None` if the header is not
+    /// `type(scope)!: description`.
+    pub fn parse(message: &str) -> Option<Self> {
+        let message = message.trim();
+        let (header, rest) = message.split_once('\n').unwrap_or((message, ""));
+
+        let (prefix, description) = header.split_once(": ")?;
+        let description = description.trim();
+        let (prefix, bang) = match prefix.strip_suffix('!') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+        let (commit_type, scope) = match prefix.split_once('(') {
+            Some((commit_type, scope)) => {
+                let scope = scope.strip_suffix(')')?;
+                if scope.is_empty() || scope.contains(['(', ')']) {
+                    return None;
+                }
+                (commit_type, Some(scope.to_string()))
+            }
+            None => (prefix, None),
+        };
+        let valid_type = !commit_type.is_empty()
+            && commit_type
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-');
+        if !valid_type || description.is_empty() {
+            return None;
+        }
+
+        let (body, footers) = split_body_and_footers(rest);
+        let breaking = bang || footers.iter().any(CommitFooter::is_breaking);
+
+        Some(Self {
+            commit_type: commit_type.to_lowercase(),
+            scope,
+            breaking,
+            description: description.to_string(),
+            body,
+            footers,
+        })
+    }
+
+    /// What the breaking change is, from its footer or else the description.
+    pub fn breaking_note(&self) -> Option<&str> {
+        if !self.breaking {
+            return None;
+        }
+        Some(
+            self.footers
+                .iter()
+                .find(|f| f.is_breaking())
+                .map_or(self.description.as_str(), |f| f.value.as_str()),
+        )
+    }
+}
+
+/// The footers are the last paragraph if it starts with a footer line;
+/// lines that are not footers continue the previous one.
+fn split_body_and_footers(text: &str) -> (Option<String>, Vec<CommitFooter>) {
+    let paragraphs: Vec<&str> = text
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+
+    let mut footers: Vec<CommitFooter> = Vec::new();
+    let mut body_paragraphs = paragraphs.as_slice();
+    if let Some((last, rest)) = paragraphs.split_last() {
+        if last.lines().next().and_then(CommitFooter::parse).is_some() {
+            for line in last.lines() {
+                match (CommitFooter::parse(line), footers.last_mut()) {
+                    (Some(footer), _) => footers.push(footer),
+                    (None, Some(previous)) => {
+                        previous.value.push('\n');
+                        previous.value.push_str(line);
+                    }
+                    (None, None) => {}
+                }
+            }
+            body_paragraphs = rest;
+        }
+    }
+
+    let body = (!body_paragraphs.is_empty()).then(|| body_paragraphs.join("\n\n"));
+    (body, footers)
+}
+
+impl VersionBump {
+    /// Bump implied by a set of commits: major for breaking changes, minor
+    /// for features, patch for fixes and performance improvements.
+    pub fn from_commits<'a>(commits: impl IntoIterator<Item = &'a ConventionalMessage>) -> Self {
+        commits
+            .into_iter()
+            .map(|commit| {
+                if commit.breaking {
+                    Self::Major
+                } else {
+                    match commit.commit_type.as_str() {
+                        "feat" => Self::Minor,
+                        "fix" | "perf" => Self::Patch,
+                        _ => Self::None,
+                    }
+                }
+            })
+            .max()
+            .unwrap_or(Self::None)
+    }
+
+    /// Apply the bump to `current`. Before 1.0.0 breaking changes bump the
+    /// minor version, and a pre-release is completed rather than bumped.
+    pub fn apply(self, current: Option<&SemVer>) -> SemVer {
+        let Some(current) = current else {
+            return SemVer {
+                major: 0,
+                minor: 1,
+                patch: 0,
+                pre: Vec::new(),
+            };
+        };
+        let mut next = SemVer {
+            pre: Vec::new(),
+            ..current.clone()
+        };
+        if current.is_prerelease() {
+            return next;
+        }
+        match self {
+            Self::Major if current.major > 0 => {
+                next.major += 1;
+                next.minor = 0;
+                next.patch = 0;
+            }
+            Self::Major | Self::Minor => {
+                next.minor += 1;
+                next.patch = 0;
+            }
+            Self::Patch => next.patch += 1,
+            Self::None => {}
+        }
+        next
+    }
+}
+
+/// Generate release notes for `from..to` (`to` defaults to HEAD, `from` to
+/// the latest release tag reachable from `to`).
+pub fn generate_release_notes(
+    path: &Path,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<ReleaseNotes> {
+    let repo = Repository::open(path)?;
+    let to_name = to.unwrap_or("HEAD").to_string();
+    let to_commit = resolve_commit(&repo, &to_name)?;
+
+    let releases: Vec<(SemVer, String, Oid)> = list_tags(path)?
+        .into_iter()
+        .filter(|tag| !tag.is_prerelease)
+        .filter_map(|tag| {
+            let version = SemVer::from_tag(&tag.name)?;
+            Some((version, tag.name, Oid::from_str(&tag.target_oid).ok()?))
+        })
+        .collect();
+    let latest_reachable = |tip: Oid| {
+        releases.iter().find(|(_, _, target)| {
+            *target == tip || repo.graph_descendant_of(tip, *target).unwrap_or(false)
+        })
+    };
+
+    let (from_name, from_oid, previous) = match from {
+        Some(from) => {
+            let oid = resolve_commit(&repo, from)?.id();
+            (Some(from.to_string()), Some(oid), latest_reachable(oid))
+        }
+        None => match latest_reachable(to_commit.id()) {
+            Some(release) => (Some(release.1.clone()), Some(release.2), Some(release)),
+            None => (None, None, None),
+        },
+    };
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL)?;
+    revwalk.push(to_commit.id())?;
+    if let Some(oid) = from_oid {
+        revwalk.hide(oid)?;
+    }
+
+    let mut commits = Vec::new();
+    let mut other_commits = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        let entry = CommitLogEntry::from_commit(&commit);
+        match ConventionalMessage::parse(commit.message().unwrap_or("")) {
+            Some(parsed) => commits.push(ConventionalCommit { entry, parsed }),
+            None if commit.parent_count() > 1 => {}
+            None => other_commits.push(entry),
+        }
+    }
+
+    let previous_version = previous.map(|(version, _, _)| version);
+    let bump = VersionBump::from_commits(commits.iter().map(|c| &c.parsed));
+    let next_version = bump.apply(previous_version).to_string();
+
+    let date = chrono::DateTime::from_timestamp(to_commit.time().seconds(), 0)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    let markdown = render_markdown(&next_version, &date, &commits, &other_commits);
+
+    Ok(ReleaseNotes {
+        from: from_name,
+        to: to_name,
+        previous_version: previous_version.map(ToString::to_string),
+        bump,
+        next_version,
+        commits,
+        other_commits,
+        markdown,
+    })
+}
+
+fn resolve_commit<'r>(repo: &'r Repository, rev: &str) -> Result<Commit<'r>> {
+    repo.revparse_single(rev)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| DevBaseError::Config {
+            message: format!("Unknown revision '{rev}'"),
+        })
+}
+
+fn render_markdown(
+    version: &str,
+    date: &str,
+    commits: &[ConventionalCommit],
+    other_commits: &[CommitLogEntry],
+) -> String {
+    let item = |commit: &ConventionalCommit, text: &str| match &commit.parsed.scope {
+        Some(scope) => format!("- **{scope}:** {text} ({})\n", commit.entry.short_oid),
+        None => format!("- {text} ({})\n", commit.entry.short_oid),
+    };
+
+    let mut out = format!("## {version} ({date})\n");
+    let mut section = |title: &str, lines: Vec<String>| {
+        if !lines.is_empty() {
+            out.push_str("\n### ");
+            out.push_str(title);
+            out.push_str("\n\n");
+            out.extend(lines);
+        }
+    };
+
+    section(
+        "⚠ BREAKING CHANGES",
+        commits
+            .iter()
+            .filter_map(|c| c.parsed.breaking_note().map(|note| item(c, note)))
+            .collect(),
+    );
+    for (commit_type, title) in SECTIONS {
+        section(
+            title,
+            commits
+                .iter()
+                .filter(|c| c.parsed.commit_type == *commit_type)
+                .map(|c| item(c, &c.parsed.description))
+                .collect(),
+        );
+    }
+    section(
+        "Other Changes",
+        commits
+            .iter()
+            .filter(|c| !SECTIONS.iter().any(|(t, _)| c.parsed.commit_type == *t))
+            .map(|c| {
+                item(
+                    c,
+                    &format!("{}: {}", c.parsed.commit_type, c.parsed.description),
+                )
+            })
+            .chain(
+                other_commits
+                    .iter()
+                    .map(|e| format!("- {} ({})\n", e.message, e.short_oid)),
+            )
+            .collect(),
+    );
+    out
+}
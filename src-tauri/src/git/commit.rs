@@ -0,0 +1,161 @@
+//! Creating commits from the index.
+//!
+//! libgit2 neither signs commits nor runs hooks. Signing is refused with a
+//! clear error when `commit.gpgsign` is set, and the `pre-commit` and
+//! `commit-msg` hooks are run here unless explicitly skipped.
+
+use git2::{Repository, RepositoryState};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::history::CommitLogEntry;
+use crate::error::{DevBaseError, Result};
+
+/// Options for a commit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommitOptions {
+    /// Do not run `pre-commit` and `commit-msg` (like `--no-verify`)
+    pub skip_hooks: bool,
+    /// Commit even if the index matches HEAD
+    pub allow_empty: bool,
+}
+
+/// Commit the index to the current branch.
+pub fn create_commit(
+    path: &Path,
+    message: &str,
+    options: &CommitOptions,
+) -> Result<CommitLogEntry> {
+    let repo = Repository::open(path)?;
+    let config = repo.config()?;
+
+    if config.get_bool("commit.gpgsign").unwrap_or(false) {
+        return Err(DevBaseError::Config {
+            message: "commit.gpgsign is enabled, but signed commits are not supported; \
+                      commit from a terminal or disable signing for this repository"
+                .to_string(),
+        });
+    }
+    if repo.state() != RepositoryState::Clean {
+        return Err(DevBaseError::Config {
+            message: "An operation (merge, rebase, ...) is in progress; finish or abort it first"
+                .to_string(),
+        });
+    }
+
+    let signature = repo.signature().map_err(|_| DevBaseError::Config {
+        message: "No identity configured; set user.name and user.email".to_string(),
+    })?;
+    let mut message = clean_message(message)?;
+
+    if !options.skip_hooks {
+        run_hook(&repo, "pre-commit", &[])?;
+    }
+
+    // The pre-commit hook may have staged more changes
+    let mut index = repo.index()?;
+    index.read(true)?;
+    if index.has_conflicts() {
+        return Err(DevBaseError::Config {
+            message: "The index has unresolved conflicts".to_string(),
+        });
+    }
+    let tree = repo.find_tree(index.write_tree()?)?;
+
+    let parent = match repo.head() {
+        Ok(head) => Some(head.peel_to_commit()?),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(e.into()),
+    };
+    let unchanged = match &parent {
+        Some(parent) => parent.tree_id() == tree.id(),
+        None => tree.is_empty(),
+    };
+    if unchanged && !options.allow_empty {
+        return Err(DevBaseError::Config {
+            message: "Nothing to commit".to_string(),
+        });
+    }
+
+    if !options.skip_hooks {
+        let message_file = repo.path().join("COMMIT_EDITMSG");
+        fs::write(&message_file, &message)?;
+        if run_hook(&repo, "commit-msg", &[&message_file])? {
+            message = clean_message(&fs::read_to_string(&message_file)?)?;
+        }
+    }
+
+    let oid = repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        &message,
+        &tree,
+        &parent.iter().collect::<Vec<_>>(),
+    )?;
+    let commit = repo.find_commit(oid)?;
+    Ok(CommitLogEntry::from_commit(&commit))
+}
+
+/// Strip comments and surrounding whitespace like `git commit` does.
+fn clean_message(message: &str) -> Result<String> {
+    let cleaned = git2::message_prettify(message, Some(b'#'))?;
+    if cleaned.trim().is_empty() {
+        return Err(DevBaseError::Config {
+            message: "Commit message is empty".to_string(),
+        });
+    }
+    Ok(cleaned)
+}
+
+fn hooks_dir(repo: &Repository) -> PathBuf {
+    let configured = repo
+        .config()
+        .ok()
+        .and_then(|config| config.get_path("core.hooksPath").ok());
+    match configured {
+        Some(dir) if dir.is_absolute() => dir,
+        Some(dir) => repo.workdir().unwrap_or_else(|| repo.path()).join(dir),
+        None => repo.path().join("hooks"),
+    }
+}
+
+/// Run a hook if it is installed. Returns whether it ran.
+fn run_hook(repo: &Repository, name: &str, args: &[&Path]) -> Result<bool> {
+    let hook = hooks_dir(repo).join(name);
+    if !is_executable(&hook) {
+        return Ok(false);
+    }
+
+    let output = Command::new(&hook)
+        .args(args)
+        .current_dir(repo.workdir().unwrap_or_else(|| repo.path()))
+        .env("GIT_INDEX_FILE", repo.path().join("index"))
+        .output()?;
+    if !output.status.success() {
+        let mut text = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if text.is_empty() {
+            text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        }
+        return Err(DevBaseError::Hook {
+            hook: name.to_string(),
+            output: text,
+        });
+    }
+    Ok(true)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
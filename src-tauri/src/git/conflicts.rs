@@ -0,0 +1,375 @@
+//! Conflict inspection and resolution.
+//!
+//! Lists conflicted index entries with the ancestor, ours and theirs blobs,
+//! resolves them, and continues or aborts the merge, cherry-pick or rebase
+//! that produced them. During a rebase "ours" is the branch being rebased
+//! onto and "theirs" the commit being replayed, as in git.
+
+use git2::{ErrorCode, IndexEntry, Oid, Repository, RepositoryState, ResetType, Signature};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::status::RepoState;
+use crate::error::{DevBaseError, Result};
+
+/// One side of a conflict.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictSide {
+    pub oid: String,
+    pub mode: u32,
+    pub is_binary: bool,
+    /// Text content (`None` for binary blobs)
+    pub content: Option<String>,
+}
+
+/// A conflicted path. A missing side was deleted on that side.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictEntry {
+    pub path: String,
+    pub ancestor: Option<ConflictSide>,
+    pub ours: Option<ConflictSide>,
+    pub theirs: Option<ConflictSide>,
+}
+
+/// The operation in progress and its conflicts.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictReport {
+    pub state: RepoState,
+    pub conflicts: Vec<ConflictEntry>,
+}
+
+/// How to resolve a conflicted path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConflictResolution {
+    Ours,
+    Theirs,
+    Content { content: String },
+}
+
+/// Result of continuing an operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum ContinueOutcome {
+    Completed {
+        head_oid: String,
+    },
+    /// A later rebase step conflicted
+    Conflicts {
+        files: Vec<String>,
+    },
+}
+
+/// List conflicted paths with the contents of each side.
+pub fn list_conflicts(path: &Path) -> Result<ConflictReport> {
+    let repo = Repository::open(path)?;
+    let index = repo.index()?;
+
+    let side = |entry: Option<IndexEntry>| -> Result<Option<ConflictSide>> {
+        let Some(entry) = entry else {
+            return Ok(None);
+        };
+        let blob = repo.find_blob(entry.id)?;
+        let is_binary = blob.is_binary();
+        Ok(Some(ConflictSide {
+            oid: entry.id.to_string(),
+            mode: entry.mode,
+            is_binary,
+            content: (!is_binary).then(|| String::from_utf8_lossy(blob.content()).into_owned()),
+        }))
+    };
+
+    let mut conflicts = Vec::new();
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        let path = [&conflict.our, &conflict.their, &conflict.ancestor]
+            .into_iter()
+            .flatten()
+            .next()
+            .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+            .unwrap_or_default();
+        conflicts.push(ConflictEntry {
+            path,
+            ancestor: side(conflict.ancestor)?,
+            ours: side(conflict.our)?,
+            theirs: side(conflict.their)?,
+        });
+    }
+    conflicts.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(ConflictReport {
+        state: repo.state().into(),
+        conflicts,
+    })
+}
+
+/// Mark `file` resolved with the chosen content. Choosing a side that
+/// deleted the file deletes it.
+pub fn resolve_conflict(path: &Path, file: &str, resolution: &ConflictResolution) -> Result<()> {
+    let repo = Repository::open(path)?;
+    let workdir = repo.workdir().ok_or_else(|| DevBaseError::Config {
+        message: "Repository has no working tree".to_string(),
+    })?;
+    let mut index = repo.index()?;
+
+    let conflict = index
+        .conflicts()?
+        .filter_map(std::result::Result::ok)
+        .find(|c| {
+            [&c.our, &c.their, &c.ancestor]
+                .into_iter()
+                .flatten()
+                .any(|entry| entry.path == file.as_bytes())
+        })
+        .ok_or_else(|| DevBaseError::Config {
+            message: format!("{file} is not conflicted"),
+        })?;
+
+    let content = match resolution {
+        ConflictResolution::Ours => side_content(&repo, conflict.our.as_ref())?,
+        ConflictResolution::Theirs => side_content(&repo, conflict.their.as_ref())?,
+        ConflictResolution::Content { content } => Some(content.clone().into_bytes()),
+    };
+
+    let full = workdir.join(file);
+    match content {
+        Some(content) => {
+            if let Some(parent) = full.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&full, content)?;
+            index.add_path(Path::new(file))?;
+        }
+        None => {
+            if full.exists() {
+                fs::remove_file(&full)?;
+            }
+            index.remove_path(Path::new(file))?;
+        }
+    }
+    index.write()?;
+    Ok(())
+}
+
+fn side_content(repo: &Repository, entry: Option<&IndexEntry>) -> Result<Option<Vec<u8>>> {
+    match entry {
+        Some(entry) => Ok(Some(repo.find_blob(entry.id)?.content().to_vec())),
+        None => Ok(None),
+    }
+}
+
+/// Commit the resolved merge or cherry-pick, or continue the rebase.
+/// `message` overrides the prepared commit message.
+pub fn continue_operation(path: &Path, message: Option<&str>) -> Result<ContinueOutcome> {
+    let mut repo = Repository::open(path)?;
+    let unresolved = conflicted_paths(&repo)?;
+    if !unresolved.is_empty() {
+        return Err(DevBaseError::Config {
+            message: format!("Unresolved conflicts: {}", unresolved.join(", ")),
+        });
+    }
+    let signature = repo.signature().map_err(|_| DevBaseError::Config {
+        message: "No identity configured; set user.name and user.email".to_string(),
+    })?;
+
+    match repo.state() {
+        RepositoryState::Merge => {
+            let mut parents = vec![repo.head()?.peel_to_commit()?.id()];
+            repo.mergehead_foreach(|oid| {
+                parents.push(*oid);
+                true
+            })?;
+            let message = prepared_message(&repo, message, "Merge");
+            commit_index(&repo, &signature, &signature, &message, &parents)?;
+        }
+        RepositoryState::CherryPick => {
+            let picked = repo.find_reference("CHERRY_PICK_HEAD")?.peel_to_commit()?;
+            let fallback = picked.message().unwrap_or_default().to_string();
+            let message = prepared_message(&repo, message, &fallback);
+            let parent = repo.head()?.peel_to_commit()?.id();
+            commit_index(&repo, &picked.author(), &signature, &message, &[parent])?;
+        }
+        RepositoryState::Rebase
+        | RepositoryState::RebaseMerge
+        | RepositoryState::RebaseInteractive => {
+            return continue_rebase(&repo, &signature, message);
+        }
+        RepositoryState::Clean => {
+            return Err(DevBaseError::Config {
+                message: "No merge, cherry-pick or rebase in progress".to_string(),
+            });
+        }
+        other => {
+            return Err(DevBaseError::Config {
+                message: format!(
+                    "Cannot continue {:?}; finish it with git",
+                    RepoState::from(other)
+                ),
+            });
+        }
+    }
+
+    repo.cleanup_state()?;
+    Ok(ContinueOutcome::Completed {
+        head_oid: repo.refname_to_id("HEAD")?.to_string(),
+    })
+}
+
+fn conflicted_paths(repo: &Repository) -> Result<Vec<String>> {
+    let mut paths: Vec<String> = repo
+        .index()?
+        .conflicts()?
+        .filter_map(std::result::Result::ok)
+        .filter_map(|c| c.our.or(c.their).or(c.ancestor))
+        .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// The given message, else `MERGE_MSG` without comments, else `fallback`.
+fn prepared_message(repo: &Repository, message: Option<&str>, fallback: &str) -> String {
+    let prepared = message
+        .map(String::from)
+        .or_else(|| repo.message().ok())
+        .and_then(|m| git2::message_prettify(m, Some(b'#')).ok())
+        .filter(|m| !m.trim().is_empty());
+    prepared.unwrap_or_else(|| fallback.to_string())
+}
+
+fn commit_index(
+    repo: &Repository,
+    author: &Signature,
+    committer: &Signature,
+    message: &str,
+    parents: &[Oid],
+) -> Result<Oid> {
+    let tree = repo.find_tree(repo.index()?.write_tree()?)?;
+    let parents = parents
+        .iter()
+        .map(|oid| repo.find_commit(*oid))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(repo.commit(
+        Some("HEAD"),
+        author,
+        committer,
+        message,
+        &tree,
+        &parents.iter().collect::<Vec<_>>(),
+    )?)
+}
+
+fn open_rebase(repo: &Repository) -> Result<git2::Rebase<'_>> {
+    repo.open_rebase(None).map_err(|e| DevBaseError::Config {
+        message: format!(
+            "This rebase cannot be resumed here ({}); use git rebase --continue or --abort",
+            e.message()
+        ),
+    })
+}
+
+fn continue_rebase(
+    repo: &Repository,
+    signature: &Signature,
+    message: Option<&str>,
+) -> Result<ContinueOutcome> {
+    let mut rebase = open_rebase(repo)?;
+
+    // Commit the step that stopped on conflicts, then replay the rest
+    if rebase.operation_current().is_some() {
+        commit_rebase_step(&mut rebase, signature, message)?;
+    }
+    while let Some(operation) = rebase.next() {
+        operation?;
+        let conflicts = conflicted_paths(repo)?;
+        if !conflicts.is_empty() {
+            return Ok(ContinueOutcome::Conflicts { files: conflicts });
+        }
+        commit_rebase_step(&mut rebase, signature, None)?;
+    }
+    rebase.finish(Some(signature))?;
+
+    Ok(ContinueOutcome::Completed {
+        head_oid: repo.refname_to_id("HEAD")?.to_string(),
+    })
+}
+
+fn commit_rebase_step(
+    rebase: &mut git2::Rebase<'_>,
+    signature: &Signature,
+    message: Option<&str>,
+) -> Result<()> {
+    match rebase.commit(None, signature, message) {
+        // The change is already upstream; git drops such commits too
+        Err(e) if e.code() == ErrorCode::Applied => Ok(()),
+        result => result.map(|_| ()).map_err(Into::into),
+    }
+}
+
+/// Abort the merge, cherry-pick or rebase in progress, restoring the state
+/// before it started. Uncommitted changes to tracked files are discarded.
+pub fn abort_operation(path: &Path) -> Result<()> {
+    let repo = Repository::open(path)?;
+    match repo.state() {
+        RepositoryState::Merge
+        | RepositoryState::CherryPick
+        | RepositoryState::CherryPickSequence
+        | RepositoryState::Revert
+        | RepositoryState::RevertSequence => {
+            let head = repo.head()?.peel_to_commit()?;
+            repo.reset(head.as_object(), ResetType::Hard, None)?;
+            repo.cleanup_state()?;
+            Ok(())
+        }
+        RepositoryState::Rebase
+        | RepositoryState::RebaseMerge
+        | RepositoryState::RebaseInteractive => match repo.open_rebase(None) {
+            Ok(mut rebase) => Ok(rebase.abort()?),
+            Err(_) => abort_cli_rebase(&repo),
+        },
+        RepositoryState::Clean => Err(DevBaseError::Config {
+            message: "No merge, cherry-pick or rebase in progress".to_string(),
+        }),
+        other => Err(DevBaseError::Config {
+            message: format!(
+                "Cannot abort {:?}; finish it with git",
+                RepoState::from(other)
+            ),
+        }),
+    }
+}
+
+/// Abort a rebase whose state libgit2 cannot open, such as one started by
+/// `git rebase`, the way `git rebase --abort` does.
+fn abort_cli_rebase(repo: &Repository) -> Result<()> {
+    let state_dir = ["rebase-merge", "rebase-apply"]
+        .iter()
+        .map(|dir| repo.path().join(dir))
+        .find(|dir| dir.is_dir())
+        .ok_or_else(|| DevBaseError::Config {
+            message: "Rebase state is missing; use git rebase --abort".to_string(),
+        })?;
+    let read = |name: &str| {
+        fs::read_to_string(state_dir.join(name))
+            .map(|content| content.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let orig_head = Oid::from_str(&read("orig-head")).map_err(|_| DevBaseError::Config {
+        message: "Rebase has no original HEAD; use git rebase --abort".to_string(),
+    })?;
+    let commit = repo.find_commit(orig_head)?;
+
+    // head-name is "detached HEAD" when the rebase did not start on a branch
+    let head_name = read("head-name");
+    if head_name.starts_with("refs/") {
+        repo.reference(&head_name, orig_head, true, "rebase: aborting")?;
+        repo.set_head(&head_name)?;
+    } else {
+        repo.set_head_detached(orig_head)?;
+    }
+    fs::remove_dir_all(&state_dir)?;
+    repo.reset(commit.as_object(), ResetType::Hard, None)?;
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//! Credential lookup for network operations.
+//!
+//! libgit2 calls the credential callback again after every rejected attempt,
+//! so the provider walks through its sources once and then gives up instead
+//! of looping forever.
+
+use git2::{Config, Cred, CredentialType, Repository};
+use std::path::PathBuf;
+
+/// Private key files tried in `~/.ssh`, in order.
+const SSH_KEY_FILES: &[&str] = &["id_ed25519", "id_ecdsa", "id_rsa"];
+
+/// Supplies credentials from ssh-agent, key files and git credential helpers.
+pub struct CredentialProvider {
+    config: Option<Config>,
+    key_files: Vec<PathBuf>,
+    tried_agent: bool,
+    next_key: usize,
+    tried_helper: bool,
+    tried_default: bool,
+}
+
+impl CredentialProvider {
+    pub fn new(repo: &Repository) -> Self {
+        let key_files = dirs::home_dir()
+            .map(|home| {
+                SSH_KEY_FILES
+                    .iter()
+                    .map(|name| home.join(".ssh").join(name))
+                    .filter(|path| path.is_file())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            config: repo.config().ok(),
+            key_files,
+            tried_agent: false,
+            next_key: 0,
+            tried_helper: false,
+            tried_default: false,
+        }
+    }
+
+    /// Credential callback body for `RemoteCallbacks::credentials`.
+    pub fn credentials(
+        &mut self,
+        url: &str,
+        username_from_url: Option<&str>,
+        allowed: CredentialType,
+    ) -> std::result::Result<Cred, git2::Error> {
+        let username = username_from_url.unwrap_or("git");
+
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(username);
+        }
+
+        if allowed.contains(CredentialType::SSH_KEY) {
+            if !self.tried_agent {
+                self.tried_agent = true;
+                return Cred::ssh_key_from_agent(username);
+            }
+            if let Some(key) = self.key_files.get(self.next_key) {
+                self.next_key += 1;
+                let public = key.with_extension("pub");
+                let public = public.is_file().then_some(public);
+                return Cred::ssh_key(username, public.as_deref(), key, None);
+            }
+        }
+
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) && !self.tried_helper {
+            self.tried_helper = true;
+            if let Some(config) = &self.config {
+                if let Ok(cred) = Cred::credential_helper(config, url, username_from_url) {
+                    return Ok(cred);
+                }
+            }
+        }
+
+        if allowed.contains(CredentialType::DEFAULT) && !self.tried_default {
+            self.tried_default = true;
+            return Cred::default();
+        }
+
+        Err(git2::Error::from_str(&format!(
+            "No usable credentials for {url} (tried ssh-agent, ~/.ssh keys and credential helpers)"
+        )))
+    }
+}
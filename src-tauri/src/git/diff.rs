@@ -0,0 +1,341 @@
+//! Binary and image diff metadata.
+//!
+//! Compares one file between two revisions (or a revision and the working
+//! tree) and describes binary content: size delta, MIME type, inline image
+//! payloads for swipe/onion-skin views, and Git LFS pointers.
+
+use base64::Engine;
+use git2::{ErrorCode, ObjectType, Oid, Repository};
+use serde::Serialize;
+use std::path::Path;
+
+use super::staging::validate_repo_path;
+use crate::error::{DevBaseError, Result};
+
+/// Number of leading bytes inspected for NUL bytes (same heuristic as git).
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// Images larger than this are described but not inlined.
+pub const MAX_INLINE_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
+/// First line of every Git LFS pointer file.
+const LFS_POINTER_HEADER: &str = "version https://git-lfs.github.com/spec/v1";
+
+/// LFS pointers are tiny; anything larger cannot be one.
+const LFS_POINTER_MAX_LEN: usize = 1024;
+
+/// A Git LFS pointer stored in the repository instead of the real content.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LfsPointer {
+    /// Object id of the real content (e.g. `sha256:...`)
+    pub oid: String,
+    /// Size of the real content in bytes
+    pub size: u64,
+}
+
+/// Image payload for visual comparison.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageData {
+    /// Width in pixels, if it could be determined
+    pub width: Option<u32>,
+    /// Height in pixels, if it could be determined
+    pub height: Option<u32>,
+    /// Base64-encoded file contents (`None` above `MAX_INLINE_IMAGE_BYTES`)
+    pub data_base64: Option<String>,
+}
+
+/// One side of a binary comparison.
+#[derive(Debug, Clone, Serialize)]
+pub struct BlobInfo {
+    /// Blob OID (`None` for working tree content)
+    pub oid: Option<String>,
+    /// Stored size in bytes
+    pub size: u64,
+    /// Content looks binary
+    pub is_binary: bool,
+    /// Detected MIME type
+    pub mime_type: String,
+    /// Set when the stored content is a Git LFS pointer
+    pub lfs_pointer: Option<LfsPointer>,
+    /// Set for supported image formats
+    pub image: Option<ImageData>,
+}
+
+impl BlobInfo {
+    /// Size of the real content, following LFS pointers.
+    pub fn content_size(&self) -> u64 {
+        self.lfs_pointer.as_ref().map_or(self.size, |p| p.size)
+    }
+}
+
+/// Binary diff metadata for a single file.
+#[derive(Debug, Clone, Serialize)]
+pub struct BinaryDiff {
+    /// Path relative to the repository root
+    pub file_path: String,
+    /// File at the old revision (`None` if added)
+    pub old: Option<BlobInfo>,
+    /// File at the new revision or working tree (`None` if deleted)
+    pub new: Option<BlobInfo>,
+    /// Content size change in bytes (new - old)
+    pub size_delta: i64,
+    /// Either side is binary
+    pub is_binary: bool,
+    /// Either side is a supported image
+    pub is_image: bool,
+    /// Either side is a Git LFS pointer
+    pub is_lfs: bool,
+}
+
+/// Describe a file's binary/image changes between two revisions.
+///
+/// `new_rev` of `None` compares against the working tree.
+pub fn get_binary_diff(
+    path: &Path,
+    file_path: &str,
+    old_rev: &str,
+    new_rev: Option<&str>,
+) -> Result<BinaryDiff> {
+    validate_repo_path(file_path)?;
+    let repo = Repository::open(path)?;
+
+    let old = read_blob_at(&repo, old_rev, file_path)?
+        .map(|(oid, data)| describe_blob(file_path, Some(oid), &data));
+
+    let new = match new_rev {
+        Some(rev) => read_blob_at(&repo, rev, file_path)?
+            .map(|(oid, data)| describe_blob(file_path, Some(oid), &data)),
+        None => {
+            read_workdir_file(&repo, file_path)?.map(|data| describe_blob(file_path, None, &data))
+        }
+    };
+
+    if old.is_none() && new.is_none() {
+        return Err(DevBaseError::Config {
+            message: format!("File not found in either revision: {file_path}"),
+        });
+    }
+
+    let sides = [old.as_ref(), new.as_ref()];
+    let old_size = old.as_ref().map_or(0, BlobInfo::content_size);
+    let new_size = new.as_ref().map_or(0, BlobInfo::content_size);
+
+    Ok(BinaryDiff {
+        file_path: file_path.to_string(),
+        size_delta: i64::try_from(new_size).unwrap_or(i64::MAX)
+            - i64::try_from(old_size).unwrap_or(i64::MAX),
+        is_binary: sides.iter().flatten().any(|s| s.is_binary),
+        is_image: sides.iter().flatten().any(|s| s.image.is_some()),
+        is_lfs: sides.iter().flatten().any(|s| s.lfs_pointer.is_some()),
+        old,
+        new,
+    })
+}
+
+/// Read a file's blob at a revision. Returns `None` if the path does not exist there.
+pub(crate) fn read_blob_at(
+    repo: &Repository,
+    rev: &str,
+    file_path: &str,
+) -> Result<Option<(Oid, Vec<u8>)>> {
+    let tree = repo.revparse_single(rev)?.peel_to_tree()?;
+
+    let entry = match tree.get_path(Path::new(file_path)) {
+        Ok(entry) => entry,
+        Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    if entry.kind() != Some(ObjectType::Blob) {
+        return Ok(None);
+    }
+
+    let blob = repo.find_blob(entry.id())?;
+    Ok(Some((blob.id(), blob.content().to_vec())))
+}
+
+/// Read a file from the working tree. Returns `None` if it does not exist.
+fn read_workdir_file(repo: &Repository, file_path: &str) -> Result<Option<Vec<u8>>> {
+    let workdir = repo.workdir().ok_or_else(|| DevBaseError::Config {
+        message: "Repository has no working tree".to_string(),
+    })?;
+
+    match std::fs::read(workdir.join(file_path)) {
+        Ok(data) => Ok(Some(data)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Build the metadata for one side of the comparison.
+fn describe_blob(file_path: &str, oid: Option<Oid>, data: &[u8]) -> BlobInfo {
+    let lfs_pointer = parse_lfs_pointer(data);
+    let mime_type = detect_mime_type(file_path, data);
+
+    let image = if lfs_pointer.is_none() && mime_type.starts_with("image/") {
+        Some(image_data(mime_type, data))
+    } else {
+        None
+    };
+
+    BlobInfo {
+        oid: oid.map(|o| o.to_string()),
+        size: data.len() as u64,
+        is_binary: lfs_pointer.is_none() && is_binary_content(data),
+        mime_type: mime_type.to_string(),
+        lfs_pointer,
+        image,
+    }
+}
+
+/// Check whether content looks binary (NUL byte near the start).
+pub(crate) fn is_binary_content(data: &[u8]) -> bool {
+    data.iter().take(BINARY_SNIFF_LEN).any(|&b| b == 0)
+}
+
+/// Check for a UTF-16 byte order mark (little or big endian).
+pub(crate) fn has_utf16_bom(data: &[u8]) -> bool {
+    data.starts_with(&[0xFF, 0xFE]) || data.starts_with(&[0xFE, 0xFF])
+}
+
+/// Detect a MIME type from magic bytes, falling back to the file extension.
+pub(crate) fn detect_mime_type(file_path: &str, data: &[u8]) -> &'static str {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return "image/png";
+    }
+    if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return "image/jpeg";
+    }
+    if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        return "image/gif";
+    }
+    if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        return "image/webp";
+    }
+    if data.starts_with(b"%PDF") {
+        return "application/pdf";
+    }
+    if data.starts_with(b"PK\x03\x04") {
+        return "application/zip";
+    }
+
+    let is_svg_ext = Path::new(file_path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"));
+    let head = String::from_utf8_lossy(&data[..data.len().min(1024)]);
+    let head = head.trim_start();
+    let is_svg_doc =
+        head.starts_with("<svg") || (head.starts_with("<?xml") && head.contains("<svg"));
+    if is_svg_ext || is_svg_doc {
+        return "image/svg+xml";
+    }
+
+    // UTF-16 text is full of NUL bytes
+    if is_binary_content(data) && !has_utf16_bom(data) {
+        "application/octet-stream"
+    } else {
+        "text/plain"
+    }
+}
+
+/// Parse a Git LFS pointer file.
+pub(crate) fn parse_lfs_pointer(data: &[u8]) -> Option<LfsPointer> {
+    if data.len() > LFS_POINTER_MAX_LEN {
+        return None;
+    }
+
+    let text = std::str::from_utf8(data).ok()?;
+    let mut lines = text.lines();
+    if lines.next()?.trim_end() != LFS_POINTER_HEADER {
+        return None;
+    }
+
+    let mut oid = None;
+    let mut size = None;
+    for line in lines {
+        if let Some(value) = line.strip_prefix("oid ") {
+            oid = Some(value.trim().to_string());
+        } else if let Some(value) = line.strip_prefix("size ") {
+            size = value.trim().parse().ok();
+        }
+    }
+
+    Some(LfsPointer {
+        oid: oid?,
+        size: size?,
+    })
+}
+
+/// Extract dimensions and (if small enough) the encoded payload of an image.
+fn image_data(mime_type: &str, data: &[u8]) -> ImageData {
+    let dimensions = if mime_type == "image/svg+xml" {
+        svg_dimensions(data)
+    } else {
+        imagesize::blob_size(data).ok().and_then(|size| {
+            Some((
+                u32::try_from(size.width).ok()?,
+                u32::try_from(size.height).ok()?,
+            ))
+        })
+    };
+
+    let data_base64 = (data.len() <= MAX_INLINE_IMAGE_BYTES)
+        .then(|| base64::engine::general_purpose::STANDARD.encode(data));
+
+    ImageData {
+        width: dimensions.map(|(w, _)| w),
+        height: dimensions.map(|(_, h)| h),
+        data_base64,
+    }
+}
+
+/// Read `width`/`height` (or the `viewBox`) from the root `<svg>` tag.
+fn svg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    let text = String::from_utf8_lossy(data);
+    let start = text.find("<svg")?;
+    let end = start + text[start..].find('>')?;
+    let tag = &text[start..end];
+
+    let width = svg_attr(tag, "width").and_then(parse_svg_length);
+    let height = svg_attr(tag, "height").and_then(parse_svg_length);
+    if let (Some(w), Some(h)) = (width, height) {
+        return Some((w, h));
+    }
+
+    let view_box: Vec<f64> = svg_attr(tag, "viewBox")?
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| s.parse().ok())
+        .collect();
+
+    match view_box.as_slice() {
+        [_, _, w, h] => Some((round_length(*w)?, round_length(*h)?)),
+        _ => None,
+    }
+}
+
+/// Find a quoted attribute value inside a tag.
+fn svg_attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let needle = format!("{name}=");
+    for (pos, _) in tag.match_indices(&needle) {
+        let preceded_by_space = tag[..pos].ends_with(char::is_whitespace);
+        let rest = &tag[pos + needle.len()..];
+        let quote = rest.chars().next()?;
+        if preceded_by_space && (quote == '"' || quote == '\'') {
+            let value = &rest[1..];
+            return value.find(quote).map(|end| &value[..end]);
+        }
+    }
+    None
+}
+
+/// Parse an absolute SVG length such as `64` or `64px`.
+fn parse_svg_length(value: &str) -> Option<u32> {
+    let number = value.trim().trim_end_matches("px");
+    round_length(number.parse().ok()?)
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn round_length(value: f64) -> Option<u32> {
+    (value.is_finite() && value > 0.0 && value < f64::from(u32::MAX)).then(|| value.round() as u32)
+}
@@ -0,0 +1,328 @@
+//! Fetching from remotes.
+//!
+//! Wraps `git2::Remote::fetch` with pruning, progress reporting, timeouts and
+//! credential lookup (see `credentials`).
+
+use git2::{FetchPrune, Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::credentials::CredentialProvider;
+use crate::error::Result;
+
+/// Default number of repositories fetched at the same time.
+pub const DEFAULT_FETCH_CONCURRENCY: usize = 4;
+
+/// Default limit for fetching one repository.
+pub const DEFAULT_FETCH_TIMEOUT_SECS: u64 = 120;
+
+/// Minimum time between two progress reports for the same remote.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Options for a fetch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FetchOptions {
+    /// Remove remote-tracking refs that no longer exist on the remote
+    pub prune: bool,
+    /// Give up on the repository after this many seconds
+    pub timeout_secs: Option<u64>,
+    /// Remotes to fetch (empty means all)
+    pub remotes: Vec<String>,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            prune: true,
+            timeout_secs: Some(DEFAULT_FETCH_TIMEOUT_SECS),
+            remotes: Vec::new(),
+        }
+    }
+}
+
+/// Transfer progress of one remote.
+#[derive(Debug, Clone, Serialize)]
+pub struct FetchProgress {
+    pub remote: String,
+    pub received_objects: usize,
+    pub indexed_objects: usize,
+    pub total_objects: usize,
+    pub received_bytes: usize,
+}
+
+/// A reference changed by a fetch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RefUpdate {
+    pub ref_name: String,
+    /// Previous target (`None` if the ref was created)
+    pub old_oid: Option<String>,
+    /// New target (`None` if the ref was pruned)
+    pub new_oid: Option<String>,
+}
+
+/// Outcome of fetching one remote.
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteFetchResult {
+    pub remote: String,
+    pub url: Option<String>,
+    pub updated_refs: Vec<RefUpdate>,
+    pub received_objects: usize,
+    pub received_bytes: usize,
+    pub error: Option<String>,
+}
+
+/// Outcome of fetching a repository.
+#[derive(Debug, Clone, Serialize)]
+pub struct FetchResult {
+    pub remotes: Vec<RemoteFetchResult>,
+    /// The timeout cut the fetch short
+    pub timed_out: bool,
+    pub duration_ms: u64,
+}
+
+impl FetchResult {
+    /// All remote errors joined, if any remote failed.
+    pub fn error(&self) -> Option<String> {
+        let errors: Vec<String> = self
+            .remotes
+            .iter()
+            .filter_map(|r| r.error.as_ref().map(|e| format!("{}: {e}", r.remote)))
+            .collect();
+        (!errors.is_empty()).then(|| errors.join("; "))
+    }
+}
+
+/// Fetch the remotes of a repository.
+///
+/// Errors of individual remotes are reported in the result; only failing to
+/// open the repository is an error.
+pub fn fetch_repo<F>(path: &Path, options: &FetchOptions, mut on_progress: F) -> Result<FetchResult>
+where
+    F: FnMut(&FetchProgress),
+{
+    let started = Instant::now();
+    let deadline = options
+        .timeout_secs
+        .map(|secs| started + Duration::from_secs(secs));
+
+    let repo = Repository::open(path)?;
+    let names: Vec<String> = if options.remotes.is_empty() {
+        repo.remotes()?.iter().flatten().map(String::from).collect()
+    } else {
+        options.remotes.clone()
+    };
+
+    let mut remotes = Vec::with_capacity(names.len());
+    let mut timed_out = false;
+
+    for name in names {
+        let mut result = RemoteFetchResult {
+            remote: name.clone(),
+            url: None,
+            updated_refs: Vec::new(),
+            received_objects: 0,
+            received_bytes: 0,
+            error: None,
+        };
+
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            timed_out = true;
+            result.error = Some("Timed out before fetching".to_string());
+            remotes.push(result);
+            continue;
+        }
+
+        let fetched = match deadline {
+            Some(deadline) => {
+                fetch_remote_until(path, &name, options.prune, deadline, &mut on_progress)
+            }
+            None => fetch_remote(&repo, &name, options.prune, None, &mut on_progress),
+        };
+        match fetched {
+            Ok((url, updated_refs, received_objects, received_bytes)) => {
+                result.url = url;
+                result.updated_refs = updated_refs;
+                result.received_objects = received_objects;
+                result.received_bytes = received_bytes;
+            }
+            Err(RemoteError::TimedOut) => {
+                timed_out = true;
+                result.error = Some(format!(
+                    "Timed out after {} seconds",
+                    options.timeout_secs.unwrap_or_default()
+                ));
+            }
+            Err(RemoteError::Git(e)) => result.error = Some(e.message().to_string()),
+        }
+
+        if let Some(error) = &result.error {
+            tracing::warn!(?path, remote = %name, %error, "Fetch failed");
+        }
+        remotes.push(result);
+    }
+
+    Ok(FetchResult {
+        remotes,
+        timed_out,
+        duration_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+    })
+}
+
+enum RemoteError {
+    TimedOut,
+    Git(git2::Error),
+}
+
+type RemoteFetchData = (Option<String>, Vec<RefUpdate>, usize, usize);
+
+enum FetchEvent {
+    Progress(FetchProgress),
+    Done(std::result::Result<RemoteFetchData, RemoteError>),
+}
+
+/// Fetch a remote on its own thread and wait until `deadline`.
+///
+/// The callbacks only run while data flows, so they cannot stop a fetch
+/// stuck connecting to or waiting on the server. On timeout the thread is
+/// left to finish on its own; its callbacks abort the transfer as soon as
+/// they run again.
+fn fetch_remote_until<F>(
+    path: &Path,
+    name: &str,
+    prune: bool,
+    deadline: Instant,
+    on_progress: &mut F,
+) -> std::result::Result<RemoteFetchData, RemoteError>
+where
+    F: FnMut(&FetchProgress),
+{
+    let (tx, rx) = mpsc::channel();
+    let path = path.to_path_buf();
+    let remote = name.to_string();
+
+    thread::spawn(move || {
+        let progress = tx.clone();
+        let fetched = Repository::open(&path)
+            .map_err(RemoteError::Git)
+            .and_then(|repo| {
+                fetch_remote(&repo, &remote, prune, Some(deadline), &mut |p| {
+                    let _ = progress.send(FetchEvent::Progress(p.clone()));
+                })
+            });
+        let _ = tx.send(FetchEvent::Done(fetched));
+    });
+
+    loop {
+        match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(FetchEvent::Progress(progress)) => on_progress(&progress),
+            Ok(FetchEvent::Done(fetched)) => return fetched,
+            Err(RecvTimeoutError::Timeout) => return Err(RemoteError::TimedOut),
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err(RemoteError::Git(git2::Error::from_str(
+                    "fetch stopped unexpectedly",
+                )))
+            }
+        }
+    }
+}
+
+fn fetch_remote<F>(
+    repo: &Repository,
+    name: &str,
+    prune: bool,
+    deadline: Option<Instant>,
+    on_progress: &mut F,
+) -> std::result::Result<RemoteFetchData, RemoteError>
+where
+    F: FnMut(&FetchProgress),
+{
+    let mut remote = repo.find_remote(name).map_err(RemoteError::Git)?;
+    let url = remote.url().map(String::from);
+
+    let expired = Cell::new(false);
+    let past_deadline = || {
+        let past = deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        if past {
+            expired.set(true);
+        }
+        past
+    };
+
+    let mut updated_refs = Vec::new();
+    let mut last_report: Option<Instant> = None;
+
+    let fetch_result = {
+        // Providers give up once exhausted, so each remote gets its own
+        let mut credentials = CredentialProvider::new(repo);
+        let mut callbacks = git2::RemoteCallbacks::new();
+        callbacks.credentials(|url, username, allowed| {
+            if past_deadline() {
+                return Err(git2::Error::from_str("fetch timed out"));
+            }
+            credentials.credentials(url, username, allowed)
+        });
+        callbacks.update_tips(|ref_name, old, new| {
+            updated_refs.push(RefUpdate {
+                ref_name: ref_name.to_string(),
+                old_oid: non_zero(old),
+                new_oid: non_zero(new),
+            });
+            true
+        });
+        callbacks.sideband_progress(|_| !past_deadline());
+        callbacks.transfer_progress(|stats| {
+            if past_deadline() {
+                return false;
+            }
+
+            let done = stats.received_objects() == stats.total_objects()
+                && stats.indexed_objects() == stats.total_objects();
+            let due = last_report.is_none_or(|last| last.elapsed() >= PROGRESS_INTERVAL);
+            if done || due {
+                last_report = Some(Instant::now());
+                on_progress(&FetchProgress {
+                    remote: name.to_string(),
+                    received_objects: stats.received_objects(),
+                    indexed_objects: stats.indexed_objects(),
+                    total_objects: stats.total_objects(),
+                    received_bytes: stats.received_bytes(),
+                });
+            }
+            true
+        });
+
+        let mut fetch_options = git2::FetchOptions::new();
+        fetch_options.remote_callbacks(callbacks).prune(if prune {
+            FetchPrune::On
+        } else {
+            FetchPrune::Off
+        });
+
+        remote.fetch::<&str>(&[], Some(&mut fetch_options), None)
+    };
+
+    if let Err(e) = fetch_result {
+        return Err(if expired.get() {
+            RemoteError::TimedOut
+        } else {
+            RemoteError::Git(e)
+        });
+    }
+
+    let stats = remote.stats();
+    Ok((
+        url,
+        updated_refs,
+        stats.received_objects(),
+        stats.received_bytes(),
+    ))
+}
+
+fn non_zero(oid: Oid) -> Option<String> {
+    (!oid.is_zero()).then(|| oid.to_string())
+}
@@ -1,5 +1,5 @@
 use crate::error::Result;
-use git2::{Repository, Sort};
+use git2::{Commit, Repository, Sort};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -15,6 +15,25 @@ pub struct CommitLogEntry {
     pub refs: Vec<String>, // Branches/Tags pointing here
 }
 
+impl CommitLogEntry {
+    /// Build a log entry from a commit (refs are left empty).
+    pub fn from_commit(commit: &Commit) -> Self {
+        let oid = commit.id().to_string();
+        let author = commit.author();
+
+        Self {
+            short_oid: oid[0..7].to_string(),
+            oid,
+            message: commit.summary().unwrap_or("").to_string(),
+            author_name: author.name().unwrap_or("Unknown").to_string(),
+            author_email: author.email().unwrap_or("").to_string(),
+            date: commit.time().seconds(),
+            parents: commit.parent_ids().map(|id| id.to_string()).collect(),
+            refs: Vec::new(),
+        }
+    }
+}
+
 pub fn get_repo_history(path: &Path, limit: usize) -> Result<Vec<CommitLogEntry>> {
     let repo = Repository::open(path)?;
     let mut revwalk = repo.revwalk()?;
@@ -42,30 +61,10 @@ pub fn get_repo_history(path: &Path, limit: usize) -> Result<Vec<CommitLogEntry>
         let oid = oid_result?;
         let commit = repo.find_commit(oid)?;
         
-        // Extract basic info
-        let message = commit.summary().unwrap_or("").to_string();
-        let author = commit.author();
-        let author_name = author.name().unwrap_or("Unknown").to_string();
-        let author_email = author.email().unwrap_or("").to_string();
-        let date = commit.time().seconds();
-        
-        let parents = commit.parent_ids()
-            .map(|id| id.to_string())
-            .collect();
-            
         // TODO: Efficiently finding refs for each commit is expensive (O(N*M))
         // For now, we will leave refs empty or implement a lookup map separately if needed for graph.
         
-        commits.push(CommitLogEntry {
-            oid: oid.to_string(),
-            short_oid: oid.to_string()[0..7].to_string(),
-            message,
-            author_name,
-            author_email,
-            date,
-            parents,
-            refs: Vec::new(),
-        });
+        commits.push(CommitLogEntry::from_commit(&commit));
         
         count += 1;
     }
@@ -3,10 +3,43 @@
 //! Provides git status checking and repository operations.
 
 mod status;
+pub mod blame;
+pub mod branch_ops;
+pub mod branches;
+pub mod changelog;
+pub mod commit;
+pub mod conflicts;
+pub mod credentials;
+pub mod diff;
+pub mod fetch;
 pub mod history;
+pub mod push;
+pub mod reflog;
+pub mod staging;
+pub mod stash;
+pub mod sync;
+pub mod tags;
+pub mod time_travel;
+pub mod worktrees;
 
 #[cfg(test)]
 mod tests;
 
 pub use status::*;
+pub use blame::*;
+pub use branch_ops::*;
+pub use branches::*;
+pub use changelog::*;
+pub use commit::*;
+pub use conflicts::*;
+pub use diff::*;
+pub use fetch::*;
 pub use history::*;
+pub use push::*;
+pub use reflog::*;
+pub use staging::*;
+pub use stash::*;
+pub use sync::*;
+pub use tags::*;
+pub use time_travel::*;
+pub use worktrees::*;
@@ -0,0 +1,435 @@
+//! Pushing branches to their upstream.
+//!
+//! Wraps `git2::Remote::push` with per-ref results, upstream setup and
+//! force-with-lease. A plain force push is deliberately not offered.
+//!
+//! The remote is listed before pushing so that up-to-date and
+//! non-fast-forward refs are reported without sending anything. Leases are
+//! checked against that listing and again in the push negotiation, right
+//! before the remote is updated.
+
+use git2::{Branch, BranchType, Direction, Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Instant;
+
+use super::credentials::CredentialProvider;
+use crate::error::{DevBaseError, Result};
+
+/// Options for a push.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PushOptions {
+    /// Local branches to push (empty means the current branch)
+    pub branches: Vec<String>,
+    /// Remote for branches without an upstream (defaults to `origin`, or the
+    /// only remote)
+    pub remote: Option<String>,
+    /// Configure the pushed branch as upstream when none is set (on by default)
+    pub set_upstream: bool,
+    /// Overwrite the remote branch, but only if it still points where our
+    /// remote-tracking branch says it does
+    pub force_with_lease: bool,
+}
+
+impl Default for PushOptions {
+    fn default() -> Self {
+        Self {
+            branches: Vec::new(),
+            remote: None,
+            set_upstream: true,
+            force_with_lease: false,
+        }
+    }
+}
+
+/// Result of pushing one ref.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RefPushStatus {
+    /// Fast-forwarded (or force-updated under a lease)
+    Pushed,
+    /// The branch did not exist on the remote
+    Created,
+    UpToDate,
+    /// The remote has commits the local branch does not
+    RejectedNonFastForward,
+    /// The remote moved since the last fetch, so the lease does not hold
+    RejectedStaleLease,
+    /// A server-side hook refused the update
+    HookDeclined,
+    /// Rejected by the remote for another reason
+    Rejected,
+    /// The push could not be attempted
+    Failed,
+}
+
+impl RefPushStatus {
+    pub fn is_success(self) -> bool {
+        matches!(self, Self::Pushed | Self::Created | Self::UpToDate)
+    }
+}
+
+/// Outcome of pushing one branch.
+#[derive(Debug, Clone, Serialize)]
+pub struct RefPushResult {
+    pub branch: String,
+    pub remote: String,
+    /// Ref updated on the remote (e.g. `refs/heads/main`)
+    pub remote_ref: String,
+    /// Remote target before the push (`None` if the branch was new there)
+    pub old_oid: Option<String>,
+    pub new_oid: String,
+    pub status: RefPushStatus,
+    /// Message from the remote or the reason for a rejection
+    pub message: Option<String>,
+    /// The upstream was configured by this push
+    pub upstream_set: bool,
+}
+
+/// Outcome of pushing a repository.
+#[derive(Debug, Clone, Serialize)]
+pub struct PushResult {
+    pub refs: Vec<RefPushResult>,
+    pub duration_ms: u64,
+}
+
+impl PushResult {
+    /// All failed refs joined, if any ref failed.
+    pub fn error(&self) -> Option<String> {
+        let errors: Vec<String> = self
+            .refs
+            .iter()
+            .filter(|r| !r.status.is_success())
+            .map(|r| {
+                let reason = r
+                    .message
+                    .clone()
+                    .unwrap_or_else(|| format!("{:?}", r.status));
+                format!("{}: {reason}", r.branch)
+            })
+            .collect();
+        (!errors.is_empty()).then(|| errors.join("; "))
+    }
+}
+
+/// A branch resolved to what it should update on which remote.
+struct PushTarget {
+    branch: String,
+    remote: String,
+    remote_ref: String,
+    local_oid: Oid,
+    has_upstream: bool,
+    /// Our remote-tracking value, used as the lease
+    tracking_oid: Option<Oid>,
+}
+
+/// Push branches of a repository.
+///
+/// Rejections and failures of individual refs are reported in the result;
+/// only failing to open the repository or resolve its branches is an error.
+pub fn push_repo(path: &Path, options: &PushOptions) -> Result<PushResult> {
+    let started = Instant::now();
+    let repo = Repository::open(path)?;
+
+    let branches = if options.branches.is_empty() {
+        let head = repo.head()?;
+        if !head.is_branch() {
+            return Err(DevBaseError::Config {
+                message: "HEAD is detached; choose a branch to push".to_string(),
+            });
+        }
+        vec![head.shorthand().unwrap_or_default().to_string()]
+    } else {
+        options.branches.clone()
+    };
+
+    let mut targets = Vec::with_capacity(branches.len());
+    for name in &branches {
+        targets.push(push_target(&repo, name, options.remote.as_deref())?);
+    }
+
+    let mut by_remote: HashMap<String, Vec<PushTarget>> = HashMap::new();
+    for target in targets {
+        by_remote
+            .entry(target.remote.clone())
+            .or_default()
+            .push(target);
+    }
+
+    let mut refs = Vec::new();
+    for (remote, targets) in by_remote {
+        refs.extend(push_remote(&repo, &remote, targets, options));
+    }
+
+    let order: HashMap<&String, usize> = branches.iter().enumerate().map(|(i, b)| (b, i)).collect();
+    refs.sort_by_key(|r| order.get(&r.branch).copied().unwrap_or(usize::MAX));
+
+    Ok(PushResult {
+        refs,
+        duration_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+    })
+}
+
+fn push_target(repo: &Repository, name: &str, remote: Option<&str>) -> Result<PushTarget> {
+    let branch = repo.find_branch(name, BranchType::Local)?;
+    let local_oid = branch.get().target().ok_or_else(|| DevBaseError::Scan {
+        message: format!("Branch {name} has no target"),
+    })?;
+    let config = repo.config()?;
+
+    let upstream_remote = config.get_string(&format!("branch.{name}.remote")).ok();
+    let upstream_merge = config.get_string(&format!("branch.{name}.merge")).ok();
+
+    let (remote, remote_ref, has_upstream) = match (upstream_remote, upstream_merge) {
+        (Some(remote), Some(merge)) if remote != "." => (remote, merge, true),
+        _ => {
+            let remote = match remote {
+                Some(remote) => remote.to_string(),
+                None => default_remote(repo)?,
+            };
+            (remote, format!("refs/heads/{name}"), false)
+        }
+    };
+
+    let tracking_oid = remote_ref
+        .strip_prefix("refs/heads/")
+        .and_then(|short| {
+            repo.find_branch(&format!("{remote}/{short}"), BranchType::Remote)
+                .ok()
+        })
+        .and_then(|b: Branch| b.get().target());
+
+    Ok(PushTarget {
+        branch: name.to_string(),
+        remote,
+        remote_ref,
+        local_oid,
+        has_upstream,
+        tracking_oid,
+    })
+}
+
+fn default_remote(repo: &Repository) -> Result<String> {
+    let remotes: Vec<String> = repo.remotes()?.iter().flatten().map(String::from).collect();
+    if remotes.iter().any(|r| r == "origin") {
+        return Ok("origin".to_string());
+    }
+    match remotes.as_slice() {
+        [only] => Ok(only.clone()),
+        [] => Err(DevBaseError::Config {
+            message: "Repository has no remotes".to_string(),
+        }),
+        _ => Err(DevBaseError::Config {
+            message: "Several remotes and none is origin; choose a remote".to_string(),
+        }),
+    }
+}
+
+fn push_remote(
+    repo: &Repository,
+    name: &str,
+    targets: Vec<PushTarget>,
+    options: &PushOptions,
+) -> Vec<RefPushResult> {
+    let fail_all = |targets: Vec<PushTarget>, message: String| {
+        targets
+            .into_iter()
+            .map(|t| result_for(&t, None, RefPushStatus::Failed, Some(message.clone())))
+            .collect()
+    };
+
+    let mut remote = match repo.find_remote(name) {
+        Ok(remote) => remote,
+        Err(e) => return fail_all(targets, e.message().to_string()),
+    };
+
+    // What the remote has right now. The push reuses this connection; the
+    // credential providers give up once exhausted, so each set of callbacks
+    // gets its own.
+    let mut probe_credentials = CredentialProvider::new(repo);
+    let mut callbacks = git2::RemoteCallbacks::new();
+    callbacks.credentials(|url, username, allowed| {
+        probe_credentials.credentials(url, username, allowed)
+    });
+    let mut connection = match remote.connect_auth(Direction::Push, Some(callbacks), None) {
+        Ok(connection) => connection,
+        Err(e) => return fail_all(targets, e.message().to_string()),
+    };
+    let advertised: HashMap<String, Oid> = match connection.list() {
+        Ok(heads) => heads
+            .iter()
+            .map(|h| (h.name().to_string(), h.oid()))
+            .collect(),
+        Err(e) => return fail_all(targets, e.message().to_string()),
+    };
+
+    let mut results = Vec::new();
+    let mut to_push = Vec::new();
+    for target in targets {
+        let remote_oid = advertised.get(&target.remote_ref).copied();
+        match remote_oid {
+            Some(oid) if oid == target.local_oid => {
+                results.push(result_for(
+                    &target,
+                    remote_oid,
+                    RefPushStatus::UpToDate,
+                    None,
+                ));
+            }
+            Some(oid) if !is_ancestor(repo, oid, target.local_oid) => {
+                if !options.force_with_lease {
+                    results.push(result_for(
+                        &target,
+                        remote_oid,
+                        RefPushStatus::RejectedNonFastForward,
+                        Some("remote has commits that are not in the local branch".to_string()),
+                    ));
+                } else if target.tracking_oid != Some(oid) {
+                    results.push(result_for(
+                        &target,
+                        remote_oid,
+                        RefPushStatus::RejectedStaleLease,
+                        Some("remote branch changed since the last fetch".to_string()),
+                    ));
+                } else {
+                    to_push.push((target, remote_oid, true));
+                }
+            }
+            _ => to_push.push((target, remote_oid, false)),
+        }
+    }
+
+    if to_push.is_empty() {
+        return results;
+    }
+
+    let refspecs: Vec<String> = to_push
+        .iter()
+        .map(|(t, _, forced)| {
+            let plus = if *forced { "+" } else { "" };
+            format!("{plus}refs/heads/{}:{}", t.branch, t.remote_ref)
+        })
+        .collect();
+    // Expected remote value per ref; a lease only holds if nothing moved
+    let leases: HashMap<String, Option<Oid>> = to_push
+        .iter()
+        .map(|(t, remote_oid, _)| (t.remote_ref.clone(), *remote_oid))
+        .collect();
+
+    let mut statuses: HashMap<String, Option<String>> = HashMap::new();
+    let mut lease_broken = false;
+    let pushed = {
+        let mut credentials = CredentialProvider::new(repo);
+        let mut callbacks = git2::RemoteCallbacks::new();
+        callbacks
+            .credentials(|url, username, allowed| credentials.credentials(url, username, allowed));
+        callbacks.push_negotiation(|updates| {
+            for update in updates {
+                let Some(dst) = update.dst_refname() else {
+                    continue;
+                };
+                let expected = leases.get(dst).copied().flatten().unwrap_or_else(Oid::zero);
+                if update.src() != expected {
+                    lease_broken = true;
+                    return Err(git2::Error::from_str(
+                        "remote branch changed while pushing; lease no longer holds",
+                    ));
+                }
+            }
+            Ok(())
+        });
+        callbacks.push_update_reference(|refname, status| {
+            statuses.insert(refname.to_string(), status.map(String::from));
+            Ok(())
+        });
+
+        let mut push_options = git2::PushOptions::new();
+        push_options.remote_callbacks(callbacks);
+        connection
+            .remote()
+            .push(&refspecs, Some(&mut push_options))
+    };
+
+    for (target, remote_oid, _) in to_push {
+        // The remote reports rejections per ref; an error from the push
+        // itself is a transport or auth failure, or our own lease check
+        let (status, message) = match (&pushed, statuses.get(&target.remote_ref)) {
+            (Err(e), _) if lease_broken => (
+                RefPushStatus::RejectedStaleLease,
+                Some(e.message().to_string()),
+            ),
+            (Err(e), _) => (RefPushStatus::Failed, Some(e.message().to_string())),
+            (Ok(()), Some(Some(message))) => (classify_rejection(message), Some(message.clone())),
+            (Ok(()), _) if remote_oid.is_none() => (RefPushStatus::Created, None),
+            (Ok(()), _) => (RefPushStatus::Pushed, None),
+        };
+
+        let mut result = result_for(&target, remote_oid, status, message);
+        if status.is_success() && options.set_upstream && !target.has_upstream {
+            match set_upstream(repo, &target) {
+                Ok(()) => result.upstream_set = true,
+                Err(e) => {
+                    tracing::warn!(branch = %target.branch, ?e, "Failed to set upstream");
+                }
+            }
+        }
+        results.push(result);
+    }
+
+    results
+}
+
+fn is_ancestor(repo: &Repository, ancestor: Oid, descendant: Oid) -> bool {
+    // A remote commit we don't have locally can't be in our history
+    repo.find_commit(ancestor).is_ok()
+        && repo
+            .graph_descendant_of(descendant, ancestor)
+            .unwrap_or(false)
+}
+
+fn set_upstream(repo: &Repository, target: &PushTarget) -> Result<()> {
+    let mut config = repo.config()?;
+    config.set_str(&format!("branch.{}.remote", target.branch), &target.remote)?;
+    config.set_str(
+        &format!("branch.{}.merge", target.branch),
+        &target.remote_ref,
+    )?;
+    Ok(())
+}
+
+/// Map a rejection message from the remote to a status.
+pub fn classify_rejection(message: &str) -> RefPushStatus {
+    let message = message.to_lowercase();
+    if message.contains("hook declined") || message.contains("hook-declined") {
+        RefPushStatus::HookDeclined
+    } else if message.contains("non-fast-forward")
+        || message.contains("fetch first")
+        || message.contains("not a fast-forward")
+    {
+        RefPushStatus::RejectedNonFastForward
+    } else if message.contains("lease") || message.contains("stale info") {
+        RefPushStatus::RejectedStaleLease
+    } else {
+        RefPushStatus::Rejected
+    }
+}
+
+fn result_for(
+    target: &PushTarget,
+    remote_oid: Option<Oid>,
+    status: RefPushStatus,
+    message: Option<String>,
+) -> RefPushResult {
+    RefPushResult {
+        branch: target.branch.clone(),
+        remote: target.remote.clone(),
+        remote_ref: target.remote_ref.clone(),
+        old_oid: remote_oid.map(|oid| oid.to_string()),
+        new_oid: target.local_oid.to_string(),
+        status,
+        message,
+        upstream_set: false,
+    }
+}
@@ -0,0 +1,178 @@
+//! Reflog listing and undo.
+//!
+//! Restoring moves a ref back to the state recorded by a reflog entry, like
+//! `git reset --hard HEAD@{n}`, after saving the current tip under
+//! `refs/devbase/undo/` so the restore itself can be undone.
+
+use git2::build::CheckoutBuilder;
+use git2::{Oid, Repository, StatusOptions};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::error::{DevBaseError, Result};
+
+/// Namespace of the refs saved before a reflog restore.
+pub const UNDO_REF_PREFIX: &str = "refs/devbase/undo/";
+
+/// One reflog entry, newest first (`index` 0 is the current state).
+#[derive(Debug, Clone, Serialize)]
+pub struct ReflogEntry {
+    pub index: usize,
+    pub old_oid: String,
+    pub new_oid: String,
+    /// Action message (`commit: ...`, `reset: moving to ...`)
+    pub message: String,
+    pub committer_name: String,
+    pub committer_email: String,
+    /// Unix timestamp
+    pub date: i64,
+}
+
+/// Result of restoring a ref to a reflog entry.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReflogRestore {
+    /// Ref that was moved (`refs/heads/main`, or `HEAD` when detached)
+    pub reference: String,
+    pub previous_oid: String,
+    pub restored_oid: String,
+    /// Ref holding the previous tip
+    pub backup_ref: String,
+}
+
+/// `HEAD`, a full ref name, or a branch name.
+fn full_ref_name(reference: Option<&str>) -> String {
+    match reference {
+        None | Some("HEAD") => "HEAD".to_string(),
+        Some(name) if name.starts_with("refs/") => name.to_string(),
+        Some(name) => format!("refs/heads/{name}"),
+    }
+}
+
+/// List the reflog of `reference` (HEAD when `None`), newest first.
+pub fn list_reflog(
+    path: &Path,
+    reference: Option<&str>,
+    limit: Option<usize>,
+) -> Result<Vec<ReflogEntry>> {
+    let repo = Repository::open(path)?;
+    let name = full_ref_name(reference);
+    if name != "HEAD" && repo.find_reference(&name).is_err() {
+        return Err(DevBaseError::Config {
+            message: format!("Reference '{name}' not found"),
+        });
+    }
+
+    let reflog = repo.reflog(&name)?;
+    Ok(reflog
+        .iter()
+        .take(limit.unwrap_or(usize::MAX))
+        .enumerate()
+        .map(|(index, entry)| {
+            let committer = entry.committer();
+            ReflogEntry {
+                index,
+                old_oid: entry.id_old().to_string(),
+                new_oid: entry.id_new().to_string(),
+                message: entry.message().unwrap_or_default().to_string(),
+                committer_name: committer.name().unwrap_or("Unknown").to_string(),
+                committer_email: committer.email().unwrap_or_default().to_string(),
+                date: committer.when().seconds(),
+            }
+        })
+        .collect())
+}
+
+/// Move `reference` (HEAD when `None`) back to the commit recorded by reflog
+/// entry `index`. Refuses when tracked files have uncommitted changes.
+pub fn restore_reflog_entry(
+    path: &Path,
+    reference: Option<&str>,
+    index: usize,
+) -> Result<ReflogRestore> {
+    let repo = Repository::open(path)?;
+    let name = full_ref_name(reference);
+
+    let dirty = tracked_changes(&repo)?;
+    if !dirty.is_empty() {
+        return Err(DevBaseError::LocalChanges { files: dirty });
+    }
+
+    let reflog = repo.reflog(&name)?;
+    let entry = reflog.get(index).ok_or_else(|| DevBaseError::Config {
+        message: format!("{name}@{{{index}}} does not exist"),
+    })?;
+    let target_oid = entry.id_new();
+    if target_oid.is_zero() {
+        return Err(DevBaseError::Config {
+            message: format!("{name}@{{{index}}} records a deleted ref"),
+        });
+    }
+    let target = repo
+        .find_commit(target_oid)
+        .map_err(|_| DevBaseError::Config {
+            message: format!("Commit {target_oid} of {name}@{{{index}}} no longer exists"),
+        })?;
+
+    // Moving HEAD moves the branch it points to
+    let head = repo.head().ok();
+    let head_branch = head
+        .as_ref()
+        .filter(|h| h.is_branch())
+        .and_then(|h| h.name().map(String::from));
+    let moved = match name.as_str() {
+        "HEAD" => head_branch.clone().unwrap_or_else(|| "HEAD".to_string()),
+        _ => name.clone(),
+    };
+    let checked_out = moved == "HEAD" || head_branch.as_deref() == Some(moved.as_str());
+
+    let previous_oid = repo.refname_to_id(&moved)?;
+    let backup_ref = save_backup(&repo, previous_oid, &moved)?;
+
+    let log_message = format!("devbase: restore to {name}@{{{index}}}");
+    if checked_out {
+        // Safe checkout refuses to overwrite untracked files in the way
+        repo.checkout_tree(target.as_object(), Some(CheckoutBuilder::new().safe()))?;
+    }
+    if moved == "HEAD" {
+        repo.set_head_detached(target_oid)?;
+    } else {
+        repo.find_reference(&moved)?
+            .set_target(target_oid, &log_message)?;
+    }
+
+    Ok(ReflogRestore {
+        reference: moved,
+        previous_oid: previous_oid.to_string(),
+        restored_oid: target_oid.to_string(),
+        backup_ref,
+    })
+}
+
+fn save_backup(repo: &Repository, oid: Oid, moved: &str) -> Result<String> {
+    let short = moved.strip_prefix("refs/heads/").unwrap_or(moved);
+    let mut stamp = chrono::Utc::now().timestamp_millis();
+    let ref_name = loop {
+        let name = format!("{UNDO_REF_PREFIX}{short}/{stamp}");
+        if repo.find_reference(&name).is_err() {
+            break name;
+        }
+        stamp += 1;
+    };
+    repo.reference(
+        &ref_name,
+        oid,
+        false,
+        "devbase: backup before reflog restore",
+    )?;
+    Ok(ref_name)
+}
+
+fn tracked_changes(repo: &Repository) -> Result<Vec<String>> {
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(false).include_ignored(false);
+    Ok(repo
+        .statuses(Some(&mut opts))?
+        .iter()
+        .filter_map(|entry| entry.path().map(String::from))
+        .collect())
+}
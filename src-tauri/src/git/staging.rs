@@ -0,0 +1,640 @@
+//! Staging, unstaging and discarding changes.
+//!
+//! Partial staging works on the diff the user is shown: hunks are addressed
+//! by index and single lines by their old/new line numbers. The selected
+//! changes are applied to the current index blob and the result is written
+//! back as a new blob, so the working tree is never touched.
+//!
+//! Discarded changes are first committed under `refs/devbase/discarded/` so
+//! they can be restored later.
+
+use git2::build::CheckoutBuilder;
+use git2::{
+    Diff, DiffOptions, FileMode, Index, IndexEntry, IndexTime, ObjectType, Oid, Patch, Repository,
+    Status, TreeWalkMode, TreeWalkResult,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path};
+
+use super::stash::stash_signature;
+use crate::error::{DevBaseError, Result};
+
+/// Namespace of the refs that keep discarded changes.
+pub const DISCARD_REF_PREFIX: &str = "refs/devbase/discarded/";
+
+/// One line of a diff hunk.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffLineInfo {
+    /// `+`, `-` or a space for context
+    pub origin: String,
+    pub old_lineno: Option<u32>,
+    pub new_lineno: Option<u32>,
+    pub content: String,
+    #[serde(skip)]
+    raw: Vec<u8>,
+}
+
+/// A hunk of a file diff.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffHunkInfo {
+    pub index: usize,
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub lines: Vec<DiffLineInfo>,
+}
+
+/// Diff of one file, as used for partial staging.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileDiff {
+    pub path: String,
+    pub is_binary: bool,
+    pub hunks: Vec<DiffHunkInfo>,
+}
+
+/// Identifies an added (`new_lineno`) or removed (`old_lineno`) diff line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LineRef {
+    pub old_lineno: Option<u32>,
+    pub new_lineno: Option<u32>,
+}
+
+/// Part of a file diff to stage or unstage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PatchSelection {
+    Hunks { hunks: Vec<usize> },
+    Lines { lines: Vec<LineRef> },
+}
+
+/// Discarded changes kept under `refs/devbase/discarded/`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscardBackup {
+    pub ref_name: String,
+    pub oid: String,
+    /// Files whose contents were saved
+    pub files: Vec<String>,
+    /// Unix timestamp
+    pub created_at: i64,
+}
+
+impl DiffLineInfo {
+    fn line_ref(&self) -> LineRef {
+        LineRef {
+            old_lineno: self.old_lineno,
+            new_lineno: self.new_lineno,
+        }
+    }
+
+    fn is_change(&self) -> bool {
+        self.origin == "+" || self.origin == "-"
+    }
+}
+
+/// Diff of a file: index against working tree, or HEAD against index when
+/// `staged` is set.
+pub fn get_file_diff(path: &Path, file: &str, staged: bool) -> Result<FileDiff> {
+    let repo = Repository::open(path)?;
+    let diff = if staged {
+        staged_diff(&repo, file, false)?
+    } else {
+        unstaged_diff(&repo, file)?
+    };
+    file_diff(&diff, file)
+}
+
+/// Stage whole files (deleted files are removed from the index).
+pub fn stage_files(path: &Path, files: &[String]) -> Result<()> {
+    let repo = Repository::open(path)?;
+    let workdir = workdir(&repo)?;
+    let mut index = repo.index()?;
+    for file in files {
+        if workdir.join(file).symlink_metadata().is_ok() {
+            index.add_path(Path::new(file))?;
+        } else {
+            index.remove_path(Path::new(file))?;
+        }
+    }
+    index.write()?;
+    Ok(())
+}
+
+/// Unstage whole files, resetting their index entries to HEAD.
+pub fn unstage_files(path: &Path, files: &[String]) -> Result<()> {
+    let repo = Repository::open(path)?;
+    match repo.head().ok().and_then(|head| head.peel_to_commit().ok()) {
+        Some(head) => repo.reset_default(Some(head.as_object()), files)?,
+        None => {
+            // Nothing committed yet: unstaging means forgetting the entry
+            let mut index = repo.index()?;
+            for file in files {
+                index.remove_path(Path::new(file))?;
+            }
+            index.write()?;
+        }
+    }
+    Ok(())
+}
+
+/// Stage part of the working tree changes of a file.
+pub fn stage_selection(path: &Path, file: &str, selection: &PatchSelection) -> Result<()> {
+    let repo = Repository::open(path)?;
+    let mut index = repo.index()?;
+    let diff = file_diff(&unstaged_diff(&repo, file)?, file)?;
+    let selected = selected_lines(&diff, selection)?;
+
+    let old = index_content(&repo, &index, file)?;
+    let content = apply_selection(&old, &diff.hunks, |line| {
+        selected.contains(&line.line_ref())
+    });
+
+    let exists = workdir(&repo)?.join(file).symlink_metadata().is_ok();
+    if !exists && content.is_empty() {
+        index.remove_path(Path::new(file))?;
+    } else {
+        write_index_entry(&repo, &mut index, file, &content)?;
+    }
+    index.write()?;
+    Ok(())
+}
+
+/// Unstage part of the staged changes of a file.
+pub fn unstage_selection(path: &Path, file: &str, selection: &PatchSelection) -> Result<()> {
+    let repo = Repository::open(path)?;
+    let mut index = repo.index()?;
+    let forward = file_diff(&staged_diff(&repo, file, false)?, file)?;
+    let selected = selected_lines(&forward, selection)?;
+
+    // Undo the selected lines by staging them from the reversed diff, where
+    // additions and removals (and their line numbers) swap sides
+    let reverse = file_diff(&staged_diff(&repo, file, true)?, file)?;
+    let old = index_content(&repo, &index, file)?;
+    let content = apply_selection(&old, &reverse.hunks, |line| {
+        selected.contains(&LineRef {
+            old_lineno: line.new_lineno,
+            new_lineno: line.old_lineno,
+        })
+    });
+
+    let in_head = head_tree(&repo)?.is_some_and(|tree| tree.get_path(Path::new(file)).is_ok());
+    let everything = forward
+        .hunks
+        .iter()
+        .flat_map(|h| &h.lines)
+        .filter(|l| l.is_change())
+        .all(|l| selected.contains(&l.line_ref()));
+    if !in_head && everything {
+        index.remove_path(Path::new(file))?;
+    } else {
+        write_index_entry(&repo, &mut index, file, &content)?;
+    }
+    index.write()?;
+    Ok(())
+}
+
+/// Discard working tree changes of files, saving them to a backup ref first.
+///
+/// Tracked files are reset to their index version and untracked files are
+/// deleted. Files deleted in the working tree have nothing to save and are
+/// simply restored.
+pub fn discard_changes(path: &Path, files: &[String]) -> Result<DiscardBackup> {
+    let repo = Repository::open(path)?;
+    let workdir = workdir(&repo)?;
+    let index = repo.index()?;
+    for file in files {
+        validate_repo_path(file)?;
+    }
+
+    let mut backup = Index::new()?;
+    let mut saved = Vec::new();
+    for file in files {
+        let full = workdir.join(file);
+        if !full.is_file() {
+            continue;
+        }
+        let content = fs::read(&full)?;
+        let mut entry = new_index_entry(file, file_mode(&full));
+        entry.id = repo.blob(&content)?;
+        entry.file_size = u32::try_from(content.len()).unwrap_or(u32::MAX);
+        backup.add(&entry)?;
+        saved.push(file.clone());
+    }
+
+    let tree = repo.find_tree(backup.write_tree_to(&repo)?)?;
+    let signature = stash_signature(&repo)?;
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let message = format!("Discarded changes\n\n{}\n", saved.join("\n"));
+    let oid = repo.commit(
+        None,
+        &signature,
+        &signature,
+        &message,
+        &tree,
+        &parent.iter().collect::<Vec<_>>(),
+    )?;
+
+    let created_at = chrono::Utc::now().timestamp_millis();
+    let mut stamp = created_at;
+    let ref_name = loop {
+        let name = format!("{DISCARD_REF_PREFIX}{stamp}");
+        if repo.find_reference(&name).is_err() {
+            break name;
+        }
+        stamp += 1;
+    };
+    repo.reference(&ref_name, oid, false, "devbase: discard backup")?;
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force();
+    let mut tracked = false;
+    for file in files {
+        if index.get_path(Path::new(file), 0).is_some() {
+            checkout.path(file);
+            tracked = true;
+        } else if repo
+            .status_file(Path::new(file))
+            .is_ok_and(|status| status.contains(Status::WT_NEW))
+        {
+            fs::remove_file(workdir.join(file))?;
+        }
+    }
+    if tracked {
+        repo.checkout_index(None, Some(&mut checkout))?;
+    }
+
+    Ok(DiscardBackup {
+        ref_name,
+        oid: oid.to_string(),
+        files: saved,
+        created_at: created_at / 1000,
+    })
+}
+
+/// Saved discards of a repository, newest first.
+pub fn list_discard_backups(path: &Path) -> Result<Vec<DiscardBackup>> {
+    let repo = Repository::open(path)?;
+    let mut backups = Vec::new();
+    for reference in repo.references_glob(&format!("{DISCARD_REF_PREFIX}*"))? {
+        let reference = reference?;
+        let commit = reference.peel_to_commit()?;
+        backups.push(DiscardBackup {
+            ref_name: reference.name().unwrap_or_default().to_string(),
+            oid: commit.id().to_string(),
+            files: tree_files(&repo, commit.tree_id())?
+                .into_iter()
+                .map(|(file, _)| file)
+                .collect(),
+            created_at: commit.time().seconds(),
+        });
+    }
+    backups.sort_by(|a, b| b.ref_name.cmp(&a.ref_name));
+    Ok(backups)
+}
+
+/// Write the files of a discard backup back into the working tree.
+///
+/// Refuses with `DevBaseError::LocalChanges` if any of the files was changed
+/// since the discard, so newer work is never overwritten.
+pub fn restore_discard_backup(path: &Path, ref_name: &str) -> Result<Vec<String>> {
+    if !ref_name.starts_with(DISCARD_REF_PREFIX) {
+        return Err(DevBaseError::Config {
+            message: format!("{ref_name} is not a discard backup"),
+        });
+    }
+    let repo = Repository::open(path)?;
+    let workdir = workdir(&repo)?;
+    let commit = repo.find_reference(ref_name)?.peel_to_commit()?;
+    let tree = commit.tree()?;
+
+    // The discard left each file at its index version or deleted it; anything
+    // else is newer work, unless it already matches the backup
+    let files = tree_files(&repo, tree.id())?;
+    let mut changed = Vec::new();
+    for (file, blob) in &files {
+        let target = workdir.join(file);
+        if target.symlink_metadata().is_err() {
+            continue;
+        }
+        let backup = repo.find_blob(*blob)?;
+        let same = fs::read(&target).is_ok_and(|content| content == backup.content());
+        let clean = repo
+            .status_file(Path::new(file))
+            .is_ok_and(|status| status.is_empty());
+        if !same && !clean {
+            changed.push(file.clone());
+        }
+    }
+    if !changed.is_empty() {
+        return Err(DevBaseError::LocalChanges { files: changed });
+    }
+
+    for (file, blob) in &files {
+        let target = workdir.join(file);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target, repo.find_blob(*blob)?.content())?;
+        set_file_mode(&target, tree.get_path(Path::new(file))?.filemode())?;
+    }
+    Ok(files.into_iter().map(|(file, _)| file).collect())
+}
+
+/// Refuse paths that could point outside the working tree.
+pub(crate) fn validate_repo_path(file: &str) -> Result<()> {
+    let path = Path::new(file);
+    let inside = !file.is_empty()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        && path.components().next() != Some(Component::Normal(".git".as_ref()));
+    if inside {
+        Ok(())
+    } else {
+        Err(DevBaseError::Config {
+            message: format!("Invalid path: {file}"),
+        })
+    }
+}
+
+fn workdir(repo: &Repository) -> Result<&Path> {
+    repo.workdir().ok_or_else(|| DevBaseError::Config {
+        message: "Repository has no working tree".to_string(),
+    })
+}
+
+fn head_tree(repo: &Repository) -> Result<Option<git2::Tree<'_>>> {
+    match repo.head() {
+        Ok(head) => Ok(Some(head.peel_to_tree()?)),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn diff_options(file: &str) -> DiffOptions {
+    let mut opts = DiffOptions::new();
+    opts.pathspec(file).disable_pathspec_match(true);
+    opts
+}
+
+fn unstaged_diff<'r>(repo: &'r Repository, file: &str) -> Result<Diff<'r>> {
+    let mut opts = diff_options(file);
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    Ok(repo.diff_index_to_workdir(None, Some(&mut opts))?)
+}
+
+fn staged_diff<'r>(repo: &'r Repository, file: &str, reverse: bool) -> Result<Diff<'r>> {
+    let mut opts = diff_options(file);
+    opts.reverse(reverse);
+    let tree = head_tree(repo)?;
+    Ok(repo.diff_tree_to_index(tree.as_ref(), None, Some(&mut opts))?)
+}
+
+fn file_diff(diff: &Diff, file: &str) -> Result<FileDiff> {
+    let Some(patch) = Patch::from_diff(diff, 0)? else {
+        return Err(DevBaseError::Config {
+            message: format!("No changes in {file}"),
+        });
+    };
+
+    let is_binary = patch.delta().flags().is_binary();
+    let mut hunks = Vec::with_capacity(patch.num_hunks());
+    for index in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(index)?;
+        let mut lines = Vec::with_capacity(line_count);
+        for line_index in 0..line_count {
+            let line = patch.line_in_hunk(index, line_index)?;
+            let origin = match line.origin() {
+                origin @ ('+' | '-' | ' ') => origin,
+                // End-of-file newline markers carry no content of their own
+                _ => continue,
+            };
+            lines.push(DiffLineInfo {
+                origin: origin.to_string(),
+                old_lineno: line.old_lineno(),
+                new_lineno: line.new_lineno(),
+                content: String::from_utf8_lossy(line.content()).to_string(),
+                raw: line.content().to_vec(),
+            });
+        }
+        hunks.push(DiffHunkInfo {
+            index,
+            header: String::from_utf8_lossy(hunk.header())
+                .trim_end()
+                .to_string(),
+            old_start: hunk.old_start(),
+            old_lines: hunk.old_lines(),
+            new_start: hunk.new_start(),
+            new_lines: hunk.new_lines(),
+            lines,
+        });
+    }
+
+    Ok(FileDiff {
+        path: file.to_string(),
+        is_binary,
+        hunks,
+    })
+}
+
+/// Changed lines picked by a selection, validated against the diff.
+fn selected_lines(diff: &FileDiff, selection: &PatchSelection) -> Result<HashSet<LineRef>> {
+    if diff.is_binary {
+        return Err(DevBaseError::Config {
+            message: format!("{} is binary; stage the whole file instead", diff.path),
+        });
+    }
+
+    let changes = diff
+        .hunks
+        .iter()
+        .flat_map(|h| &h.lines)
+        .filter(|l| l.is_change());
+    let selected: HashSet<LineRef> = match selection {
+        PatchSelection::Hunks { hunks } => {
+            if let Some(missing) = hunks.iter().find(|&&i| i >= diff.hunks.len()) {
+                return Err(DevBaseError::Config {
+                    message: format!("{} has no hunk {missing}", diff.path),
+                });
+            }
+            diff.hunks
+                .iter()
+                .filter(|h| hunks.contains(&h.index))
+                .flat_map(|h| &h.lines)
+                .filter(|l| l.is_change())
+                .map(DiffLineInfo::line_ref)
+                .collect()
+        }
+        PatchSelection::Lines { lines } => {
+            let known: HashSet<LineRef> = changes.map(DiffLineInfo::line_ref).collect();
+            if let Some(missing) = lines.iter().find(|l| !known.contains(l)) {
+                return Err(DevBaseError::Config {
+                    message: format!("{missing:?} is not a changed line of {}", diff.path),
+                });
+            }
+            lines.iter().copied().collect()
+        }
+    };
+
+    if selected.is_empty() {
+        return Err(DevBaseError::Config {
+            message: "Nothing selected".to_string(),
+        });
+    }
+    Ok(selected)
+}
+
+/// Apply the selected changes of `hunks` to `old`.
+fn apply_selection<F>(old: &[u8], hunks: &[DiffHunkInfo], selected: F) -> Vec<u8>
+where
+    F: Fn(&DiffLineInfo) -> bool,
+{
+    let old_lines: Vec<&[u8]> = old.split_inclusive(|&b| b == b'\n').collect();
+    let mut out = Vec::with_capacity(old.len());
+    let push = |out: &mut Vec<u8>, bytes: &[u8]| {
+        // A kept last line without newline is no longer last
+        if out.last().is_some_and(|&b| b != b'\n') {
+            out.push(b'\n');
+        }
+        out.extend_from_slice(bytes);
+    };
+
+    let mut pos = 0usize;
+    for hunk in hunks {
+        // For pure insertions old_start is the line the hunk follows
+        let start = if hunk.old_lines == 0 {
+            hunk.old_start as usize
+        } else {
+            (hunk.old_start as usize).saturating_sub(1)
+        };
+        while pos < start.min(old_lines.len()) {
+            push(&mut out, old_lines[pos]);
+            pos += 1;
+        }
+
+        for line in &hunk.lines {
+            match line.origin.as_str() {
+                " " => {
+                    if let Some(old_line) = old_lines.get(pos) {
+                        push(&mut out, old_line);
+                    }
+                    pos += 1;
+                }
+                "-" => {
+                    if !selected(line) {
+                        if let Some(old_line) = old_lines.get(pos) {
+                            push(&mut out, old_line);
+                        }
+                    }
+                    pos += 1;
+                }
+                _ => {
+                    if selected(line) {
+                        push(&mut out, &line.raw);
+                    }
+                }
+            }
+        }
+    }
+    while pos < old_lines.len() {
+        push(&mut out, old_lines[pos]);
+        pos += 1;
+    }
+    out
+}
+
+fn index_content(repo: &Repository, index: &Index, file: &str) -> Result<Vec<u8>> {
+    match index.get_path(Path::new(file), 0) {
+        Some(entry) => Ok(repo.find_blob(entry.id)?.content().to_vec()),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn write_index_entry(
+    repo: &Repository,
+    index: &mut Index,
+    file: &str,
+    content: &[u8],
+) -> Result<()> {
+    let entry = match index.get_path(Path::new(file), 0) {
+        Some(entry) => entry,
+        None => new_index_entry(file, file_mode(&workdir(repo)?.join(file))),
+    };
+    index.add_frombuffer(&entry, content)?;
+    Ok(())
+}
+
+fn new_index_entry(file: &str, mode: u32) -> IndexEntry {
+    IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode,
+        uid: 0,
+        gid: 0,
+        file_size: 0,
+        id: Oid::zero(),
+        flags: u16::try_from(file.len().min(0xfff)).unwrap_or(0xfff),
+        flags_extended: 0,
+        path: file.as_bytes().to_vec(),
+    }
+}
+
+#[cfg(unix)]
+fn file_mode(path: &Path) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    let executable = path
+        .metadata()
+        .is_ok_and(|m| m.permissions().mode() & 0o111 != 0);
+    if executable {
+        0o100_755
+    } else {
+        0o100_644
+    }
+}
+
+#[cfg(not(unix))]
+fn file_mode(_path: &Path) -> u32 {
+    0o100_644
+}
+
+/// Set or clear the executable bits to match a git file mode.
+#[cfg(unix)]
+fn set_file_mode(path: &Path, mode: i32) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = fs::metadata(path)?.permissions();
+    let current = permissions.mode();
+    permissions.set_mode(if mode == i32::from(FileMode::BlobExecutable) {
+        current | 0o111
+    } else {
+        current & !0o111
+    });
+    fs::set_permissions(path, permissions)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_file_mode(_path: &Path, _mode: i32) -> Result<()> {
+    Ok(())
+}
+
+fn tree_files(repo: &Repository, tree: Oid) -> Result<Vec<(String, Oid)>> {
+    let tree = repo.find_tree(tree)?;
+    let mut files = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            files.push((
+                format!("{dir}{}", entry.name().unwrap_or_default()),
+                entry.id(),
+            ));
+        }
+        TreeWalkResult::Ok
+    })?;
+    Ok(files)
+}
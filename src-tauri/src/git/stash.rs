@@ -0,0 +1,353 @@
+//! Stash listing, inspection and management.
+//!
+//! Stashes are addressed by their index in the stash list (`stash@{N}`).
+//! Because the list shifts whenever a stash is created or dropped, callers
+//! can pass the OID they saw and the operation is refused if it no longer
+//! matches.
+
+use git2::{
+    Commit, Delta, Diff, DiffDelta, DiffOptions, ErrorCode, Patch, Repository, Signature,
+    StashApplyOptions, StashFlags, Tree,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::error::{DevBaseError, Result};
+
+/// A file changed by a stash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StashFileChange {
+    pub path: String,
+    /// Previous path for renames
+    pub old_path: Option<String>,
+    /// added, modified, deleted, renamed, typechange or untracked
+    pub status: String,
+}
+
+/// One entry of the stash list.
+#[derive(Debug, Clone, Serialize)]
+pub struct StashEntry {
+    /// Position in the stash list (`stash@{index}`)
+    pub index: usize,
+    pub oid: String,
+    pub message: String,
+    /// Branch the stash was created on, parsed from the message
+    pub branch: Option<String>,
+    /// Unix timestamp
+    pub date: i64,
+    pub files: Vec<StashFileChange>,
+    /// Lines added across all files
+    pub additions: usize,
+    /// Lines removed across all files
+    pub deletions: usize,
+    /// The stash also holds untracked files
+    pub has_untracked: bool,
+}
+
+/// Patch of one file in a stash.
+#[derive(Debug, Clone, Serialize)]
+pub struct StashFilePatch {
+    #[serde(flatten)]
+    pub change: StashFileChange,
+    pub additions: usize,
+    pub deletions: usize,
+    pub is_binary: bool,
+    /// Unified diff (empty for binary files)
+    pub patch: String,
+}
+
+/// Options for creating a stash.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StashCreateOptions {
+    pub message: Option<String>,
+    /// Stash untracked files as well
+    pub include_untracked: bool,
+    /// Leave staged changes in the index and working tree
+    pub keep_index: bool,
+}
+
+/// Outcome of applying or popping a stash.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StashApplyResult {
+    /// Changes were written to the working tree
+    pub applied: bool,
+    /// Files left with conflict markers
+    pub conflicts: Vec<String>,
+    /// The stash was removed from the list (pop without conflicts)
+    pub dropped: bool,
+    /// Why nothing was applied
+    pub message: Option<String>,
+}
+
+/// List the stashes of a repository, newest first.
+pub fn list_stashes(path: &Path) -> Result<Vec<StashEntry>> {
+    let mut repo = Repository::open(path)?;
+    let mut stashes = Vec::new();
+    repo.stash_foreach(|index, message, oid| {
+        stashes.push((index, message.to_string(), *oid));
+        true
+    })?;
+
+    stashes
+        .into_iter()
+        .map(|(index, message, oid)| {
+            let commit = repo.find_commit(oid)?;
+            let mut files = Vec::new();
+            let (mut additions, mut deletions) = (0, 0);
+            for (diff, untracked) in stash_diffs(&repo, &commit)? {
+                files.extend(diff.deltas().map(|delta| file_change(&delta, untracked)));
+                let stats = diff.stats()?;
+                additions += stats.insertions();
+                deletions += stats.deletions();
+            }
+            Ok(StashEntry {
+                index,
+                oid: oid.to_string(),
+                branch: branch_from_message(&message),
+                message,
+                date: commit.time().seconds(),
+                files,
+                additions,
+                deletions,
+                has_untracked: commit.parent_count() > 2,
+            })
+        })
+        .collect()
+}
+
+/// Stashes created before `cutoff` (Unix timestamp).
+pub fn stashes_older_than(path: &Path, cutoff: i64) -> Result<Vec<StashEntry>> {
+    Ok(list_stashes(path)?
+        .into_iter()
+        .filter(|stash| stash.date < cutoff)
+        .collect())
+}
+
+/// Per-file patches of a stash, including its untracked files.
+pub fn get_stash_diff(path: &Path, index: usize, oid: Option<&str>) -> Result<Vec<StashFilePatch>> {
+    let mut repo = Repository::open(path)?;
+    let stash_oid = resolve_stash(&mut repo, index, oid)?;
+    let commit = repo.find_commit(stash_oid)?;
+
+    let mut patches = Vec::new();
+    for (diff, untracked) in stash_diffs(&repo, &commit)? {
+        for idx in 0..diff.deltas().len() {
+            let Some(mut patch) = Patch::from_diff(&diff, idx)? else {
+                continue;
+            };
+            let change = file_change(&patch.delta(), untracked);
+            let is_binary = patch.delta().flags().is_binary();
+            let (_, additions, deletions) = patch.line_stats()?;
+            let text = if is_binary {
+                String::new()
+            } else {
+                String::from_utf8_lossy(&patch.to_buf()?).to_string()
+            };
+            patches.push(StashFilePatch {
+                change,
+                additions,
+                deletions,
+                is_binary,
+                patch: text,
+            });
+        }
+    }
+    Ok(patches)
+}
+
+/// Stash local changes. Returns the new entry (`stash@{0}`).
+pub fn create_stash(path: &Path, options: &StashCreateOptions) -> Result<StashEntry> {
+    let mut repo = Repository::open(path)?;
+    let signature = stash_signature(&repo)?;
+
+    let mut flags = StashFlags::DEFAULT;
+    if options.include_untracked {
+        flags |= StashFlags::INCLUDE_UNTRACKED;
+    }
+    if options.keep_index {
+        flags |= StashFlags::KEEP_INDEX;
+    }
+
+    match repo.stash_save2(&signature, options.message.as_deref(), Some(flags)) {
+        Ok(_) => {}
+        Err(e) if e.code() == ErrorCode::NotFound => {
+            return Err(DevBaseError::Config {
+                message: "No local changes to stash".to_string(),
+            })
+        }
+        Err(e) => return Err(e.into()),
+    }
+    drop(repo);
+
+    list_stashes(path)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| DevBaseError::Internal {
+            message: "Stash was created but is not listed".to_string(),
+        })
+}
+
+/// Apply a stash, keeping it in the list.
+pub fn apply_stash(
+    path: &Path,
+    index: usize,
+    oid: Option<&str>,
+    reinstate_index: bool,
+) -> Result<StashApplyResult> {
+    let mut repo = Repository::open(path)?;
+    resolve_stash(&mut repo, index, oid)?;
+    apply(&mut repo, index, reinstate_index)
+}
+
+/// Apply a stash and drop it, unless applying left conflicts.
+pub fn pop_stash(
+    path: &Path,
+    index: usize,
+    oid: Option<&str>,
+    reinstate_index: bool,
+) -> Result<StashApplyResult> {
+    let mut repo = Repository::open(path)?;
+    resolve_stash(&mut repo, index, oid)?;
+    let mut result = apply(&mut repo, index, reinstate_index)?;
+    // Like `git stash pop`, keep the stash when it did not apply cleanly
+    if result.applied && result.conflicts.is_empty() {
+        repo.stash_drop(index)?;
+        result.dropped = true;
+    }
+    Ok(result)
+}
+
+/// Remove a stash from the list.
+pub fn drop_stash(path: &Path, index: usize, oid: Option<&str>) -> Result<()> {
+    let mut repo = Repository::open(path)?;
+    resolve_stash(&mut repo, index, oid)?;
+    repo.stash_drop(index)?;
+    Ok(())
+}
+
+/// Signature for stashes made by the app, falling back when the repository
+/// has no identity configured.
+pub(crate) fn stash_signature(repo: &Repository) -> Result<Signature<'static>> {
+    Ok(repo
+        .signature()
+        .or_else(|_| Signature::now("DevBase", "devbase@localhost"))?)
+}
+
+fn resolve_stash(repo: &mut Repository, index: usize, expected: Option<&str>) -> Result<git2::Oid> {
+    let mut found = None;
+    repo.stash_foreach(|i, _, oid| {
+        if i == index {
+            found = Some(*oid);
+            return false;
+        }
+        true
+    })?;
+
+    let oid = found.ok_or_else(|| DevBaseError::Config {
+        message: format!("No stash at stash@{{{index}}}"),
+    })?;
+    if expected.is_some_and(|expected| expected != oid.to_string()) {
+        return Err(DevBaseError::Config {
+            message: "The stash list changed; reload and try again".to_string(),
+        });
+    }
+    Ok(oid)
+}
+
+fn apply(repo: &mut Repository, index: usize, reinstate_index: bool) -> Result<StashApplyResult> {
+    let mut options = StashApplyOptions::new();
+    if reinstate_index {
+        options.reinstantiate_index();
+    }
+
+    match repo.stash_apply(index, Some(&mut options)) {
+        Ok(()) => {}
+        Err(e) if e.code() == ErrorCode::Conflict || e.code() == ErrorCode::MergeConflict => {
+            return Ok(StashApplyResult {
+                message: Some(format!(
+                    "Local changes would be overwritten: {}",
+                    e.message()
+                )),
+                ..StashApplyResult::default()
+            });
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    let conflicts = repo
+        .index()?
+        .conflicts()?
+        .filter_map(|c| c.ok())
+        .filter_map(|c| c.our.or(c.their).or(c.ancestor))
+        .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+        .collect();
+
+    Ok(StashApplyResult {
+        applied: true,
+        conflicts,
+        ..StashApplyResult::default()
+    })
+}
+
+/// Branch name from a stash message (`WIP on main: ...` or `On main: ...`).
+fn branch_from_message(message: &str) -> Option<String> {
+    let rest = message
+        .strip_prefix("WIP on ")
+        .or_else(|| message.strip_prefix("On "))?;
+    let (branch, _) = rest.split_once(':')?;
+    (branch != "(no branch)").then(|| branch.to_string())
+}
+
+/// Diffs recorded in a stash commit: the stashed tree against its base,
+/// then the untracked files commit (third parent) if there is one. The flag
+/// marks the untracked diff.
+fn stash_diffs<'r>(repo: &'r Repository, stash: &Commit) -> Result<Vec<(Diff<'r>, bool)>> {
+    let base = stash.parent(0)?.tree()?;
+    let mut opts = DiffOptions::new();
+    let mut diff = repo.diff_tree_to_tree(Some(&base), Some(&stash.tree()?), Some(&mut opts))?;
+    diff.find_similar(None)?;
+    let mut diffs = vec![(diff, false)];
+
+    if stash.parent_count() > 2 {
+        let untracked: Tree = stash.parent(2)?.tree()?;
+        let diff = repo.diff_tree_to_tree(None, Some(&untracked), Some(&mut DiffOptions::new()))?;
+        diffs.push((diff, true));
+    }
+
+    Ok(diffs)
+}
+
+fn file_change(delta: &DiffDelta, untracked: bool) -> StashFileChange {
+    let path = delta
+        .new_file()
+        .path()
+        .or_else(|| delta.old_file().path())
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let old_path = (delta.status() == Delta::Renamed)
+        .then(|| {
+            delta
+                .old_file()
+                .path()
+                .map(|p| p.to_string_lossy().to_string())
+        })
+        .flatten();
+    let status = if untracked {
+        "untracked"
+    } else {
+        match delta.status() {
+            Delta::Added => "added",
+            Delta::Deleted => "deleted",
+            Delta::Renamed => "renamed",
+            Delta::Typechange => "typechange",
+            _ => "modified",
+        }
+    };
+
+    StashFileChange {
+        path,
+        old_path,
+        status: status.to_string(),
+    }
+}
@@ -1,13 +1,18 @@
 //! Git repository status checking.
 
-use std::path::Path;
-use git2::{Repository, StatusOptions};
-use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Condvar, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+use git2::{BranchType, ErrorClass, ErrorCode, Repository, RepositoryState, Status, StatusOptions};
+use serde::{Deserialize, Serialize};
 
+use super::branches::resolve_remote_default_branch;
 use crate::error::{DevBaseError, Result};
 
 /// Repository health/status information.
-#[derive(Debug, Clone, Serialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RepoHealth {
     /// Repository has uncommitted changes
     pub is_dirty: bool,
@@ -25,15 +30,245 @@ pub struct RepoHealth {
     pub current_branch: Option<String>,
     /// Whether the repo is on a detached HEAD
     pub is_detached: bool,
+    /// Operation in progress (merge, rebase, cherry-pick, ...)
+    pub state: RepoState,
+    /// Step progress of an in-progress rebase
+    pub operation_progress: Option<OperationProgress>,
+    /// Number of conflicted index entries
+    pub conflicted_count: u32,
+    /// Paths with unresolved conflicts
+    pub conflicted_files: Vec<String>,
+    /// Per-category file counts
+    pub breakdown: StatusBreakdown,
+    /// Remote default branch (`origin/main`), from `refs/remotes/<remote>/HEAD`
+    pub default_branch: Option<String>,
+    /// Commits on the current branch not in the remote default branch
+    pub ahead_of_default: u32,
+    /// Commits in the remote default branch not on the current branch
+    pub behind_default: u32,
+    /// Number of local branches with commits not on any remote
+    pub unpushed_branch_count: u32,
+    /// Local branches with commits not on any remote
+    pub unpushed_branches: Vec<String>,
+    /// Computed in light mode (untracked directories counted as one entry)
+    pub is_light: bool,
+    /// The time budget ran out; file counts and branch details may be missing
+    pub is_partial: bool,
+    /// Why the health could not be (fully) determined
+    pub error: Option<HealthError>,
+}
+
+/// An `index.lock` older than this is assumed to be left behind by a crashed git process.
+pub const STALE_INDEX_LOCK_SECS: u64 = 60;
+
+/// Category of a health check failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthErrorKind {
+    /// Path or repository no longer exists (deleted, unmounted drive)
+    MissingPath,
+    /// Path cannot be read
+    PermissionDenied,
+    /// Repository data (index, objects, refs) cannot be parsed
+    CorruptRepository,
+    /// A stale `index.lock` blocks writes to the index
+    LockedIndex,
+    Other,
+}
+
+/// Why a repository's health is unknown or untrustworthy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthError {
+    pub kind: HealthErrorKind,
+    pub message: String,
+}
+
+/// How much work a health check may do.
+///
+/// libgit2 does not read git's untracked cache, so light mode gets the same
+/// effect by not descending into untracked directories at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct HealthOptions {
+    /// Skip untracked directory recursion, ignored files and worktree rename detection
+    pub light: bool,
+    /// Stop waiting for the working tree scan after this many milliseconds
+    pub budget_ms: Option<u64>,
+}
+
+/// Counts collected from the working tree scan.
+#[derive(Debug, Default)]
+struct WorktreeScan {
+    breakdown: StatusBreakdown,
+    staged_count: u32,
+    uncommitted_count: u32,
+}
+
+/// Ignored files at least this large are counted as `ignored_large`.
+pub const LARGE_IGNORED_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Entries visited inside ignored directories per scan when looking for
+/// large files; `target/` or `node_modules/` can hold millions.
+pub const IGNORED_DIR_WALK_LIMIT: usize = 20_000;
+
+/// Working tree changes broken down by category.
+///
+/// A file is counted once per category it falls in, on either the index or
+/// the working tree side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct StatusBreakdown {
+    /// New files not yet tracked
+    pub untracked: u32,
+    /// New files staged for commit
+    pub added: u32,
+    pub modified: u32,
+    pub deleted: u32,
+    pub renamed: u32,
+    pub type_changed: u32,
+    pub conflicted: u32,
+    /// Ignored files of at least `LARGE_IGNORED_FILE_BYTES`, including those
+    /// inside ignored directories within the first `IGNORED_DIR_WALK_LIMIT` entries
+    pub ignored_large: u32,
+}
+
+impl StatusBreakdown {
+    fn record(&mut self, s: Status) {
+        if s.is_wt_new() {
+            self.untracked += 1;
+        }
+        if s.is_index_new() {
+            self.added += 1;
+        }
+        if s.is_index_modified() || s.is_wt_modified() {
+            self.modified += 1;
+        }
+        if s.is_index_deleted() || s.is_wt_deleted() {
+            self.deleted += 1;
+        }
+        if s.is_index_renamed() || s.is_wt_renamed() {
+            self.renamed += 1;
+        }
+        if s.is_index_typechange() || s.is_wt_typechange() {
+            self.type_changed += 1;
+        }
+        if s.is_conflicted() {
+            self.conflicted += 1;
+        }
+    }
+}
+
+/// Kind of change on one side (index or working tree) of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChange {
+    New,
+    Modified,
+    Deleted,
+    Renamed,
+    TypeChange,
+}
+
+/// Status of a single file for the repository detail view.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileStatusEntry {
+    /// Current path relative to the repository root
+    pub path: String,
+    /// Rename source, if renamed
+    pub old_path: Option<String>,
+    /// Staged change
+    pub index_status: Option<FileChange>,
+    /// Unstaged change
+    pub worktree_status: Option<FileChange>,
+    pub is_conflicted: bool,
+    pub is_ignored: bool,
+}
+
+/// Repository operation state, mirroring `git2::RepositoryState`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RepoState {
+    /// No operation in progress
+    #[default]
+    Clean,
+    Merge,
+    Revert,
+    RevertSequence,
+    CherryPick,
+    CherryPickSequence,
+    Bisect,
+    Rebase,
+    RebaseInteractive,
+    RebaseMerge,
+    ApplyMailbox,
+    ApplyMailboxOrRebase,
+}
+
+impl From<RepositoryState> for RepoState {
+    fn from(state: RepositoryState) -> Self {
+        match state {
+            RepositoryState::Clean => Self::Clean,
+            RepositoryState::Merge => Self::Merge,
+            RepositoryState::Revert => Self::Revert,
+            RepositoryState::RevertSequence => Self::RevertSequence,
+            RepositoryState::CherryPick => Self::CherryPick,
+            RepositoryState::CherryPickSequence => Self::CherryPickSequence,
+            RepositoryState::Bisect => Self::Bisect,
+            RepositoryState::Rebase => Self::Rebase,
+            RepositoryState::RebaseInteractive => Self::RebaseInteractive,
+            RepositoryState::RebaseMerge => Self::RebaseMerge,
+            RepositoryState::ApplyMailbox => Self::ApplyMailbox,
+            RepositoryState::ApplyMailboxOrRebase => Self::ApplyMailboxOrRebase,
+        }
+    }
+}
+
+/// Progress of a multi-step operation ("step N of M").
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationProgress {
+    pub current: u32,
+    pub total: u32,
 }
 
 /// Get the health status of a repository.
 pub fn get_repo_health(path: &Path) -> Result<RepoHealth> {
-    let mut repo = Repository::open(path).map_err(|e| DevBaseError::Scan {
-        message: format!("Failed to open repository: {e}"),
-    })?;
+    get_repo_health_with(path, &HealthOptions::default())
+}
+
+/// Get the health of a repository, reporting failures in `RepoHealth::error`.
+///
+/// Unlike `get_repo_health_with` this never fails, and a repository that
+/// cannot be read is never reported as clean.
+pub fn check_repo_health(path: &Path, options: &HealthOptions) -> RepoHealth {
+    match get_repo_health_with(path, options) {
+        Ok(health) => health,
+        Err(e) => RepoHealth {
+            is_light: options.light,
+            error: Some(HealthError {
+                kind: classify_health_error(path, &e),
+                message: e.to_string(),
+            }),
+            ..RepoHealth::default()
+        },
+    }
+}
+
+/// Get the health status of a repository within a time budget.
+///
+/// Cheap checks run first. If the working tree scan does not finish within
+/// the budget, the result is returned without it and marked `is_partial`;
+/// the remaining branch checks are skipped once the budget is spent.
+pub fn get_repo_health_with(path: &Path, options: &HealthOptions) -> Result<RepoHealth> {
+    let deadline = options
+        .budget_ms
+        .map(|ms| Instant::now() + Duration::from_millis(ms));
 
-    let mut health = RepoHealth::default();
+    let mut repo = Repository::open(path)?;
+
+    let mut health = RepoHealth {
+        is_light: options.light,
+        error: stale_index_lock(&repo),
+        ..RepoHealth::default()
+    };
 
     // Get current branch
     if let Ok(head) = repo.head() {
@@ -41,28 +276,11 @@ pub fn get_repo_health(path: &Path) -> Result<RepoHealth> {
         health.current_branch = head.shorthand().map(String::from);
     }
 
-    // Get status
-    let mut opts = StatusOptions::new();
-    opts.include_untracked(true)
-        .recurse_untracked_dirs(true);
-
-    if let Ok(statuses) = repo.statuses(Some(&mut opts)) {
-        for status in statuses.iter() {
-            let s = status.status();
-            
-            if s.is_index_new() || s.is_index_modified() || s.is_index_deleted() ||
-               s.is_index_renamed() || s.is_index_typechange() {
-                health.staged_count += 1;
-            }
-            
-            if s.is_wt_new() || s.is_wt_modified() || s.is_wt_deleted() ||
-               s.is_wt_renamed() || s.is_wt_typechange() {
-                health.uncommitted_count += 1;
-            }
-        }
-    }
-
-    health.is_dirty = health.uncommitted_count > 0 || health.staged_count > 0;
+    // Get in-progress operation and conflicts
+    health.state = RepoState::from(repo.state());
+    health.operation_progress = get_operation_progress(&repo);
+    health.conflicted_files = get_conflicted_files(&repo);
+    health.conflicted_count = health.conflicted_files.len() as u32;
 
     // Get stash count
     let mut stash_count = 0u32;
@@ -80,9 +298,209 @@ pub fn get_repo_health(path: &Path) -> Result<RepoHealth> {
         }
     }
 
+    // Get status
+    let scan = match deadline {
+        Some(deadline) => scan_worktree_until(path, options.light, deadline)?,
+        None => Some(scan_worktree(&repo, options.light)?),
+    };
+    match scan {
+        Some(scan) => {
+            health.breakdown = scan.breakdown;
+            health.staged_count = scan.staged_count;
+            health.uncommitted_count = scan.uncommitted_count;
+        }
+        None => health.is_partial = true,
+    }
+
+    health.is_dirty = health.uncommitted_count > 0 || health.staged_count > 0;
+
+    if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+        health.is_partial = true;
+        return Ok(health);
+    }
+
+    // Get ahead/behind against the remote default branch
+    if let Some(default) = resolve_remote_default_branch(&repo) {
+        if let Some(head_oid) = repo.head().ok().and_then(|h| h.target()) {
+            if let Ok((ahead, behind)) = repo.graph_ahead_behind(head_oid, default.oid) {
+                health.ahead_of_default = ahead as u32;
+                health.behind_default = behind as u32;
+            }
+        }
+        health.default_branch = Some(default.name);
+    }
+
+    // Get local branches that only exist on this machine
+    health.unpushed_branches = get_unpushed_branches(&repo);
+    health.unpushed_branch_count = health.unpushed_branches.len() as u32;
+
     Ok(health)
 }
 
+/// Working trees with a scan thread running, including abandoned ones.
+static SCANS_IN_FLIGHT: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+/// Signalled whenever a scan thread finishes.
+static SCAN_FINISHED: Condvar = Condvar::new();
+
+/// Marks a working tree as being scanned until dropped.
+struct ScanSlot(PathBuf);
+
+impl ScanSlot {
+    /// Wait until no other scan of `path` is running, or `None` at `deadline`.
+    fn claim(path: &Path, deadline: Instant) -> Option<Self> {
+        let mut running = SCANS_IN_FLIGHT
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        while running.contains(path) {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            if timeout.is_zero() {
+                return None;
+            }
+            running = SCAN_FINISHED
+                .wait_timeout(running, timeout)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+        running.insert(path.to_path_buf());
+        Some(Self(path.to_path_buf()))
+    }
+}
+
+impl Drop for ScanSlot {
+    fn drop(&mut self) {
+        SCANS_IN_FLIGHT
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.0);
+        SCAN_FINISHED.notify_all();
+    }
+}
+
+/// Run the working tree scan on its own thread and wait until `deadline`.
+///
+/// libgit2 cannot cancel a status scan, so on timeout the thread is left to
+/// finish on its own and its result is dropped. At most one scan per working
+/// tree runs at a time; later calls wait for it instead of piling up threads
+/// behind a slow tree. Returns `None` on timeout.
+fn scan_worktree_until(
+    path: &Path,
+    light: bool,
+    deadline: Instant,
+) -> Result<Option<WorktreeScan>> {
+    let Some(slot) = ScanSlot::claim(path, deadline) else {
+        return Ok(None);
+    };
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let scan = Repository::open(&slot.0)
+            .map_err(DevBaseError::from)
+            .and_then(|repo| scan_worktree(&repo, light));
+        // Release the tree before reporting so a caller retrying at once
+        // does not have to wait
+        drop(slot);
+        let _ = tx.send(scan);
+    });
+
+    match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        Ok(scan) => scan.map(Some),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Count staged, unstaged, untracked and large ignored files.
+fn scan_worktree(repo: &Repository, light: bool) -> Result<WorktreeScan> {
+    let mut scan = WorktreeScan::default();
+
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(!light)
+        .include_ignored(!light)
+        .recurse_ignored_dirs(false)
+        .renames_head_to_index(true)
+        .renames_index_to_workdir(!light);
+
+    let workdir = repo.workdir().map(Path::to_path_buf);
+    let mut walk_budget = IGNORED_DIR_WALK_LIMIT;
+
+    // A failing scan must not look like a clean working tree
+    let statuses = repo.statuses(Some(&mut opts))?;
+    for status in statuses.iter() {
+        let s = status.status();
+
+        if s.is_ignored() {
+            if let (Some(dir), Some(file)) = (&workdir, status.path()) {
+                scan.breakdown.ignored_large += count_large_files(&dir.join(file), &mut walk_budget);
+            }
+            continue;
+        }
+
+        scan.breakdown.record(s);
+        
+        if s.is_index_new() || s.is_index_modified() || s.is_index_deleted() ||
+           s.is_index_renamed() || s.is_index_typechange() {
+            scan.staged_count += 1;
+        }
+        
+        if s.is_wt_new() || s.is_wt_modified() || s.is_wt_deleted() ||
+           s.is_wt_renamed() || s.is_wt_typechange() {
+            scan.uncommitted_count += 1;
+        }
+    }
+
+    Ok(scan)
+}
+
+/// Report an `index.lock` that has been sitting around for too long.
+fn stale_index_lock(repo: &Repository) -> Option<HealthError> {
+    let lock = repo.path().join("index.lock");
+    let age = std::fs::metadata(&lock).ok()?.modified().ok()?.elapsed().ok()?;
+
+    (age.as_secs() >= STALE_INDEX_LOCK_SECS).then(|| HealthError {
+        kind: HealthErrorKind::LockedIndex,
+        message: format!(
+            "{} was left behind; remove it if no git process is running",
+            lock.display()
+        ),
+    })
+}
+
+/// Work out why a health check failed.
+fn classify_health_error(path: &Path, error: &DevBaseError) -> HealthErrorKind {
+    match std::fs::read_dir(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return HealthErrorKind::MissingPath,
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            return HealthErrorKind::PermissionDenied
+        }
+        _ => {}
+    }
+
+    match error {
+        DevBaseError::Git(e) => match (e.code(), e.class()) {
+            (ErrorCode::Locked, _) => HealthErrorKind::LockedIndex,
+            // The directory exists but the repository inside it is gone
+            (ErrorCode::NotFound, ErrorClass::Repository) => HealthErrorKind::MissingPath,
+            (_, ErrorClass::Os) if e.message().to_lowercase().contains("permission denied") => {
+                HealthErrorKind::PermissionDenied
+            }
+            (
+                _,
+                ErrorClass::Index
+                | ErrorClass::Odb
+                | ErrorClass::Object
+                | ErrorClass::Repository
+                | ErrorClass::Reference
+                | ErrorClass::Zlib,
+            ) => HealthErrorKind::CorruptRepository,
+            _ => HealthErrorKind::Other,
+        },
+        DevBaseError::FileSystem(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            HealthErrorKind::PermissionDenied
+        }
+        _ => HealthErrorKind::Other,
+    }
+}
+
 /// Get the number of commits ahead and behind the upstream.
 fn get_ahead_behind(repo: &Repository, branch_name: &str) -> Result<(u32, u32)> {
     let branch = repo.find_branch(branch_name, git2::BranchType::Local)
@@ -111,14 +529,207 @@ fn get_ahead_behind(repo: &Repository, branch_name: &str) -> Result<(u32, u32)>
     Ok((ahead as u32, behind as u32))
 }
 
+/// List local branches with at least one commit not reachable from any remote-tracking ref.
+fn get_unpushed_branches(repo: &Repository) -> Vec<String> {
+    let Ok(branches) = repo.branches(Some(BranchType::Local)) else {
+        return Vec::new();
+    };
+
+    let mut unpushed = Vec::new();
+    for (branch, _) in branches.flatten() {
+        let (Some(tip), Ok(Some(name))) = (branch.get().target(), branch.name()) else {
+            continue;
+        };
+
+        let has_local_commits = repo
+            .revwalk()
+            .and_then(|mut walk| {
+                walk.push(tip)?;
+                walk.hide_glob("refs/remotes/*")?;
+                Ok(walk.next().is_some())
+            })
+            .unwrap_or(false);
+
+        if has_local_commits {
+            unpushed.push(name.to_string());
+        }
+    }
+
+    unpushed
+}
+
+/// Count large files at `path`, descending into directories (but not
+/// symlinks) until `budget` entries have been visited.
+fn count_large_files(path: &Path, budget: &mut usize) -> u32 {
+    let mut count = 0;
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(path) = pending.pop() {
+        let Ok(metadata) = std::fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if metadata.is_file() && metadata.len() >= LARGE_IGNORED_FILE_BYTES {
+            count += 1;
+        } else if metadata.is_dir() {
+            let Ok(entries) = std::fs::read_dir(&path) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                if *budget == 0 {
+                    break;
+                }
+                *budget -= 1;
+                pending.push(entry.path());
+            }
+        }
+    }
+    count
+}
+
+/// List the status of every changed file in a repository.
+pub fn get_file_statuses(path: &Path, include_ignored: bool) -> Result<Vec<FileStatusEntry>> {
+    let repo = Repository::open(path)?;
+
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(include_ignored)
+        .recurse_ignored_dirs(false)
+        .renames_head_to_index(true)
+        .renames_index_to_workdir(true);
+
+    let statuses = repo.statuses(Some(&mut opts))?;
+    let mut entries = Vec::with_capacity(statuses.len());
+
+    for status in statuses.iter() {
+        let s = status.status();
+
+        // Rename sources live in the diff deltas, not in the entry path
+        let delta_path = |delta: Option<git2::DiffDelta<'_>>, old: bool| {
+            delta.and_then(|d| {
+                let file = if old { d.old_file() } else { d.new_file() };
+                file.path().map(|p| p.to_string_lossy().to_string())
+            })
+        };
+
+        let path = delta_path(status.index_to_workdir(), false)
+            .or_else(|| delta_path(status.head_to_index(), false))
+            .or_else(|| status.path().map(String::from))
+            .unwrap_or_default();
+
+        let old_path = if s.is_index_renamed() {
+            delta_path(status.head_to_index(), true)
+        } else if s.is_wt_renamed() {
+            delta_path(status.index_to_workdir(), true)
+        } else {
+            None
+        };
+
+        entries.push(FileStatusEntry {
+            path,
+            old_path,
+            index_status: index_change(s),
+            worktree_status: worktree_change(s),
+            is_conflicted: s.is_conflicted(),
+            is_ignored: s.is_ignored(),
+        });
+    }
+
+    Ok(entries)
+}
+
+fn index_change(s: Status) -> Option<FileChange> {
+    if s.is_index_new() {
+        Some(FileChange::New)
+    } else if s.is_index_modified() {
+        Some(FileChange::Modified)
+    } else if s.is_index_deleted() {
+        Some(FileChange::Deleted)
+    } else if s.is_index_renamed() {
+        Some(FileChange::Renamed)
+    } else if s.is_index_typechange() {
+        Some(FileChange::TypeChange)
+    } else {
+        None
+    }
+}
+
+fn worktree_change(s: Status) -> Option<FileChange> {
+    if s.is_wt_new() {
+        Some(FileChange::New)
+    } else if s.is_wt_modified() {
+        Some(FileChange::Modified)
+    } else if s.is_wt_deleted() {
+        Some(FileChange::Deleted)
+    } else if s.is_wt_renamed() {
+        Some(FileChange::Renamed)
+    } else if s.is_wt_typechange() {
+        Some(FileChange::TypeChange)
+    } else {
+        None
+    }
+}
+
+/// Read "step N of M" for an in-progress rebase.
+fn get_operation_progress(repo: &Repository) -> Option<OperationProgress> {
+    let git_dir = repo.path();
+    let read_number = |path: std::path::PathBuf| -> Option<u32> {
+        std::fs::read_to_string(path).ok()?.trim().parse().ok()
+    };
+
+    // rebase-merge: msgnum/end, rebase-apply (am-style): next/last
+    let layouts = [("rebase-merge", "msgnum", "end"), ("rebase-apply", "next", "last")];
+    for (dir, current, total) in layouts {
+        let dir = git_dir.join(dir);
+        if dir.is_dir() {
+            return Some(OperationProgress {
+                current: read_number(dir.join(current))?,
+                total: read_number(dir.join(total))?,
+            });
+        }
+    }
+
+    None
+}
+
+/// List paths with conflicted index entries.
+fn get_conflicted_files(repo: &Repository) -> Vec<String> {
+    let Ok(index) = repo.index() else {
+        return Vec::new();
+    };
+    if !index.has_conflicts() {
+        return Vec::new();
+    }
+
+    let Ok(conflicts) = index.conflicts() else {
+        return Vec::new();
+    };
+
+    conflicts
+        .filter_map(|c| c.ok())
+        .filter_map(|c| c.our.or(c.their).or(c.ancestor))
+        .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+        .collect()
+}
+
 /// Status summary for display.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum RepoStatus {
+    /// Path or repository cannot be reached
+    Unavailable,
+    /// Repository is corrupt or locked
+    Error,
+    /// Has unresolved conflicts
+    Conflicted,
+    /// Stuck mid-operation (merge, rebase, cherry-pick, ...)
+    #[serde(rename = "in_progress")]
+    InProgress,
     /// All clean, up to date
     Clean,
     /// Has uncommitted changes
     Dirty,
+    /// No problems found, but the check ran out of time before it could confirm clean
+    Partial,
     /// Has commits to push
     Ahead,
     /// Has commits to pull
@@ -130,7 +741,20 @@ pub enum RepoStatus {
 impl RepoHealth {
     /// Get the overall status for display.
     pub fn status(&self) -> RepoStatus {
-        if self.commits_ahead > 0 && self.commits_behind > 0 {
+        if let Some(error) = &self.error {
+            match error.kind {
+                HealthErrorKind::MissingPath | HealthErrorKind::PermissionDenied => {
+                    RepoStatus::Unavailable
+                }
+                HealthErrorKind::CorruptRepository
+                | HealthErrorKind::LockedIndex
+                | HealthErrorKind::Other => RepoStatus::Error,
+            }
+        } else if self.conflicted_count > 0 {
+            RepoStatus::Conflicted
+        } else if self.state != RepoState::Clean {
+            RepoStatus::InProgress
+        } else if self.commits_ahead > 0 && self.commits_behind > 0 {
             RepoStatus::Diverged
         } else if self.commits_behind > 0 {
             RepoStatus::Behind
@@ -138,6 +762,8 @@ impl RepoHealth {
             RepoStatus::Ahead
         } else if self.is_dirty {
             RepoStatus::Dirty
+        } else if self.is_partial {
+            RepoStatus::Partial
         } else {
             RepoStatus::Clean
         }
@@ -0,0 +1,183 @@
+//! Safe sync: fetch, then fast-forward the current branch to its upstream.
+//!
+//! Nothing is ever merged or rebased. A repository is only touched when the
+//! update is a pure fast-forward and the working tree is clean, or when local
+//! changes can be stashed and restored around the update.
+
+use git2::build::CheckoutBuilder;
+use git2::{Branch, Repository, RepositoryState, StatusOptions};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::fetch::{fetch_repo, FetchOptions};
+use super::stash::stash_signature;
+use crate::error::{DevBaseError, Result};
+
+const AUTOSTASH_MESSAGE: &str = "devbase autostash";
+
+/// Options for a sync.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncOptions {
+    /// Fetch before fast-forwarding
+    pub fetch: bool,
+    /// Stash local changes around the update instead of skipping
+    pub autostash: bool,
+    pub fetch_options: FetchOptions,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        Self {
+            fetch: true,
+            autostash: false,
+            fetch_options: FetchOptions::default(),
+        }
+    }
+}
+
+/// What happened to one repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum SyncOutcome {
+    /// The branch was fast-forwarded
+    Updated {
+        branch: String,
+        from: String,
+        to: String,
+        /// Number of commits pulled in
+        commits: u32,
+        /// Local changes were stashed around the update
+        stashed: bool,
+        /// The stash could not be re-applied and was left in the stash list
+        stash_conflict: bool,
+    },
+    /// Nothing to pull
+    UpToDate {
+        branch: String,
+    },
+    /// Left alone on purpose
+    Skipped {
+        reason: String,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+/// Fetch (optionally) and fast-forward the current branch of a repository.
+pub fn sync_repo(path: &Path, options: &SyncOptions) -> SyncOutcome {
+    if options.fetch {
+        if let Some(error) = fetch_error(path, &options.fetch_options) {
+            return SyncOutcome::Failed { error };
+        }
+    }
+    fast_forward_repo(path, options.autostash)
+}
+
+/// Fetch for a sync, returning why it failed if it did.
+pub fn fetch_error(path: &Path, options: &FetchOptions) -> Option<String> {
+    match fetch_repo(path, options, |_| {}) {
+        Ok(result) => result.error().map(|e| format!("Fetch failed: {e}")),
+        Err(e) => Some(format!("Fetch failed: {e}")),
+    }
+}
+
+/// Fast-forward the current branch to its already-fetched upstream.
+pub fn fast_forward_repo(path: &Path, autostash: bool) -> SyncOutcome {
+    fast_forward(path, autostash).unwrap_or_else(|e| SyncOutcome::Failed {
+        error: e.to_string(),
+    })
+}
+
+fn skipped(reason: &str) -> SyncOutcome {
+    SyncOutcome::Skipped {
+        reason: reason.to_string(),
+    }
+}
+
+fn fast_forward(path: &Path, autostash: bool) -> Result<SyncOutcome> {
+    let mut repo = Repository::open(path)?;
+
+    if repo.state() != RepositoryState::Clean {
+        return Ok(skipped("an operation (merge, rebase, ...) is in progress"));
+    }
+
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => {
+            return Ok(skipped("branch has no commits yet"))
+        }
+        Err(e) => return Err(e.into()),
+    };
+    if !head.is_branch() {
+        return Ok(skipped("HEAD is detached"));
+    }
+
+    let branch_name = head.shorthand().unwrap_or("HEAD").to_string();
+    let ref_name = head.name().unwrap_or_default().to_string();
+    let head_oid = head.target().ok_or_else(|| DevBaseError::Scan {
+        message: "HEAD has no target".to_string(),
+    })?;
+
+    let Ok(upstream) = Branch::wrap(head).upstream() else {
+        return Ok(skipped("branch has no upstream"));
+    };
+    let upstream_oid = upstream.get().target().ok_or_else(|| DevBaseError::Scan {
+        message: "Upstream has no target".to_string(),
+    })?;
+    drop(upstream);
+
+    if head_oid == upstream_oid || repo.graph_descendant_of(head_oid, upstream_oid)? {
+        return Ok(SyncOutcome::UpToDate {
+            branch: branch_name,
+        });
+    }
+    if !repo.graph_descendant_of(upstream_oid, head_oid)? {
+        return Ok(skipped(
+            "branch has diverged from its upstream; not a fast-forward",
+        ));
+    }
+
+    let dirty = has_tracked_changes(&repo)?;
+    if dirty && !autostash {
+        return Ok(skipped("working tree has uncommitted changes"));
+    }
+
+    if dirty {
+        let signature = stash_signature(&repo)?;
+        repo.stash_save(&signature, AUTOSTASH_MESSAGE, None)?;
+    }
+
+    let update = (|| -> Result<()> {
+        let target = repo.find_commit(upstream_oid)?;
+        // Safe checkout refuses to overwrite untracked files in the way
+        repo.checkout_tree(target.as_object(), Some(CheckoutBuilder::new().safe()))?;
+        repo.find_reference(&ref_name)?.set_target(
+            upstream_oid,
+            &format!("devbase sync: fast-forward to {upstream_oid}"),
+        )?;
+        Ok(())
+    })();
+
+    let stash_conflict = dirty && repo.stash_pop(0, None).is_err();
+    update?;
+
+    let (commits, _) = repo.graph_ahead_behind(upstream_oid, head_oid)?;
+
+    Ok(SyncOutcome::Updated {
+        branch: branch_name,
+        from: head_oid.to_string(),
+        to: upstream_oid.to_string(),
+        commits: u32::try_from(commits).unwrap_or(u32::MAX),
+        stashed: dirty,
+        stash_conflict,
+    })
+}
+
+/// Check for staged or unstaged changes to tracked files, including conflicts.
+fn has_tracked_changes(repo: &Repository) -> Result<bool> {
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(false).include_ignored(false);
+    Ok(!repo.statuses(Some(&mut opts))?.is_empty())
+}
@@ -0,0 +1,285 @@
+//! Tag and release inventory.
+//!
+//! Lists lightweight and annotated tags, recognises version-like tag names
+//! (`v1.2.3`, `1.2`, `pkg@1.2.3-rc.1`, `release/2.0.0`) and orders them by
+//! semver precedence to find each repository's latest release.
+
+use git2::{Oid, Repository, Sort};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use super::branches::resolve_default_branch;
+use crate::error::Result;
+
+/// A semantic version parsed from a tag name. Build metadata is ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemVer {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Pre-release identifiers (`rc.1` -> `["rc", "1"]`)
+    pub pre: Vec<String>,
+}
+
+/// A tag of a repository.
+#[derive(Debug, Clone, Serialize)]
+pub struct TagInfo {
+    pub name: String,
+    /// Commit the tag points to
+    pub target_oid: String,
+    pub is_annotated: bool,
+    pub tagger_name: Option<String>,
+    pub tagger_email: Option<String>,
+    /// Tagger date for annotated tags, commit date otherwise (Unix timestamp)
+    pub date: i64,
+    pub message: Option<String>,
+    /// Normalized version if the name is version-like (`1.2.0`)
+    pub version: Option<String>,
+    pub is_prerelease: bool,
+}
+
+/// Someone who committed since the last release.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuthorCount {
+    pub name: String,
+    pub email: String,
+    pub commits: u32,
+}
+
+/// Latest release of a repository and the work on top of it.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReleaseSummary {
+    /// Highest version tag reachable from the default branch
+    pub latest_release: Option<TagInfo>,
+    /// Branch the counts refer to
+    pub branch: Option<String>,
+    /// Commits on the branch since the release (all commits if there is none)
+    pub commits_since: u32,
+    /// Authors of those commits, most active first
+    pub authors_since: Vec<AuthorCount>,
+}
+
+impl SemVer {
+    /// Parse a tag name, skipping a prefix such as `v`, `pkg@` or `release/`.
+    pub fn from_tag(name: &str) -> Option<Self> {
+        let starts = std::iter::once(0).chain(
+            name.char_indices()
+                .filter(|(_, c)| matches!(c, '/' | '@' | '-' | '_'))
+                .map(|(i, _)| i + 1),
+        );
+        for start in starts {
+            let candidate = &name[start..];
+            let candidate = candidate.strip_prefix(['v', 'V']).unwrap_or(candidate);
+            if let Some(version) = Self::parse(candidate) {
+                return Some(version);
+            }
+        }
+        None
+    }
+
+    /// Parse `MAJOR.MINOR[.PATCH][-PRE][+BUILD]`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.split_once('+').map_or(text, |(version, _)| version);
+        let (core, pre) = match text.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (text, None),
+        };
+
+        let parts: Vec<&str> = core.split('.').collect();
+        if !(2..=3).contains(&parts.len()) {
+            return None;
+        }
+        let number = |part: &str| {
+            (!part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+                .then(|| part.parse::<u64>().ok())
+                .flatten()
+        };
+        let major = number(parts[0])?;
+        let minor = number(parts[1])?;
+        let patch = match parts.get(2) {
+            Some(part) => number(part)?,
+            None => 0,
+        };
+
+        let pre = match pre {
+            Some(pre) => {
+                let identifiers: Vec<String> = pre.split('.').map(String::from).collect();
+                let valid = identifiers.iter().all(|id| {
+                    !id.is_empty() && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+                });
+                if !valid {
+                    return None;
+                }
+                identifiers
+            }
+            None => Vec::new(),
+        };
+
+        Some(Self {
+            major,
+            minor,
+            patch,
+            pre,
+        })
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+}
+
+impl fmt::Display for SemVer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for SemVer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                // A release ranks above its pre-releases
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => compare_prerelease(&self.pre, &other.pre),
+            })
+    }
+}
+
+impl PartialOrd for SemVer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn compare_prerelease(a: &[String], b: &[String]) -> Ordering {
+    for (x, y) in a.iter().zip(b) {
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            // Numeric identifiers rank below alphanumeric ones
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => x.cmp(y),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+/// List all tags: version tags first (highest version first), then the
+/// others, newest first.
+pub fn list_tags(path: &Path) -> Result<Vec<TagInfo>> {
+    let repo = Repository::open(path)?;
+    let mut tags: Vec<(Option<SemVer>, TagInfo)> = Vec::new();
+
+    for name in repo.tag_names(None)?.iter().flatten() {
+        let Ok(reference) = repo.find_reference(&format!("refs/tags/{name}")) else {
+            continue;
+        };
+        // Tags of trees or blobs are not releases
+        let Ok(commit) = reference.peel_to_commit() else {
+            continue;
+        };
+        let annotated = reference.target().and_then(|oid| repo.find_tag(oid).ok());
+
+        let version = SemVer::from_tag(name);
+        let tagger = annotated.as_ref().and_then(git2::Tag::tagger);
+        tags.push((
+            version.clone(),
+            TagInfo {
+                name: name.to_string(),
+                target_oid: commit.id().to_string(),
+                is_annotated: annotated.is_some(),
+                tagger_name: tagger.as_ref().and_then(|s| s.name().map(String::from)),
+                tagger_email: tagger.as_ref().and_then(|s| s.email().map(String::from)),
+                date: tagger
+                    .as_ref()
+                    .map_or_else(|| commit.time().seconds(), |s| s.when().seconds()),
+                message: annotated
+                    .as_ref()
+                    .and_then(|tag| tag.message().map(|m| m.trim_end().to_string())),
+                version: version.as_ref().map(ToString::to_string),
+                is_prerelease: version.as_ref().is_some_and(SemVer::is_prerelease),
+            },
+        ));
+    }
+
+    tags.sort_by(|(va, a), (vb, b)| match (va, vb) {
+        (Some(va), Some(vb)) => vb.cmp(va).then_with(|| a.name.cmp(&b.name)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => b.date.cmp(&a.date).then_with(|| a.name.cmp(&b.name)),
+    });
+    Ok(tags.into_iter().map(|(_, tag)| tag).collect())
+}
+
+/// Latest release on the default branch (HEAD if there is none) and the
+/// commits and authors since.
+pub fn get_release_summary(path: &Path, include_prereleases: bool) -> Result<ReleaseSummary> {
+    let tags = list_tags(path)?;
+    let repo = Repository::open(path)?;
+
+    let (branch, tip) = match resolve_default_branch(&repo) {
+        Some(default) => (Some(default.name), Some(default.oid)),
+        None => match repo.head() {
+            Ok(head) => (head.shorthand().map(String::from), head.target()),
+            Err(_) => (None, None),
+        },
+    };
+    let Some(tip) = tip else {
+        return Ok(ReleaseSummary::default());
+    };
+
+    let latest_release = tags.into_iter().find(|tag| {
+        tag.version.is_some()
+            && (include_prereleases || !tag.is_prerelease)
+            && Oid::from_str(&tag.target_oid).is_ok_and(|target| {
+                target == tip || repo.graph_descendant_of(tip, target).unwrap_or(false)
+            })
+    });
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL)?;
+    revwalk.push(tip)?;
+    if let Some(release) = &latest_release {
+        revwalk.hide(Oid::from_str(&release.target_oid)?)?;
+    }
+
+    let mut commits_since = 0u32;
+    let mut authors: HashMap<String, AuthorCount> = HashMap::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        commits_since += 1;
+        let author = commit.author();
+        let email = author.email().unwrap_or_default().to_string();
+        authors
+            .entry(email.to_lowercase())
+            .or_insert_with(|| AuthorCount {
+                name: author.name().unwrap_or("Unknown").to_string(),
+                email,
+                commits: 0,
+            })
+            .commits += 1;
+    }
+
+    let mut authors_since: Vec<AuthorCount> = authors.into_values().collect();
+    authors_since.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(ReleaseSummary {
+        latest_release,
+        branch,
+        commits_since,
+        authors_since,
+    })
+}
@@ -149,4 +149,1985 @@ mod tests {
         
         assert_eq!(health.stash_count, 1);
     }
+
+    fn commit_all(path: &std::path::Path, message: &str) {
+        Command::new("git")
+            .args(["add", "-A"])
+            .current_dir(path)
+            .output()
+            .ok();
+        Command::new("git")
+            .args(["commit", "-m", message])
+            .current_dir(path)
+            .output()
+            .ok();
+    }
+
+    /// Minimal PNG header; enough for dimension sniffing.
+    fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+        let mut data = b"\x89PNG\r\n\x1a\n".to_vec();
+        data.extend_from_slice(&[0, 0, 0, 13]);
+        data.extend_from_slice(b"IHDR");
+        data.extend_from_slice(&width.to_be_bytes());
+        data.extend_from_slice(&height.to_be_bytes());
+        data.extend_from_slice(&[8, 6, 0, 0, 0, 0, 0, 0, 0]);
+        data
+    }
+
+    #[test]
+    fn test_binary_diff_image_between_revisions() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+
+        fs::write(temp.path().join("logo.png"), png_bytes(16, 16)).unwrap();
+        commit_all(temp.path(), "add logo");
+        let mut bigger = png_bytes(32, 24);
+        bigger.extend_from_slice(&[0; 100]);
+        fs::write(temp.path().join("logo.png"), &bigger).unwrap();
+        commit_all(temp.path(), "resize logo");
+
+        let diff = get_binary_diff(temp.path(), "logo.png", "HEAD~1", Some("HEAD")).unwrap();
+
+        assert!(diff.is_binary);
+        assert!(diff.is_image);
+        assert_eq!(diff.size_delta, 100);
+        let old = diff.old.unwrap();
+        let new = diff.new.unwrap();
+        assert_eq!(old.mime_type, "image/png");
+        let new_image = new.image.unwrap();
+        assert_eq!((new_image.width, new_image.height), (Some(32), Some(24)));
+        assert!(new_image.data_base64.is_some());
+        assert_eq!(old.image.unwrap().width, Some(16));
+    }
+
+    #[test]
+    fn test_binary_diff_against_working_tree() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+
+        fs::write(temp.path().join("icon.svg"), r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 48 32"></svg>"#).unwrap();
+        commit_all(temp.path(), "add icon");
+        fs::remove_file(temp.path().join("icon.svg")).unwrap();
+
+        let diff = get_binary_diff(temp.path(), "icon.svg", "HEAD", None).unwrap();
+
+        assert!(diff.new.is_none());
+        assert!(!diff.is_binary);
+        let image = diff.old.unwrap().image.unwrap();
+        assert_eq!((image.width, image.height), (Some(48), Some(32)));
+        assert!(diff.size_delta < 0);
+    }
+
+    #[test]
+    fn test_binary_diff_detects_lfs_pointer() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+
+        let pointer = "version https://git-lfs.github.com/spec/v1\n\
+            oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\n\
+            size 12345\n";
+        fs::write(temp.path().join("video.mp4"), pointer).unwrap();
+        commit_all(temp.path(), "add video");
+
+        let diff = get_binary_diff(temp.path(), "video.mp4", "HEAD", None).unwrap();
+
+        assert!(diff.is_lfs);
+        assert!(!diff.is_binary);
+        let lfs = diff.new.unwrap().lfs_pointer.unwrap();
+        assert_eq!(lfs.size, 12345);
+        assert!(lfs.oid.starts_with("sha256:"));
+    }
+
+    #[test]
+    fn test_binary_diff_missing_file() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+        fs::write(temp.path().join("README.md"), "# Test").unwrap();
+        commit_all(temp.path(), "init");
+
+        assert!(get_binary_diff(temp.path(), "nope.bin", "HEAD", None).is_err());
+    }
+
+    #[test]
+    fn test_binary_diff_rejects_paths_outside_repo() {
+        let temp = tempdir().unwrap();
+        let repo_path = temp.path().join("repo");
+        fs::create_dir_all(&repo_path).unwrap();
+        init_git_repo(&repo_path);
+        fs::write(repo_path.join("README.md"), "# Test").unwrap();
+        commit_all(&repo_path, "init");
+        let secret = temp.path().join("secret.bin");
+        fs::write(&secret, [0u8, 1, 2]).unwrap();
+
+        for file in ["../secret.bin", secret.to_str().unwrap()] {
+            let err = get_binary_diff(&repo_path, file, "HEAD", None).unwrap_err();
+            assert!(err.to_string().contains("Invalid path"));
+        }
+    }
+
+    fn head_oid(path: &std::path::Path) -> String {
+        let output = Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .current_dir(path)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    #[test]
+    fn test_blame_attributes_lines_to_commits() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+
+        fs::write(temp.path().join("lib.rs"), "one\ntwo\nthree\n").unwrap();
+        commit_all(temp.path(), "first");
+        let first = head_oid(temp.path());
+        fs::write(temp.path().join("lib.rs"), "one\nTWO\nthree\nfour\n").unwrap();
+        commit_all(temp.path(), "second");
+        let second = head_oid(temp.path());
+
+        let cache = BlameCache::new();
+        let options = BlameFileOptions::default();
+        let blame = get_file_blame(temp.path(), "lib.rs", "HEAD", &options, &cache).unwrap();
+
+        assert_eq!(blame.total_lines, 4);
+        let owners: Vec<(usize, &str)> = blame
+            .hunks
+            .iter()
+            .map(|h| (h.start_line, h.commit_oid.as_str()))
+            .collect();
+        assert_eq!(
+            owners,
+            vec![(1, first.as_str()), (2, second.as_str()), (3, first.as_str()), (4, second.as_str())]
+        );
+        assert_eq!(blame.hunks[1].summary, "second");
+        assert_eq!(blame.hunks[1].author_name, "Test User");
+
+        // Blaming an older revision only sees the first commit
+        let old = get_file_blame(temp.path(), "lib.rs", "HEAD~1", &options, &cache).unwrap();
+        assert_eq!(old.hunks.len(), 1);
+        assert_eq!(old.hunks[0].line_count, 3);
+    }
+
+    #[test]
+    fn test_blame_honours_ignore_revs_file() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+
+        fs::write(temp.path().join("lib.rs"), "a\nb\nc\n").unwrap();
+        commit_all(temp.path(), "original");
+        let original = head_oid(temp.path());
+        fs::write(temp.path().join("lib.rs"), "a\nB\nc\n").unwrap();
+        commit_all(temp.path(), "reformat");
+        let reformat = head_oid(temp.path());
+        fs::write(
+            temp.path().join(".git-blame-ignore-revs"),
+            format!("# formatting\n{reformat}\n"),
+        )
+        .unwrap();
+        commit_all(temp.path(), "ignore reformat");
+
+        let cache = BlameCache::new();
+        let blame =
+            get_file_blame(temp.path(), "lib.rs", "HEAD", &BlameFileOptions::default(), &cache)
+                .unwrap();
+        assert_eq!(blame.ignored_revs, 1);
+        assert!(blame.hunks.iter().all(|h| h.commit_oid == original));
+
+        let options = BlameFileOptions {
+            use_ignore_revs: false,
+            ..BlameFileOptions::default()
+        };
+        let blame = get_file_blame(temp.path(), "lib.rs", "HEAD", &options, &cache).unwrap();
+        assert!(blame.hunks.iter().any(|h| h.commit_oid == reformat));
+    }
+
+    #[test]
+    fn test_blame_line_range_and_cache() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+
+        let content: String = (1..=50).map(|i| format!("line {i}\n")).collect();
+        fs::write(temp.path().join("big.txt"), content).unwrap();
+        commit_all(temp.path(), "big file");
+
+        let cache = BlameCache::new();
+        let options = BlameFileOptions {
+            start_line: Some(11),
+            end_line: Some(20),
+            ..BlameFileOptions::default()
+        };
+        let blame = get_file_blame(temp.path(), "big.txt", "HEAD", &options, &cache).unwrap();
+
+        assert_eq!(blame.total_lines, 50);
+        assert!(!blame.from_cache);
+        assert_eq!(blame.hunks.len(), 1);
+        assert_eq!(blame.hunks[0].start_line, 11);
+        assert_eq!(blame.hunks[0].line_count, 10);
+
+        let again = get_file_blame(temp.path(), "big.txt", "HEAD", &options, &cache).unwrap();
+        assert!(again.from_cache);
+        assert_eq!(again.blob_oid, blame.blob_oid);
+    }
+
+    #[test]
+    fn test_blame_cache_distinguishes_reverted_content() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+
+        fs::write(temp.path().join("lib.rs"), "a\n").unwrap();
+        commit_all(temp.path(), "first");
+        fs::write(temp.path().join("lib.rs"), "b\n").unwrap();
+        commit_all(temp.path(), "change");
+        fs::write(temp.path().join("lib.rs"), "a\n").unwrap();
+        commit_all(temp.path(), "revert");
+        let revert = head_oid(temp.path());
+
+        let cache = BlameCache::new();
+        let options = BlameFileOptions::default();
+        let first = get_file_blame(temp.path(), "lib.rs", "HEAD~2", &options, &cache).unwrap();
+        let latest = get_file_blame(temp.path(), "lib.rs", "HEAD", &options, &cache).unwrap();
+
+        assert_eq!(first.blob_oid, latest.blob_oid);
+        assert!(!latest.from_cache);
+        assert_eq!(latest.hunks[0].commit_oid, revert);
+    }
+
+    #[test]
+    fn test_time_travel_lists_tree_with_last_commit() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+
+        fs::create_dir_all(temp.path().join("src")).unwrap();
+        fs::write(temp.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(temp.path().join("README.md"), "# Test\n").unwrap();
+        commit_all(temp.path(), "initial");
+        let initial = head_oid(temp.path());
+        fs::write(temp.path().join("README.md"), "# Test\n\nMore docs\n").unwrap();
+        commit_all(temp.path(), "docs");
+        let docs = head_oid(temp.path());
+
+        let root = list_tree_at(temp.path(), "HEAD", None).unwrap();
+        let names: Vec<&str> = root.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["src", "README.md"]);
+        assert_eq!(root[0].kind, TreeEntryKind::Directory);
+        assert_eq!(root[0].last_commit.as_ref().unwrap().oid, initial);
+        assert_eq!(root[1].mode, "100644");
+        assert_eq!(root[1].size, Some(18));
+        assert_eq!(root[1].last_commit.as_ref().unwrap().oid, docs);
+
+        let src = list_tree_at(temp.path(), "HEAD~1", Some("src")).unwrap();
+        assert_eq!(src.len(), 1);
+        assert_eq!(src[0].path, "src/main.rs");
+
+        assert!(list_tree_at(temp.path(), "HEAD", Some("missing")).is_err());
+    }
+
+    #[test]
+    fn test_time_travel_reads_old_file_without_checkout() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+
+        fs::write(temp.path().join("notes.txt"), "version one\n").unwrap();
+        commit_all(temp.path(), "v1");
+        fs::write(temp.path().join("notes.txt"), "version two\n").unwrap();
+        commit_all(temp.path(), "v2");
+        fs::write(temp.path().join("notes.txt"), "local edit\n").unwrap();
+
+        let file = read_file_at(temp.path(), "HEAD~1", "notes.txt", 1024).unwrap();
+        assert_eq!(file.content.as_deref(), Some("version one\n"));
+        assert_eq!(file.encoding.as_deref(), Some("utf-8"));
+        assert!(!file.truncated);
+
+        let capped = read_file_at(temp.path(), "HEAD", "notes.txt", 7).unwrap();
+        assert_eq!(capped.content.as_deref(), Some("version"));
+        assert!(capped.truncated);
+        assert_eq!(capped.size, 12);
+
+        // Working tree is left alone
+        let worktree = fs::read_to_string(temp.path().join("notes.txt")).unwrap();
+        assert_eq!(worktree, "local edit\n");
+    }
+
+    #[test]
+    fn test_time_travel_detects_encoding_and_binary() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+
+        fs::write(temp.path().join("latin.txt"), b"caf\xe9\n").unwrap();
+        fs::write(temp.path().join("blob.bin"), [0u8, 1, 2, 3]).unwrap();
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain("héllo\n".encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        fs::write(temp.path().join("utf16.txt"), utf16).unwrap();
+        commit_all(temp.path(), "encodings");
+
+        let latin = read_file_at(temp.path(), "HEAD", "latin.txt", 1024).unwrap();
+        assert_eq!(latin.encoding.as_deref(), Some("latin-1"));
+        assert_eq!(latin.content.as_deref(), Some("café\n"));
+
+        let utf16 = read_file_at(temp.path(), "HEAD", "utf16.txt", 1024).unwrap();
+        assert!(!utf16.is_binary);
+        assert_eq!(utf16.encoding.as_deref(), Some("utf-16le"));
+        assert_eq!(utf16.content.as_deref(), Some("héllo\n"));
+        assert_eq!(utf16.mime_type, "text/plain");
+
+        let binary = read_file_at(temp.path(), "HEAD", "blob.bin", 1024).unwrap();
+        assert!(binary.is_binary);
+        assert!(binary.content.is_none());
+    }
+
+    fn git(path: &std::path::Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(path)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    /// Create a bare `remote.git` with one commit on `main` and a `work` clone of it.
+    fn setup_remote_clone(root: &std::path::Path) -> (std::path::PathBuf, std::path::PathBuf) {
+        let seed = root.join("seed");
+        let bare = root.join("remote.git");
+        let work = root.join("work");
+        fs::create_dir_all(&seed).unwrap();
+        init_git_repo(&seed);
+        fs::write(seed.join("README.md"), "# Test").unwrap();
+        commit_all(&seed, "initial");
+        git(&seed, &["branch", "-M", "main"]);
+
+        git(root, &["init", "--bare", "remote.git"]);
+        git(&bare, &["symbolic-ref", "HEAD", "refs/heads/main"]);
+        git(&seed, &["push", bare.to_str().unwrap(), "main"]);
+
+        git(root, &["clone", bare.to_str().unwrap(), "work"]);
+        git(&work, &["config", "user.email", "test@test.com"]);
+        git(&work, &["config", "user.name", "Test User"]);
+        (bare, work)
+    }
+
+    #[test]
+    fn test_list_branches_with_upstream_and_merge_status() {
+        let temp = tempdir().unwrap();
+        let (_bare, work) = setup_remote_clone(temp.path());
+
+        // Merged feature branch, pushed and then deleted on the remote
+        git(&work, &["checkout", "-b", "merged-feature"]);
+        git(&work, &["push", "-u", "origin", "merged-feature"]);
+        git(&work, &["push", "origin", "--delete", "merged-feature"]);
+
+        // Unmerged branch with an unpushed commit
+        git(&work, &["checkout", "-b", "wip", "main"]);
+        git(&work, &["push", "-u", "origin", "wip"]);
+        fs::write(work.join("wip.txt"), "wip").unwrap();
+        commit_all(&work, "wip commit");
+        git(&work, &["checkout", "main"]);
+
+        let branches = list_branches(&work).unwrap();
+        let find = |name: &str| branches.iter().find(|b| b.name == name).unwrap();
+
+        let main = find("main");
+        assert!(main.is_head);
+        assert!(main.is_default);
+        assert_eq!(main.upstream.as_deref(), Some("origin/main"));
+        assert_eq!(main.last_author_name, "Test User");
+
+        let merged = find("merged-feature");
+        assert!(merged.is_merged);
+        assert!(merged.upstream_gone);
+
+        let wip = find("wip");
+        assert!(!wip.is_merged);
+        assert!(!wip.upstream_gone);
+        assert_eq!((wip.commits_ahead, wip.commits_behind), (1, 0));
+
+        let remote_main = find("origin/main");
+        assert!(remote_main.is_remote);
+        assert!(remote_main.is_default);
+        assert!(branches.iter().all(|b| b.name != "origin/HEAD"));
+    }
+
+    #[test]
+    fn test_merge_conflict_reported_in_health() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+        fs::write(temp.path().join("file.txt"), "base\n").unwrap();
+        commit_all(temp.path(), "base");
+        git(temp.path(), &["branch", "-M", "main"]);
+
+        git(temp.path(), &["checkout", "-b", "other"]);
+        fs::write(temp.path().join("file.txt"), "theirs\n").unwrap();
+        commit_all(temp.path(), "theirs");
+        git(temp.path(), &["checkout", "main"]);
+        fs::write(temp.path().join("file.txt"), "ours\n").unwrap();
+        commit_all(temp.path(), "ours");
+        git(temp.path(), &["merge", "other"]);
+
+        let health = get_repo_health(temp.path()).unwrap();
+
+        assert_eq!(health.state, RepoState::Merge);
+        assert_eq!(health.conflicted_count, 1);
+        assert_eq!(health.conflicted_files, vec!["file.txt".to_string()]);
+        assert_eq!(health.status(), RepoStatus::Conflicted);
+    }
+
+    #[test]
+    fn test_rebase_progress_reported_in_health() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+        fs::write(temp.path().join("file.txt"), "base\n").unwrap();
+        commit_all(temp.path(), "base");
+        git(temp.path(), &["branch", "-M", "main"]);
+
+        git(temp.path(), &["checkout", "-b", "feature"]);
+        fs::write(temp.path().join("file.txt"), "feature one\n").unwrap();
+        commit_all(temp.path(), "feature one");
+        fs::write(temp.path().join("other.txt"), "feature two\n").unwrap();
+        commit_all(temp.path(), "feature two");
+        git(temp.path(), &["checkout", "main"]);
+        fs::write(temp.path().join("file.txt"), "main change\n").unwrap();
+        commit_all(temp.path(), "main change");
+        git(temp.path(), &["checkout", "feature"]);
+        git(temp.path(), &["rebase", "main"]);
+
+        let health = get_repo_health(temp.path()).unwrap();
+
+        assert!(matches!(
+            health.state,
+            RepoState::Rebase | RepoState::RebaseInteractive | RepoState::RebaseMerge
+        ));
+        assert_eq!(
+            health.operation_progress,
+            Some(OperationProgress { current: 1, total: 2 })
+        );
+        assert_eq!(health.status(), RepoStatus::Conflicted);
+
+        // Resolving the conflict leaves the repo in progress, outranking dirty
+        fs::write(temp.path().join("file.txt"), "resolved\n").unwrap();
+        git(temp.path(), &["add", "file.txt"]);
+        let health = get_repo_health(temp.path()).unwrap();
+        assert_eq!(health.conflicted_count, 0);
+        assert_eq!(health.status(), RepoStatus::InProgress);
+    }
+
+    #[test]
+    fn test_status_breakdown_by_category() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+        fs::write(temp.path().join(".gitignore"), "*.bin\n").unwrap();
+        fs::write(temp.path().join("keep.txt"), "keep\n").unwrap();
+        fs::write(temp.path().join("edit.txt"), "edit\n").unwrap();
+        fs::write(temp.path().join("gone.txt"), "gone\n").unwrap();
+        fs::write(temp.path().join("move.txt"), "content that is long enough to detect a rename\n")
+            .unwrap();
+        commit_all(temp.path(), "initial");
+
+        fs::write(temp.path().join("edit.txt"), "edited\n").unwrap();
+        fs::remove_file(temp.path().join("gone.txt")).unwrap();
+        git(temp.path(), &["mv", "move.txt", "moved.txt"]);
+        fs::write(temp.path().join("scratch.txt"), "scratch\n").unwrap();
+        let big = vec![0u8; usize::try_from(LARGE_IGNORED_FILE_BYTES).unwrap()];
+        fs::write(temp.path().join("huge.bin"), &big).unwrap();
+        fs::write(temp.path().join("tiny.bin"), "x").unwrap();
+        // Inside an ignored directory
+        fs::create_dir_all(temp.path().join("target/debug")).unwrap();
+        fs::write(temp.path().join("target/debug/app.bin"), &big).unwrap();
+        fs::write(temp.path().join(".git/info/exclude"), "target/\n").unwrap();
+
+        let health = get_repo_health(temp.path()).unwrap();
+        let b = health.breakdown;
+
+        assert_eq!(b.untracked, 1);
+        assert_eq!(b.modified, 1);
+        assert_eq!(b.deleted, 1);
+        assert_eq!(b.renamed, 1);
+        assert_eq!(b.ignored_large, 2);
+        assert_eq!(b.conflicted, 0);
+    }
+
+    #[test]
+    fn test_file_statuses_include_rename_source() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+        fs::write(temp.path().join("old.txt"), "content that is long enough to detect a rename\n")
+            .unwrap();
+        fs::write(temp.path().join("both.txt"), "v1\n").unwrap();
+        commit_all(temp.path(), "initial");
+
+        git(temp.path(), &["mv", "old.txt", "new.txt"]);
+        fs::write(temp.path().join("both.txt"), "v2\n").unwrap();
+        git(temp.path(), &["add", "both.txt"]);
+        fs::write(temp.path().join("both.txt"), "v3\n").unwrap();
+
+        let entries = get_file_statuses(temp.path(), false).unwrap();
+
+        let renamed = entries.iter().find(|e| e.path == "new.txt").unwrap();
+        assert_eq!(renamed.old_path.as_deref(), Some("old.txt"));
+        assert_eq!(renamed.index_status, Some(FileChange::Renamed));
+        assert_eq!(renamed.worktree_status, None);
+
+        let both = entries.iter().find(|e| e.path == "both.txt").unwrap();
+        assert_eq!(both.index_status, Some(FileChange::Modified));
+        assert_eq!(both.worktree_status, Some(FileChange::Modified));
+    }
+
+    #[test]
+    fn test_health_against_remote_default_and_unpushed_branches() {
+        let temp = tempdir().unwrap();
+        let (_bare, work) = setup_remote_clone(temp.path());
+
+        // Feature branch without upstream, two commits ahead of origin/main
+        git(&work, &["checkout", "-b", "feature"]);
+        fs::write(work.join("a.txt"), "a").unwrap();
+        commit_all(&work, "a");
+        fs::write(work.join("b.txt"), "b").unwrap();
+        commit_all(&work, "b");
+
+        // Pushed branch with nothing local
+        git(&work, &["branch", "pushed", "main"]);
+        git(&work, &["push", "origin", "pushed"]);
+
+        let health = get_repo_health(&work).unwrap();
+
+        assert_eq!(health.default_branch.as_deref(), Some("origin/main"));
+        assert_eq!((health.ahead_of_default, health.behind_default), (2, 0));
+        assert_eq!((health.commits_ahead, health.commits_behind), (0, 0));
+        assert_eq!(health.unpushed_branch_count, 1);
+        assert_eq!(health.unpushed_branches, vec!["feature".to_string()]);
+    }
+
+
+    #[test]
+    fn test_light_mode_skips_untracked_recursion_and_ignored() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+        fs::write(temp.path().join(".gitignore"), "*.bin\n").unwrap();
+        commit_all(temp.path(), "initial");
+
+        fs::create_dir_all(temp.path().join("build/out")).unwrap();
+        for name in ["a.o", "b.o", "c.o"] {
+            fs::write(temp.path().join("build/out").join(name), "obj").unwrap();
+        }
+        let big = vec![0u8; usize::try_from(LARGE_IGNORED_FILE_BYTES).unwrap()];
+        fs::write(temp.path().join("huge.bin"), big).unwrap();
+
+        let full = get_repo_health(temp.path()).unwrap();
+        assert_eq!(full.breakdown.untracked, 3);
+        assert_eq!(full.breakdown.ignored_large, 1);
+        assert!(!full.is_light);
+
+        let options = HealthOptions {
+            light: true,
+            budget_ms: None,
+        };
+        let light = get_repo_health_with(temp.path(), &options).unwrap();
+        assert_eq!(light.breakdown.untracked, 1);
+        assert_eq!(light.breakdown.ignored_large, 0);
+        assert!(light.is_dirty);
+        assert!(light.is_light);
+        assert!(!light.is_partial);
+    }
+
+    #[test]
+    fn test_exhausted_budget_returns_partial_health() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+        fs::write(temp.path().join("file.txt"), "content\n").unwrap();
+        commit_all(temp.path(), "initial");
+
+        let options = HealthOptions {
+            light: false,
+            budget_ms: Some(0),
+        };
+        let health = get_repo_health_with(temp.path(), &options).unwrap();
+        assert!(health.is_partial);
+        assert_eq!(health.status(), RepoStatus::Partial);
+        assert_eq!(health.current_branch, get_repo_health(temp.path()).unwrap().current_branch);
+
+        let generous = HealthOptions {
+            light: false,
+            budget_ms: Some(60_000),
+        };
+        assert!(!get_repo_health_with(temp.path(), &generous).unwrap().is_partial);
+    }
+
+
+    #[test]
+    fn test_missing_repository_is_unavailable() {
+        let temp = tempdir().unwrap();
+        let options = HealthOptions::default();
+
+        let gone = check_repo_health(&temp.path().join("unmounted"), &options);
+        assert_eq!(gone.error.as_ref().unwrap().kind, HealthErrorKind::MissingPath);
+        assert_eq!(gone.status(), RepoStatus::Unavailable);
+
+        // Directory still there, repository removed
+        let not_repo = check_repo_health(temp.path(), &options);
+        assert_eq!(not_repo.error.unwrap().kind, HealthErrorKind::MissingPath);
+    }
+
+    #[test]
+    fn test_corrupt_index_is_error_not_clean() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+        fs::write(temp.path().join("file.txt"), "content\n").unwrap();
+        commit_all(temp.path(), "initial");
+
+        fs::write(temp.path().join(".git/index"), b"DIRC garbage").unwrap();
+
+        let health = check_repo_health(temp.path(), &HealthOptions::default());
+        assert_eq!(health.error.as_ref().unwrap().kind, HealthErrorKind::CorruptRepository);
+        assert_eq!(health.status(), RepoStatus::Error);
+    }
+
+    #[test]
+    fn test_stale_index_lock_is_reported() {
+        let temp = tempdir().unwrap();
+        init_git_repo(temp.path());
+        fs::write(temp.path().join("file.txt"), "content\n").unwrap();
+        commit_all(temp.path(), "initial");
+
+        let lock = fs::File::create(temp.path().join(".git/index.lock")).unwrap();
+        assert!(check_repo_health(temp.path(), &HealthOptions::default()).error.is_none());
+
+        let old = std::time::SystemTime::now()
+            - std::time::Duration::from_secs(STALE_INDEX_LOCK_SECS + 5);
+        lock.set_modified(old).unwrap();
+
+        let health = check_repo_health(temp.path(), &HealthOptions::default());
+        assert_eq!(health.error.as_ref().unwrap().kind, HealthErrorKind::LockedIndex);
+        assert_eq!(health.current_branch, get_repo_health(temp.path()).unwrap().current_branch);
+        assert_eq!(health.status(), RepoStatus::Error);
+    }
+
+
+    fn push_from_seed(root: &std::path::Path, file: &str, args: &[&str]) {
+        let seed = root.join("seed");
+        fs::write(seed.join(file), file).unwrap();
+        commit_all(&seed, file);
+        let bare = root.join("remote.git");
+        let mut push = vec!["push", bare.to_str().unwrap()];
+        push.extend_from_slice(args);
+        git(&seed, &push);
+    }
+
+    #[test]
+    fn test_fetch_over_file_url_updates_refs_and_reports_progress() {
+        let temp = tempdir().unwrap();
+        let (bare, work) = setup_remote_clone(temp.path());
+        let url = format!("file://{}", bare.display());
+        git(&work, &["remote", "set-url", "origin", &url]);
+
+        push_from_seed(temp.path(), "new.txt", &["main"]);
+
+        let mut progress = Vec::new();
+        let result = fetch_repo(&work, &FetchOptions::default(), |p| progress.push(p.clone())).unwrap();
+
+        assert!(!result.timed_out);
+        assert!(result.error().is_none());
+        assert_eq!(result.remotes.len(), 1);
+        assert_eq!(result.remotes[0].url.as_deref(), Some(url.as_str()));
+        let update = result.remotes[0]
+            .updated_refs
+            .iter()
+            .find(|u| u.ref_name == "refs/remotes/origin/main")
+            .unwrap();
+        assert!(update.old_oid.is_some() && update.new_oid.is_some());
+        assert!(progress.iter().all(|p| p.remote == "origin"));
+
+        assert_eq!(get_repo_health(&work).unwrap().commits_behind, 1);
+    }
+
+    #[test]
+    fn test_fetch_prunes_deleted_remote_branches() {
+        let temp = tempdir().unwrap();
+        let (bare, work) = setup_remote_clone(temp.path());
+
+        push_from_seed(temp.path(), "topic.txt", &["HEAD:refs/heads/topic"]);
+        fetch_repo(&work, &FetchOptions::default(), |_| {}).unwrap();
+        assert!(git(&work, &["branch", "-r"]).contains("origin/topic"));
+
+        git(&bare, &["branch", "-D", "topic"]);
+
+        let keep = FetchOptions {
+            prune: false,
+            ..FetchOptions::default()
+        };
+        fetch_repo(&work, &keep, |_| {}).unwrap();
+        assert!(git(&work, &["branch", "-r"]).contains("origin/topic"));
+
+        fetch_repo(&work, &FetchOptions::default(), |_| {}).unwrap();
+        assert!(!git(&work, &["branch", "-r"]).contains("origin/topic"));
+    }
+
+    #[test]
+    fn test_fetch_timeout_and_unknown_remote() {
+        let temp = tempdir().unwrap();
+        let (_, work) = setup_remote_clone(temp.path());
+
+        let expired = FetchOptions {
+            timeout_secs: Some(0),
+            ..FetchOptions::default()
+        };
+        let result = fetch_repo(&work, &expired, |_| {}).unwrap();
+        assert!(result.timed_out);
+        assert!(result.error().is_some());
+
+        let unknown = FetchOptions {
+            remotes: vec!["nope".to_string()],
+            ..FetchOptions::default()
+        };
+        let result = fetch_repo(&work, &unknown, |_| {}).unwrap();
+        assert!(!result.timed_out);
+        assert!(result.remotes[0].error.is_some());
+    }
+
+    #[test]
+    fn test_fetch_times_out_on_silent_server() {
+        let temp = tempdir().unwrap();
+        let (_, work) = setup_remote_clone(temp.path());
+        // Accepts connections but never answers, so no callback ever runs
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("git://{}/repo.git", listener.local_addr().unwrap());
+        git(&work, &["remote", "set-url", "origin", &url]);
+
+        let options = FetchOptions {
+            timeout_secs: Some(1),
+            ..FetchOptions::default()
+        };
+        let started = std::time::Instant::now();
+        let result = fetch_repo(&work, &options, |_| {}).unwrap();
+        assert!(result.timed_out);
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    }
+
+
+    fn file_remote_clone(root: &std::path::Path) -> (std::path::PathBuf, std::path::PathBuf) {
+        let (bare, work) = setup_remote_clone(root);
+        let url = format!("file://{}", bare.display());
+        git(&work, &["remote", "set-url", "origin", &url]);
+        (bare, work)
+    }
+
+    #[test]
+    fn test_sync_fast_forwards_clean_repo() {
+        let temp = tempdir().unwrap();
+        let (_bare, work) = file_remote_clone(temp.path());
+        let before = head_oid(&work);
+        push_from_seed(temp.path(), "one.txt", &["main"]);
+        push_from_seed(temp.path(), "two.txt", &["main"]);
+
+        let outcome = sync_repo(&work, &SyncOptions::default());
+        let SyncOutcome::Updated { branch, from, to, commits, stashed, .. } = outcome else {
+            panic!("expected update, got {outcome:?}");
+        };
+        assert_eq!(branch, "main");
+        assert_eq!(from, before);
+        assert_eq!(to, head_oid(&work));
+        assert_eq!(commits, 2);
+        assert!(!stashed);
+        assert!(work.join("two.txt").exists());
+        assert!(git(&work, &["status", "--porcelain"]).is_empty());
+
+        assert_eq!(
+            sync_repo(&work, &SyncOptions::default()),
+            SyncOutcome::UpToDate { branch: "main".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_sync_skips_dirty_repo_unless_autostash() {
+        let temp = tempdir().unwrap();
+        let (_bare, work) = file_remote_clone(temp.path());
+        let before = head_oid(&work);
+        push_from_seed(temp.path(), "one.txt", &["main"]);
+        fs::write(work.join("README.md"), "# Local edit").unwrap();
+
+        let outcome = sync_repo(&work, &SyncOptions::default());
+        assert!(matches!(outcome, SyncOutcome::Skipped { .. }), "{outcome:?}");
+        assert_eq!(head_oid(&work), before);
+
+        let options = SyncOptions { autostash: true, ..SyncOptions::default() };
+        let outcome = sync_repo(&work, &options);
+        assert!(
+            matches!(outcome, SyncOutcome::Updated { stashed: true, stash_conflict: false, .. }),
+            "{outcome:?}"
+        );
+        assert_eq!(fs::read_to_string(work.join("README.md")).unwrap(), "# Local edit");
+        assert!(work.join("one.txt").exists());
+        assert!(git(&work, &["stash", "list"]).is_empty());
+    }
+
+    #[test]
+    fn test_sync_skips_diverged_detached_and_untracked_branches() {
+        let temp = tempdir().unwrap();
+        let (_bare, work) = file_remote_clone(temp.path());
+        push_from_seed(temp.path(), "one.txt", &["main"]);
+
+        // Local-only branch without upstream
+        git(&work, &["checkout", "-b", "local-only"]);
+        let outcome = sync_repo(&work, &SyncOptions::default());
+        assert_eq!(outcome, SyncOutcome::Skipped { reason: "branch has no upstream".to_string() });
+
+        // Detached HEAD
+        git(&work, &["checkout", "--detach"]);
+        let outcome = sync_repo(&work, &SyncOptions::default());
+        assert_eq!(outcome, SyncOutcome::Skipped { reason: "HEAD is detached".to_string() });
+
+        // Diverged from upstream
+        git(&work, &["checkout", "main"]);
+        fs::write(work.join("local.txt"), "local").unwrap();
+        commit_all(&work, "local commit");
+        let before = head_oid(&work);
+        let outcome = sync_repo(&work, &SyncOptions::default());
+        assert!(matches!(outcome, SyncOutcome::Skipped { .. }), "{outcome:?}");
+        assert_eq!(head_oid(&work), before);
+    }
+
+    #[test]
+    fn test_sync_reports_fetch_failure() {
+        let temp = tempdir().unwrap();
+        let (_bare, work) = file_remote_clone(temp.path());
+        git(&work, &["remote", "set-url", "origin", "file:///nonexistent/remote.git"]);
+
+        let outcome = sync_repo(&work, &SyncOptions::default());
+        assert!(matches!(outcome, SyncOutcome::Failed { .. }), "{outcome:?}");
+    }
+
+
+    #[test]
+    fn test_push_current_branch_and_report_up_to_date() {
+        let temp = tempdir().unwrap();
+        let (bare, work) = file_remote_clone(temp.path());
+        fs::write(work.join("local.txt"), "local").unwrap();
+        commit_all(&work, "local commit");
+
+        let result = push_repo(&work, &PushOptions::default()).unwrap();
+        assert_eq!(result.refs.len(), 1);
+        let pushed = &result.refs[0];
+        assert_eq!(pushed.status, RefPushStatus::Pushed);
+        assert_eq!(pushed.remote_ref, "refs/heads/main");
+        assert_eq!(pushed.new_oid, head_oid(&work));
+        assert_eq!(git(&bare, &["rev-parse", "main"]).trim(), head_oid(&work));
+        // Remote-tracking branch follows the push
+        assert_eq!(git(&work, &["rev-parse", "origin/main"]).trim(), head_oid(&work));
+
+        let again = push_repo(&work, &PushOptions::default()).unwrap();
+        assert_eq!(again.refs[0].status, RefPushStatus::UpToDate);
+        assert!(again.error().is_none());
+    }
+
+    #[test]
+    fn test_push_new_branch_sets_upstream() {
+        let temp = tempdir().unwrap();
+        let (bare, work) = file_remote_clone(temp.path());
+        git(&work, &["checkout", "-b", "feature"]);
+        fs::write(work.join("feature.txt"), "feature").unwrap();
+        commit_all(&work, "feature");
+
+        let result = push_repo(&work, &PushOptions::default()).unwrap();
+        let pushed = &result.refs[0];
+        assert_eq!(pushed.status, RefPushStatus::Created);
+        assert!(pushed.old_oid.is_none());
+        assert!(pushed.upstream_set);
+        assert_eq!(git(&bare, &["rev-parse", "feature"]).trim(), head_oid(&work));
+        assert_eq!(
+            git(&work, &["rev-parse", "--abbrev-ref", "feature@{upstream}"]).trim(),
+            "origin/feature"
+        );
+
+        git(&work, &["checkout", "-b", "scratch"]);
+        let options = PushOptions {
+            set_upstream: false,
+            ..PushOptions::default()
+        };
+        let result = push_repo(&work, &options).unwrap();
+        assert_eq!(result.refs[0].status, RefPushStatus::Created);
+        assert!(!result.refs[0].upstream_set);
+        assert!(git(&work, &["rev-parse", "--abbrev-ref", "scratch@{upstream}"]).is_empty());
+    }
+
+    #[test]
+    fn test_push_rejects_non_fast_forward_and_honours_lease() {
+        let temp = tempdir().unwrap();
+        let (bare, work) = file_remote_clone(temp.path());
+        fs::write(work.join("local.txt"), "local").unwrap();
+        commit_all(&work, "local commit");
+        push_from_seed(temp.path(), "remote.txt", &["main"]);
+        let remote_tip = git(&bare, &["rev-parse", "main"]).trim().to_string();
+
+        let result = push_repo(&work, &PushOptions::default()).unwrap();
+        assert_eq!(result.refs[0].status, RefPushStatus::RejectedNonFastForward);
+        assert!(result.error().is_some());
+
+        // The remote moved after our last fetch, so the lease does not hold
+        let lease = PushOptions {
+            force_with_lease: true,
+            ..PushOptions::default()
+        };
+        let result = push_repo(&work, &lease).unwrap();
+        assert_eq!(result.refs[0].status, RefPushStatus::RejectedStaleLease);
+        assert_eq!(git(&bare, &["rev-parse", "main"]).trim(), remote_tip);
+
+        // After fetching, the lease matches and the remote is overwritten
+        git(&work, &["fetch", "origin"]);
+        let result = push_repo(&work, &lease).unwrap();
+        assert_eq!(result.refs[0].status, RefPushStatus::Pushed);
+        assert_eq!(result.refs[0].old_oid.as_deref(), Some(remote_tip.as_str()));
+        assert_eq!(git(&bare, &["rev-parse", "main"]).trim(), head_oid(&work));
+    }
+
+    #[test]
+    fn test_push_classifies_remote_rejections() {
+        assert_eq!(
+            classify_rejection("pre-receive hook declined"),
+            RefPushStatus::HookDeclined
+        );
+        assert_eq!(
+            classify_rejection("cannot push non-fast-forwardable reference"),
+            RefPushStatus::RejectedNonFastForward
+        );
+        assert_eq!(classify_rejection("stale info"), RefPushStatus::RejectedStaleLease);
+        assert_eq!(classify_rejection("funny refname"), RefPushStatus::Rejected);
+    }
+
+
+    fn stash_fixture(path: &std::path::Path) {
+        init_git_repo(path);
+        fs::write(path.join("README.md"), "# Test\n").unwrap();
+        commit_all(path, "initial");
+        git(path, &["branch", "-M", "main"]);
+    }
+
+    #[test]
+    fn test_list_stashes_with_branch_files_and_untracked() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        fs::write(temp.path().join("README.md"), "# Test\nmore\n").unwrap();
+        fs::write(temp.path().join("scratch.txt"), "scratch\n").unwrap();
+        git(temp.path(), &["stash", "push", "-u", "-m", "work in progress"]);
+
+        let stashes = list_stashes(temp.path()).unwrap();
+        assert_eq!(stashes.len(), 1);
+        let stash = &stashes[0];
+        assert_eq!(stash.index, 0);
+        assert_eq!(stash.message, "On main: work in progress");
+        assert_eq!(stash.branch.as_deref(), Some("main"));
+        assert!(stash.has_untracked);
+        assert!(stash.date > 0);
+
+        let readme = stash.files.iter().find(|f| f.path == "README.md").unwrap();
+        assert_eq!(readme.status, "modified");
+        let scratch = stash.files.iter().find(|f| f.path == "scratch.txt").unwrap();
+        assert_eq!(scratch.status, "untracked");
+        assert_eq!((stash.additions, stash.deletions), (2, 0));
+
+        let diff = get_stash_diff(temp.path(), 0, Some(&stash.oid)).unwrap();
+        let readme = diff.iter().find(|p| p.change.path == "README.md").unwrap();
+        assert_eq!((readme.additions, readme.deletions), (1, 0));
+        assert!(readme.patch.contains("+more"));
+
+        assert!(stashes_older_than(temp.path(), stash.date).unwrap().is_empty());
+        assert_eq!(stashes_older_than(temp.path(), stash.date + 1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_create_apply_pop_and_drop_stash() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+
+        let err = create_stash(temp.path(), &StashCreateOptions::default()).unwrap_err();
+        assert!(matches!(err, crate::error::DevBaseError::Config { .. }));
+
+        fs::write(temp.path().join("README.md"), "# Changed\n").unwrap();
+        fs::write(temp.path().join("new.txt"), "new\n").unwrap();
+        let options = StashCreateOptions {
+            message: Some("saved".to_string()),
+            include_untracked: true,
+            ..StashCreateOptions::default()
+        };
+        let stash = create_stash(temp.path(), &options).unwrap();
+        assert!(stash.message.ends_with("saved"));
+        assert!(stash.has_untracked);
+        assert!(!temp.path().join("new.txt").exists());
+
+        // A stale OID is refused
+        let err = apply_stash(temp.path(), 0, Some("0000000"), false).unwrap_err();
+        assert!(matches!(err, crate::error::DevBaseError::Config { .. }));
+
+        let applied = apply_stash(temp.path(), 0, Some(&stash.oid), false).unwrap();
+        assert!(applied.applied && !applied.dropped && applied.conflicts.is_empty());
+        assert!(temp.path().join("new.txt").exists());
+        assert_eq!(list_stashes(temp.path()).unwrap().len(), 1);
+
+        git(temp.path(), &["checkout", "--", "."]);
+        fs::remove_file(temp.path().join("new.txt")).unwrap();
+        let popped = pop_stash(temp.path(), 0, None, false).unwrap();
+        assert!(popped.applied && popped.dropped);
+        assert_eq!(fs::read_to_string(temp.path().join("README.md")).unwrap(), "# Changed\n");
+        assert!(list_stashes(temp.path()).unwrap().is_empty());
+
+        let stash = create_stash(temp.path(), &options).unwrap();
+        drop_stash(temp.path(), 0, Some(&stash.oid)).unwrap();
+        assert!(list_stashes(temp.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_stash_keep_index() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        fs::write(temp.path().join("staged.txt"), "staged\n").unwrap();
+        git(temp.path(), &["add", "staged.txt"]);
+        fs::write(temp.path().join("README.md"), "# Unstaged\n").unwrap();
+
+        let options = StashCreateOptions {
+            keep_index: true,
+            ..StashCreateOptions::default()
+        };
+        create_stash(temp.path(), &options).unwrap();
+        assert!(temp.path().join("staged.txt").exists());
+        assert_eq!(fs::read_to_string(temp.path().join("README.md")).unwrap(), "# Test\n");
+    }
+
+    #[test]
+    fn test_pop_stash_reports_conflicts_and_keeps_stash() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        fs::write(temp.path().join("README.md"), "# Stashed\n").unwrap();
+        git(temp.path(), &["stash"]);
+        fs::write(temp.path().join("README.md"), "# Committed\n").unwrap();
+        commit_all(temp.path(), "conflicting change");
+
+        let result = pop_stash(temp.path(), 0, None, false).unwrap();
+        assert!(result.applied);
+        assert_eq!(result.conflicts, vec!["README.md".to_string()]);
+        assert!(!result.dropped);
+        assert_eq!(list_stashes(temp.path()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_apply_stash_refuses_to_overwrite_local_changes() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        fs::write(temp.path().join("README.md"), "# Stashed\n").unwrap();
+        git(temp.path(), &["stash"]);
+        fs::write(temp.path().join("README.md"), "# Local\n").unwrap();
+
+        let result = apply_stash(temp.path(), 0, None, false).unwrap();
+        assert!(!result.applied);
+        assert!(result.message.is_some());
+        assert_eq!(fs::read_to_string(temp.path().join("README.md")).unwrap(), "# Local\n");
+    }
+
+
+    fn numbered_lines(count: usize) -> String {
+        (1..=count).map(|i| format!("line {i}\n")).collect()
+    }
+
+    fn staged_content(path: &std::path::Path, file: &str) -> String {
+        let output = Command::new("git")
+            .args(["show", &format!(":{file}")])
+            .current_dir(path)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[test]
+    fn test_stage_and_unstage_whole_files() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        fs::write(temp.path().join("extra.txt"), "extra\n").unwrap();
+        commit_all(temp.path(), "add extra");
+
+        fs::write(temp.path().join("README.md"), "# Changed\n").unwrap();
+        fs::write(temp.path().join("new.txt"), "new\n").unwrap();
+        fs::remove_file(temp.path().join("extra.txt")).unwrap();
+        let files = ["README.md", "new.txt", "extra.txt"].map(String::from);
+
+        stage_files(temp.path(), &files).unwrap();
+        let staged = git(temp.path(), &["diff", "--cached", "--name-status"]);
+        assert!(staged.contains("M\tREADME.md"));
+        assert!(staged.contains("A\tnew.txt"));
+        assert!(staged.contains("D\textra.txt"));
+
+        unstage_files(temp.path(), &files).unwrap();
+        assert!(git(temp.path(), &["diff", "--cached", "--name-status"]).is_empty());
+        // The working tree is left alone
+        assert_eq!(fs::read_to_string(temp.path().join("README.md")).unwrap(), "# Changed\n");
+    }
+
+    #[test]
+    fn test_stage_and_unstage_single_hunks() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        fs::write(temp.path().join("list.txt"), numbered_lines(12)).unwrap();
+        commit_all(temp.path(), "add list");
+
+        let changed = numbered_lines(12)
+            .replace("line 1\n", "line one\n")
+            .replace("line 12\n", "line twelve\n");
+        fs::write(temp.path().join("list.txt"), &changed).unwrap();
+
+        let diff = get_file_diff(temp.path(), "list.txt", false).unwrap();
+        assert_eq!(diff.hunks.len(), 2);
+        assert!(!diff.is_binary);
+
+        stage_selection(temp.path(), "list.txt", &PatchSelection::Hunks { hunks: vec![1] }).unwrap();
+        let expected = numbered_lines(12).replace("line 12\n", "line twelve\n");
+        assert_eq!(staged_content(temp.path(), "list.txt"), expected);
+        assert_eq!(fs::read_to_string(temp.path().join("list.txt")).unwrap(), changed);
+
+        stage_files(temp.path(), &["list.txt".to_string()]).unwrap();
+        let staged = get_file_diff(temp.path(), "list.txt", true).unwrap();
+        assert_eq!(staged.hunks.len(), 2);
+        unstage_selection(temp.path(), "list.txt", &PatchSelection::Hunks { hunks: vec![0] }).unwrap();
+        assert_eq!(staged_content(temp.path(), "list.txt"), expected);
+
+        let err = stage_selection(temp.path(), "list.txt", &PatchSelection::Hunks { hunks: vec![5] })
+            .unwrap_err();
+        assert!(matches!(err, crate::error::DevBaseError::Config { .. }));
+    }
+
+    #[test]
+    fn test_stage_and_unstage_line_ranges() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        fs::write(temp.path().join("list.txt"), numbered_lines(3)).unwrap();
+        commit_all(temp.path(), "add list");
+
+        fs::write(temp.path().join("list.txt"), "line 1\nfirst\nsecond\nline 3\n").unwrap();
+        let diff = get_file_diff(temp.path(), "list.txt", false).unwrap();
+        let lines = &diff.hunks[0].lines;
+        let pick = |origin: &str, content: &str| {
+            let line = lines
+                .iter()
+                .find(|l| l.origin == origin && l.content == content)
+                .unwrap();
+            LineRef { old_lineno: line.old_lineno, new_lineno: line.new_lineno }
+        };
+
+        // Stage the removal of "line 2" and the addition of "second" only
+        let selection = PatchSelection::Lines {
+            lines: vec![pick("-", "line 2\n"), pick("+", "second\n")],
+        };
+        stage_selection(temp.path(), "list.txt", &selection).unwrap();
+        assert_eq!(staged_content(temp.path(), "list.txt"), "line 1\nsecond\nline 3\n");
+
+        // Unstage the addition again, keeping the removal staged
+        let staged = get_file_diff(temp.path(), "list.txt", true).unwrap();
+        let added = staged.hunks[0].lines.iter().find(|l| l.origin == "+").unwrap();
+        let selection = PatchSelection::Lines {
+            lines: vec![LineRef { old_lineno: added.old_lineno, new_lineno: added.new_lineno }],
+        };
+        unstage_selection(temp.path(), "list.txt", &selection).unwrap();
+        assert_eq!(staged_content(temp.path(), "list.txt"), "line 1\nline 3\n");
+    }
+
+    #[test]
+    fn test_stage_lines_of_untracked_file() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        fs::write(temp.path().join("new.txt"), "keep\nskip\n").unwrap();
+
+        let diff = get_file_diff(temp.path(), "new.txt", false).unwrap();
+        let keep = diff.hunks[0].lines.iter().find(|l| l.content == "keep\n").unwrap();
+        let selection = PatchSelection::Lines {
+            lines: vec![LineRef { old_lineno: keep.old_lineno, new_lineno: keep.new_lineno }],
+        };
+        stage_selection(temp.path(), "new.txt", &selection).unwrap();
+        assert_eq!(staged_content(temp.path(), "new.txt"), "keep\n");
+
+        let staged = get_file_diff(temp.path(), "new.txt", true).unwrap();
+        unstage_selection(temp.path(), "new.txt", &PatchSelection::Hunks { hunks: vec![0] }).unwrap();
+        assert_eq!(staged.hunks.len(), 1);
+        assert!(!git(temp.path(), &["ls-files"]).contains("new.txt"));
+    }
+
+    #[test]
+    fn test_discard_changes_keeps_recoverable_backup() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        fs::write(temp.path().join("README.md"), "# Precious\n").unwrap();
+        fs::create_dir_all(temp.path().join("notes")).unwrap();
+        fs::write(temp.path().join("notes/todo.txt"), "todo\n").unwrap();
+
+        let files = ["README.md", "notes/todo.txt"].map(String::from);
+        let backup = discard_changes(temp.path(), &files).unwrap();
+        assert!(backup.ref_name.starts_with(DISCARD_REF_PREFIX));
+        assert_eq!(backup.files, files.to_vec());
+        assert_eq!(fs::read_to_string(temp.path().join("README.md")).unwrap(), "# Test\n");
+        assert!(!temp.path().join("notes/todo.txt").exists());
+
+        let backups = list_discard_backups(temp.path()).unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].oid, backup.oid);
+
+        let restored = restore_discard_backup(temp.path(), &backup.ref_name).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(fs::read_to_string(temp.path().join("README.md")).unwrap(), "# Precious\n");
+        assert_eq!(fs::read_to_string(temp.path().join("notes/todo.txt")).unwrap(), "todo\n");
+
+        assert!(restore_discard_backup(temp.path(), "refs/heads/main").is_err());
+    }
+
+    #[test]
+    fn test_restore_discard_backup_refuses_newer_changes() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        fs::write(temp.path().join("README.md"), "# Precious\n").unwrap();
+        let backup = discard_changes(temp.path(), &["README.md".to_string()]).unwrap();
+
+        fs::write(temp.path().join("README.md"), "# Newer\n").unwrap();
+        match restore_discard_backup(temp.path(), &backup.ref_name) {
+            Err(crate::error::DevBaseError::LocalChanges { files }) => assert_eq!(files, ["README.md"]),
+            other => panic!("expected local changes, got {other:?}"),
+        }
+        assert_eq!(fs::read_to_string(temp.path().join("README.md")).unwrap(), "# Newer\n");
+
+        // Content that already matches the backup is not newer work
+        fs::write(temp.path().join("README.md"), "# Precious\n").unwrap();
+        restore_discard_backup(temp.path(), &backup.ref_name).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_restore_discard_backup_keeps_executable_bit() {
+        use std::os::unix::fs::PermissionsExt;
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        let script = temp.path().join("run.sh");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let backup = discard_changes(temp.path(), &["run.sh".to_string()]).unwrap();
+        assert!(!script.exists());
+        restore_discard_backup(temp.path(), &backup.ref_name).unwrap();
+        assert_ne!(fs::metadata(&script).unwrap().permissions().mode() & 0o111, 0);
+    }
+
+    #[test]
+    fn test_discard_changes_rejects_paths_outside_repo() {
+        let temp = tempdir().unwrap();
+        let repo_path = temp.path().join("repo");
+        fs::create_dir_all(&repo_path).unwrap();
+        stash_fixture(&repo_path);
+        let outside = temp.path().join("outside.txt");
+        fs::write(&outside, "keep\n").unwrap();
+
+        for file in ["../outside.txt", outside.to_str().unwrap(), ".git/config"] {
+            assert!(discard_changes(&repo_path, &[file.to_string()]).is_err());
+        }
+        assert!(outside.exists());
+        assert!(repo_path.join(".git/config").exists());
+
+        // Ignored files are not untracked changes and are left alone
+        fs::write(repo_path.join(".gitignore"), "*.log\n").unwrap();
+        fs::write(repo_path.join("build.log"), "log\n").unwrap();
+        discard_changes(&repo_path, &["build.log".to_string()]).unwrap();
+        assert!(repo_path.join("build.log").exists());
+    }
+
+    #[cfg(unix)]
+    fn install_hook(path: &std::path::Path, name: &str, script: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        let hook = path.join(".git/hooks").join(name);
+        fs::create_dir_all(hook.parent().unwrap()).unwrap();
+        fs::write(&hook, script).unwrap();
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn test_create_commit_uses_identity_and_returns_entry() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+
+        let err = create_commit(temp.path(), "nothing", &CommitOptions::default()).unwrap_err();
+        assert!(err.to_string().contains("Nothing to commit"));
+
+        fs::write(temp.path().join("README.md"), "# Changed\n").unwrap();
+        stage_files(temp.path(), &["README.md".to_string()]).unwrap();
+
+        let err = create_commit(temp.path(), "# only a comment\n", &CommitOptions::default()).unwrap_err();
+        assert!(err.to_string().contains("empty"));
+
+        let entry = create_commit(temp.path(), "Update readme\n\n# comment", &CommitOptions::default()).unwrap();
+        assert_eq!(entry.oid, head_oid(temp.path()));
+        assert_eq!(entry.message, "Update readme");
+        assert_eq!(entry.author_email, "test@test.com");
+        assert_eq!(entry.parents.len(), 1);
+        assert!(git(temp.path(), &["status", "--porcelain"]).is_empty());
+    }
+
+    #[test]
+    fn test_create_commit_refuses_gpgsign() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        git(temp.path(), &["config", "commit.gpgsign", "true"]);
+        fs::write(temp.path().join("README.md"), "# Changed\n").unwrap();
+        stage_files(temp.path(), &["README.md".to_string()]).unwrap();
+
+        let err = create_commit(temp.path(), "signed", &CommitOptions::default()).unwrap_err();
+        assert!(err.to_string().contains("commit.gpgsign"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_create_commit_runs_or_skips_hooks() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        let before = head_oid(temp.path());
+        install_hook(temp.path(), "pre-commit", "#!/bin/sh\necho 'lint failed' >&2\nexit 1\n");
+        install_hook(temp.path(), "commit-msg", "#!/bin/sh\necho 'Reviewed-by: hook' >> \"$1\"\n");
+        fs::write(temp.path().join("README.md"), "# Changed\n").unwrap();
+        stage_files(temp.path(), &["README.md".to_string()]).unwrap();
+
+        let err = create_commit(temp.path(), "blocked", &CommitOptions::default()).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::error::DevBaseError::Hook { ref hook, ref output } if hook == "pre-commit" && output == "lint failed"
+        ));
+        assert_eq!(head_oid(temp.path()), before);
+
+        let skip = CommitOptions { skip_hooks: true, ..CommitOptions::default() };
+        create_commit(temp.path(), "skipped", &skip).unwrap();
+        assert!(!git(temp.path(), &["log", "-1", "--format=%B"]).contains("Reviewed-by"));
+
+        install_hook(temp.path(), "pre-commit", "#!/bin/sh\nexit 0\n");
+        fs::write(temp.path().join("README.md"), "# Again\n").unwrap();
+        stage_files(temp.path(), &["README.md".to_string()]).unwrap();
+        create_commit(temp.path(), "hooked", &CommitOptions::default()).unwrap();
+        assert!(git(temp.path(), &["log", "-1", "--format=%B"]).contains("Reviewed-by: hook"));
+    }
+
+
+    #[test]
+    fn test_checkout_local_and_remote_branches() {
+        let temp = tempdir().unwrap();
+        let (_bare, work) = setup_remote_clone(temp.path());
+        git(&work, &["checkout", "-b", "local"]);
+        git(&work, &["checkout", "-b", "remote-only"]);
+        fs::write(work.join("remote.txt"), "remote").unwrap();
+        commit_all(&work, "remote work");
+        git(&work, &["push", "origin", "remote-only"]);
+        git(&work, &["checkout", "main"]);
+        git(&work, &["branch", "-D", "remote-only"]);
+
+        let result = checkout_branch(&work, "local", &CheckoutOptions::default()).unwrap();
+        assert_eq!(result.branch, "local");
+        assert_eq!(result.previous.as_deref(), Some("main"));
+        assert!(!result.created_tracking);
+
+        // Plain name resolves to the only remote that has it
+        let result = checkout_branch(&work, "remote-only", &CheckoutOptions::default()).unwrap();
+        assert!(result.created_tracking);
+        assert_eq!(result.head_oid, head_oid(&work));
+        assert!(work.join("remote.txt").exists());
+        assert_eq!(
+            git(&work, &["rev-parse", "--abbrev-ref", "remote-only@{upstream}"]),
+            "origin/remote-only"
+        );
+
+        // origin/<name> with an existing local branch switches to that branch
+        git(&work, &["checkout", "main"]);
+        let result = checkout_branch(&work, "origin/remote-only", &CheckoutOptions::default()).unwrap();
+        assert_eq!(result.branch, "remote-only");
+        assert!(!result.created_tracking);
+
+        assert!(checkout_branch(&work, "missing", &CheckoutOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_checkout_refuses_to_overwrite_local_changes_unless_autostash() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        fs::write(temp.path().join("README.md"), numbered_lines(8)).unwrap();
+        commit_all(temp.path(), "longer readme");
+        git(temp.path(), &["checkout", "-b", "other"]);
+        fs::write(temp.path().join("README.md"), numbered_lines(8).replace("line 8", "line eight")).unwrap();
+        commit_all(temp.path(), "other change");
+        git(temp.path(), &["checkout", "main"]);
+
+        let local = numbered_lines(8).replace("line 1\n", "line one\n");
+        fs::write(temp.path().join("README.md"), &local).unwrap();
+
+        let err = checkout_branch(temp.path(), "other", &CheckoutOptions::default()).unwrap_err();
+        match err {
+            crate::error::DevBaseError::LocalChanges { files } => assert_eq!(files, vec!["README.md"]),
+            other => panic!("expected LocalChanges, got {other:?}"),
+        }
+        assert_eq!(git(temp.path(), &["branch", "--show-current"]), "main");
+        assert_eq!(fs::read_to_string(temp.path().join("README.md")).unwrap(), local);
+
+        let options = CheckoutOptions { autostash: true };
+        let result = checkout_branch(temp.path(), "other", &options).unwrap();
+        assert!(result.stashed && !result.stash_conflict);
+        assert_eq!(git(temp.path(), &["branch", "--show-current"]), "other");
+        let merged = fs::read_to_string(temp.path().join("README.md")).unwrap();
+        assert!(merged.contains("line one") && merged.contains("line eight"));
+        assert!(git(temp.path(), &["stash", "list"]).is_empty());
+    }
+
+    #[test]
+    fn test_create_rename_and_delete_branches() {
+        let temp = tempdir().unwrap();
+        let (_bare, work) = setup_remote_clone(temp.path());
+        let first = head_oid(&work);
+        fs::write(work.join("second.txt"), "second").unwrap();
+        commit_all(&work, "second");
+
+        let info = create_branch(&work, "from-first", Some(&first)).unwrap();
+        assert_eq!(info.tip_oid, first);
+        assert!(info.upstream.is_none());
+
+        let info = create_branch(&work, "tracking", Some("origin/main")).unwrap();
+        assert_eq!(info.upstream.as_deref(), Some("origin/main"));
+
+        assert!(create_branch(&work, "tracking", None).is_err());
+        assert!(create_branch(&work, "bad..name", None).is_err());
+        assert!(create_branch(&work, "x", Some("no-such-rev")).is_err());
+
+        let renamed = rename_branch(&work, "tracking", "renamed").unwrap();
+        assert_eq!(renamed.upstream.as_deref(), Some("origin/main"));
+        assert!(git(&work, &["branch", "--list", "tracking"]).is_empty());
+
+        // Unmerged work needs force; the current branch is never deleted
+        git(&work, &["checkout", "-b", "unmerged"]);
+        fs::write(work.join("unmerged.txt"), "unmerged").unwrap();
+        commit_all(&work, "unmerged");
+        let unmerged_tip = head_oid(&work);
+        assert!(delete_branch(&work, "unmerged", true).is_err());
+        git(&work, &["checkout", "main"]);
+        assert!(delete_branch(&work, "unmerged", false).is_err());
+        let deleted = delete_branch(&work, "unmerged", true).unwrap();
+        assert_eq!(deleted.tip_oid, unmerged_tip);
+
+        let deleted = delete_branch(&work, "from-first", false).unwrap();
+        assert_eq!(deleted.tip_oid, first);
+    }
+
+    #[test]
+    fn test_set_and_unset_branch_upstream() {
+        let temp = tempdir().unwrap();
+        let (_bare, work) = setup_remote_clone(temp.path());
+        git(&work, &["branch", "feature"]);
+
+        let info = set_branch_upstream(&work, "feature", Some("origin/main")).unwrap();
+        assert_eq!(info.upstream.as_deref(), Some("origin/main"));
+
+        let info = set_branch_upstream(&work, "feature", None).unwrap();
+        assert!(info.upstream.is_none());
+        // Unsetting twice is fine
+        set_branch_upstream(&work, "feature", None).unwrap();
+
+        assert!(set_branch_upstream(&work, "feature", Some("origin/missing")).is_err());
+    }
+
+
+    #[test]
+    fn test_branch_presence_local_remote_and_missing() {
+        let temp = tempdir().unwrap();
+        let (_bare, work) = setup_remote_clone(temp.path());
+        git(&work, &["remote", "set-head", "origin", "main"]);
+        git(&work, &["checkout", "-b", "feature"]);
+        fs::write(work.join("one.txt"), "one").unwrap();
+        commit_all(&work, "one");
+        git(&work, &["push", "-u", "origin", "feature"]);
+        fs::write(work.join("two.txt"), "two").unwrap();
+        commit_all(&work, "two");
+
+        let presence = branch_presence(&work, "feature").unwrap();
+        assert_eq!(presence.local_tip.as_deref(), Some(head_oid(&work).as_str()));
+        assert!(presence.is_head);
+        assert_eq!(presence.remote_branches, vec!["origin/feature"]);
+        assert_eq!(presence.upstream.as_deref(), Some("origin/feature"));
+        assert_eq!((presence.ahead_upstream, presence.behind_upstream), (1, 0));
+        assert_eq!(presence.default_branch.as_deref(), Some("origin/main"));
+        assert_eq!((presence.ahead_default, presence.behind_default), (2, 0));
+
+        // Remote-only: counts come from the remote branch
+        git(&work, &["checkout", "main"]);
+        git(&work, &["branch", "-D", "feature"]);
+        let presence = branch_presence(&work, "feature").unwrap();
+        assert!(presence.local_tip.is_none() && presence.exists());
+        assert_eq!(presence.ahead_default, 1);
+
+        assert!(!branch_presence(&work, "missing").unwrap().exists());
+    }
+
+    #[test]
+    fn test_apply_branch_operation_reports_done_and_skipped() {
+        let temp = tempdir().unwrap();
+        let (_bare, work) = setup_remote_clone(temp.path());
+
+        let create = BranchOperation::Create { name: "feature".to_string(), start_point: None };
+        assert_eq!(
+            apply_branch_operation(&work, &create),
+            BranchOperationOutcome::Done { tip_oid: head_oid(&work) }
+        );
+        assert!(matches!(apply_branch_operation(&work, &create), BranchOperationOutcome::Skipped { .. }));
+
+        let checkout = BranchOperation::Checkout { name: "feature".to_string(), autostash: false };
+        assert!(matches!(apply_branch_operation(&work, &checkout), BranchOperationOutcome::Done { .. }));
+        assert_eq!(git(&work, &["branch", "--show-current"]), "feature");
+        assert!(matches!(apply_branch_operation(&work, &checkout), BranchOperationOutcome::Skipped { .. }));
+
+        // Deleting the checked out branch fails; elsewhere it is done
+        let delete = BranchOperation::Delete { name: "feature".to_string(), force: false };
+        assert!(matches!(apply_branch_operation(&work, &delete), BranchOperationOutcome::Failed { .. }));
+        git(&work, &["checkout", "main"]);
+        assert!(matches!(apply_branch_operation(&work, &delete), BranchOperationOutcome::Done { .. }));
+        assert!(matches!(apply_branch_operation(&work, &delete), BranchOperationOutcome::Skipped { .. }));
+
+        let missing = BranchOperation::Checkout { name: "missing".to_string(), autostash: false };
+        assert!(matches!(apply_branch_operation(&work, &missing), BranchOperationOutcome::Skipped { .. }));
+    }
+
+
+    #[test]
+    fn test_semver_parsing_and_precedence() {
+        assert_eq!(SemVer::from_tag("v1.2.3").unwrap().to_string(), "1.2.3");
+        assert_eq!(SemVer::from_tag("1.4").unwrap().to_string(), "1.4.0");
+        assert_eq!(SemVer::from_tag("pkg@2.0.0-rc.1").unwrap().to_string(), "2.0.0-rc.1");
+        assert_eq!(SemVer::from_tag("release/3.1.0+build.7").unwrap().to_string(), "3.1.0");
+        assert!(SemVer::from_tag("nightly").is_none());
+        assert!(SemVer::from_tag("v1").is_none());
+
+        let mut versions: Vec<SemVer> = ["1.0.0", "1.0.0-alpha", "1.0.0-alpha.1", "1.0.0-beta", "1.0.0-alpha.beta", "0.9.10", "0.9.9"]
+            .iter()
+            .map(|v| SemVer::parse(v).unwrap())
+            .collect();
+        versions.sort();
+        let ordered: Vec<String> = versions.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            ordered,
+            ["0.9.9", "0.9.10", "1.0.0-alpha", "1.0.0-alpha.1", "1.0.0-alpha.beta", "1.0.0-beta", "1.0.0"]
+        );
+    }
+
+    #[test]
+    fn test_list_tags_annotated_and_lightweight() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        git(temp.path(), &["tag", "v0.9.0"]);
+        git(temp.path(), &["tag", "-a", "v1.0.0", "-m", "First release"]);
+        git(temp.path(), &["tag", "snapshot"]);
+        git(temp.path(), &["tag", "v1.1.0-rc.1"]);
+
+        let tags = list_tags(temp.path()).unwrap();
+        let names: Vec<&str> = tags.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["v1.1.0-rc.1", "v1.0.0", "v0.9.0", "snapshot"]);
+
+        let release = &tags[1];
+        assert!(release.is_annotated);
+        assert_eq!(release.message.as_deref(), Some("First release"));
+        assert_eq!(release.tagger_email.as_deref(), Some("test@test.com"));
+        assert_eq!(release.target_oid, head_oid(temp.path()));
+        assert!(tags[0].is_prerelease && !tags[0].is_annotated);
+        assert!(tags[3].version.is_none());
+    }
+
+    #[test]
+    fn test_release_summary_counts_commits_since_tag() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        git(temp.path(), &["tag", "v1.0.0"]);
+        fs::write(temp.path().join("a.txt"), "a\n").unwrap();
+        commit_all(temp.path(), "add a");
+        git(temp.path(), &["tag", "v1.1.0-beta.1"]);
+        fs::write(temp.path().join("b.txt"), "b\n").unwrap();
+        git(temp.path(), &["add", "-A"]);
+        git(temp.path(), &["-c", "user.name=Other", "-c", "user.email=other@test.com", "commit", "-m", "add b"]);
+
+        // A higher version that is not on the default branch is ignored
+        git(temp.path(), &["checkout", "-b", "side"]);
+        fs::write(temp.path().join("c.txt"), "c\n").unwrap();
+        commit_all(temp.path(), "side work");
+        git(temp.path(), &["tag", "v9.0.0"]);
+        git(temp.path(), &["checkout", "main"]);
+
+        let summary = get_release_summary(temp.path(), false).unwrap();
+        assert_eq!(summary.branch.as_deref(), Some("main"));
+        assert_eq!(summary.latest_release.unwrap().name, "v1.0.0");
+        assert_eq!(summary.commits_since, 2);
+        assert_eq!(summary.authors_since.len(), 2);
+
+        let summary = get_release_summary(temp.path(), true).unwrap();
+        assert_eq!(summary.latest_release.unwrap().name, "v1.1.0-beta.1");
+        assert_eq!(summary.commits_since, 1);
+        assert_eq!(summary.authors_since[0].email, "other@test.com");
+    }
+
+
+    #[test]
+    fn test_parse_conventional_message() {
+        let parsed = ConventionalMessage::parse(
+            "feat(api)!: drop v1 endpoints\n\nThe old routes are gone.\n\nBREAKING CHANGE: clients must use /v2\n  and re-authenticate\nRefs #42\nReviewed-by: Sam",
+        )
+        .unwrap();
+        assert_eq!(parsed.commit_type, "feat");
+        assert_eq!(parsed.scope.as_deref(), Some("api"));
+        assert!(parsed.breaking);
+        assert_eq!(parsed.description, "drop v1 endpoints");
+        assert_eq!(parsed.body.as_deref(), Some("The old routes are gone."));
+        let footers: Vec<(&str, &str)> =
+            parsed.footers.iter().map(|f| (f.token.as_str(), f.value.as_str())).collect();
+        assert_eq!(
+            footers,
+            [
+                ("BREAKING CHANGE", "clients must use /v2\n  and re-authenticate"),
+                ("Refs", "#42"),
+                ("Reviewed-by", "Sam"),
+            ]
+        );
+        assert_eq!(parsed.breaking_note(), Some("clients must use /v2\n  and re-authenticate"));
+
+        let fix = ConventionalMessage::parse("Fix: handle empty input").unwrap();
+        assert_eq!((fix.commit_type.as_str(), fix.breaking, fix.body), ("fix", false, None));
+
+        assert!(ConventionalMessage::parse("Update README").is_none());
+        assert!(ConventionalMessage::parse("feat(): missing scope").is_none());
+        assert!(ConventionalMessage::parse("Merge branch 'x': stuff").is_none());
+    }
+
+    #[test]
+    fn test_version_bump_suggestion() {
+        let messages: Vec<ConventionalMessage> = ["fix: a", "feat: b", "chore: c"]
+            .iter()
+            .map(|m| ConventionalMessage::parse(m).unwrap())
+            .collect();
+        assert_eq!(VersionBump::from_commits(&messages), VersionBump::Minor);
+        assert_eq!(VersionBump::from_commits(&messages[2..]), VersionBump::None);
+
+        let v = |s: &str| SemVer::parse(s).unwrap();
+        assert_eq!(VersionBump::Major.apply(Some(&v("1.4.2"))).to_string(), "2.0.0");
+        assert_eq!(VersionBump::Major.apply(Some(&v("0.4.2"))).to_string(), "0.5.0");
+        assert_eq!(VersionBump::Minor.apply(Some(&v("1.4.2"))).to_string(), "1.5.0");
+        assert_eq!(VersionBump::Patch.apply(Some(&v("1.4.2"))).to_string(), "1.4.3");
+        assert_eq!(VersionBump::Minor.apply(Some(&v("2.0.0-rc.1"))).to_string(), "2.0.0");
+        assert_eq!(VersionBump::Patch.apply(None).to_string(), "0.1.0");
+    }
+
+    #[test]
+    fn test_generate_release_notes_since_last_tag() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        git(temp.path(), &["tag", "v1.2.0"]);
+        for (file, message) in [
+            ("a.txt", "feat(ui): add dark mode"),
+            ("b.txt", "fix: crash on empty repo"),
+            ("c.txt", "chore: bump deps"),
+            ("d.txt", "Tweak wording"),
+        ] {
+            fs::write(temp.path().join(file), message).unwrap();
+            commit_all(temp.path(), message);
+        }
+
+        let notes = generate_release_notes(temp.path(), None, None).unwrap();
+        assert_eq!(notes.from.as_deref(), Some("v1.2.0"));
+        assert_eq!(notes.previous_version.as_deref(), Some("1.2.0"));
+        assert_eq!(notes.bump, VersionBump::Minor);
+        assert_eq!(notes.next_version, "1.3.0");
+        assert_eq!(notes.commits.len(), 3);
+        assert_eq!(notes.other_commits.len(), 1);
+
+        let md = &notes.markdown;
+        assert!(md.starts_with("## 1.3.0 ("));
+        let features = md.find("### Features").unwrap();
+        let fixes = md.find("### Bug Fixes").unwrap();
+        assert!(features < fixes);
+        assert!(md.contains("- **ui:** add dark mode ("));
+        assert!(md.contains("- chore: bump deps ("));
+        assert!(md.contains("- Tweak wording ("));
+        assert!(!md.contains("BREAKING"));
+
+        // An explicit range with a breaking change
+        fs::write(temp.path().join("e.txt"), "e").unwrap();
+        commit_all(temp.path(), "refactor!: rename config keys");
+        let notes = generate_release_notes(temp.path(), Some("HEAD~1"), Some("HEAD")).unwrap();
+        assert_eq!(notes.commits.len(), 1);
+        assert_eq!(notes.next_version, "2.0.0");
+        assert!(notes.markdown.contains("### ⚠ BREAKING CHANGES\n\n- rename config keys ("));
+
+        assert!(generate_release_notes(temp.path(), Some("nope"), None).is_err());
+    }
+
+
+    #[test]
+    fn test_list_reflog_for_head_and_branch() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        fs::write(temp.path().join("a.txt"), "a\n").unwrap();
+        commit_all(temp.path(), "add a");
+        git(temp.path(), &["checkout", "-b", "feature"]);
+        git(temp.path(), &["checkout", "main"]);
+
+        let head = list_reflog(temp.path(), None, None).unwrap();
+        assert!(head[0].message.starts_with("checkout: moving from feature to main"));
+        assert_eq!(head[0].index, 0);
+        assert_eq!(head[0].new_oid, head_oid(temp.path()));
+        assert_eq!(head[0].committer_email, "test@test.com");
+        assert_eq!(list_reflog(temp.path(), None, Some(2)).unwrap().len(), 2);
+
+        let main = list_reflog(temp.path(), Some("main"), None).unwrap();
+        assert!(main[0].message.starts_with("commit: add a"));
+        assert_eq!(main[0].old_oid, main[1].new_oid);
+
+        assert!(list_reflog(temp.path(), Some("missing"), None).is_err());
+    }
+
+    #[test]
+    fn test_restore_reflog_entry_backs_up_and_refuses_dirty() {
+        let temp = tempdir().unwrap();
+        stash_fixture(temp.path());
+        let first = head_oid(temp.path());
+        fs::write(temp.path().join("a.txt"), "a\n").unwrap();
+        commit_all(temp.path(), "add a");
+        let second = head_oid(temp.path());
+        git(temp.path(), &["reset", "--hard", "HEAD~1"]);
+        assert_eq!(head_oid(temp.path()), first);
+
+        fs::write(temp.path().join("README.md"), "changed\n").unwrap();
+        assert!(matches!(
+            restore_reflog_entry(temp.path(), None, 1),
+            Err(crate::error::DevBaseError::LocalChanges { .. })
+        ));
+        git(temp.path(), &["checkout", "--", "README.md"]);
+
+        // HEAD@{1} is the commit the reset threw away
+        let restored = restore_reflog_entry(temp.path(), None, 1).unwrap();
+        assert_eq!(restored.reference, "refs/heads/main");
+        assert_eq!(restored.restored_oid, second);
+        assert_eq!(restored.previous_oid, first);
+        assert!(restored.backup_ref.starts_with(UNDO_REF_PREFIX));
+        assert_eq!(git(temp.path(), &["rev-parse", &restored.backup_ref]), first);
+        assert_eq!(head_oid(temp.path()), second);
+        assert_eq!(git(temp.path(), &["branch", "--show-current"]), "main");
+        assert!(temp.path().join("a.txt").exists());
+
+        assert!(restore_reflog_entry(temp.path(), None, 99).is_err());
+    }
+
+
+    #[test]
+    fn test_add_and_list_worktrees() {
+        let temp = tempdir().unwrap();
+        let main = temp.path().join("main");
+        fs::create_dir(&main).unwrap();
+        stash_fixture(&main);
+        git(&main, &["branch", "review"]);
+
+        let review_path = temp.path().join("review");
+        let added = add_worktree(&main, "review", &review_path, None).unwrap();
+        assert_eq!(added.name.as_deref(), Some("review"));
+        assert_eq!(added.branch.as_deref(), Some("review"));
+        assert!(added.health.is_some());
+        assert!(review_path.join("README.md").exists());
+
+        // A missing branch is created from HEAD
+        let fresh = add_worktree(&main, "fresh", &temp.path().join("fresh"), None).unwrap();
+        assert_eq!(fresh.head_oid, Some(head_oid(&main)));
+
+        // Already checked out, or a non-empty target, is refused
+        assert!(add_worktree(&main, "review", &temp.path().join("again"), None).is_err());
+        assert!(add_worktree(&main, "other", &main, None).is_err());
+
+        git(&main, &["worktree", "lock", "--reason", "in review", review_path.to_str().unwrap()]);
+        // Listing works from a linked worktree too
+        let worktrees = list_worktrees(&review_path, false).unwrap();
+        assert_eq!(worktrees.len(), 3);
+        assert!(worktrees[0].is_main);
+        assert_eq!(worktrees[0].branch.as_deref(), Some("main"));
+        let review = worktrees.iter().find(|w| w.name.as_deref() == Some("review")).unwrap();
+        assert!(review.is_locked && !review.is_prunable);
+        assert_eq!(review.lock_reason.as_deref(), Some("in review"));
+        assert!(review.health.is_none());
+    }
+
+    #[test]
+    fn test_remove_and_prune_worktrees() {
+        let temp = tempdir().unwrap();
+        let main = temp.path().join("main");
+        fs::create_dir(&main).unwrap();
+        stash_fixture(&main);
+        let keep = temp.path().join("keep");
+        let gone = temp.path().join("gone");
+        add_worktree(&main, "keep", &keep, None).unwrap();
+        add_worktree(&main, "gone", &gone, None).unwrap();
+
+        fs::write(keep.join("README.md"), "edited\n").unwrap();
+        assert!(matches!(
+            remove_worktree(&main, "keep", false),
+            Err(crate::error::DevBaseError::LocalChanges { .. })
+        ));
+        remove_worktree(&main, "keep", true).unwrap();
+        assert!(!keep.exists());
+        assert!(remove_worktree(&main, "keep", false).is_err());
+
+        fs::remove_dir_all(&gone).unwrap();
+        let listed = list_worktrees(&main, true).unwrap();
+        let stale = listed.iter().find(|w| w.name.as_deref() == Some("gone")).unwrap();
+        assert!(stale.is_prunable && stale.health.is_none());
+
+        assert_eq!(prune_worktrees(&main).unwrap(), ["gone"]);
+        assert_eq!(list_worktrees(&main, false).unwrap().len(), 1);
+    }
+
+
+    /// `main` and `feature` both change line 1 of `f.txt` from `base`.
+    fn conflict_fixture(path: &std::path::Path) {
+        stash_fixture(path);
+        fs::write(path.join("f.txt"), "base\n").unwrap();
+        commit_all(path, "base");
+        git(path, &["checkout", "-b", "feature"]);
+        fs::write(path.join("f.txt"), "feature\n").unwrap();
+        commit_all(path, "feature change");
+        git(path, &["checkout", "main"]);
+        fs::write(path.join("f.txt"), "main\n").unwrap();
+        commit_all(path, "main change");
+    }
+
+    #[test]
+    fn test_list_and_resolve_merge_conflict() {
+        let temp = tempdir().unwrap();
+        conflict_fixture(temp.path());
+        let main_tip = head_oid(temp.path());
+        git(temp.path(), &["merge", "feature"]);
+
+        let report = list_conflicts(temp.path()).unwrap();
+        assert_eq!(report.state, RepoState::Merge);
+        assert_eq!(report.conflicts.len(), 1);
+        let conflict = &report.conflicts[0];
+        assert_eq!(conflict.path, "f.txt");
+        assert_eq!(conflict.ancestor.as_ref().unwrap().content.as_deref(), Some("base\n"));
+        assert_eq!(conflict.ours.as_ref().unwrap().content.as_deref(), Some("main\n"));
+        assert_eq!(conflict.theirs.as_ref().unwrap().content.as_deref(), Some("feature\n"));
+
+        assert!(continue_operation(temp.path(), None).is_err());
+        assert!(resolve_conflict(temp.path(), "README.md", &ConflictResolution::Ours).is_err());
+        resolve_conflict(
+            temp.path(),
+            "f.txt",
+            &ConflictResolution::Content { content: "both\n".to_string() },
+        )
+        .unwrap();
+        assert!(list_conflicts(temp.path()).unwrap().conflicts.is_empty());
+
+        let outcome = continue_operation(temp.path(), None).unwrap();
+        assert_eq!(outcome, ContinueOutcome::Completed { head_oid: head_oid(temp.path()) });
+        assert_eq!(git(temp.path(), &["rev-parse", "HEAD^1"]), main_tip);
+        assert_eq!(git(temp.path(), &["rev-parse", "HEAD^2"]), git(temp.path(), &["rev-parse", "feature"]));
+        assert!(git(temp.path(), &["log", "-1", "--format=%s"]).starts_with("Merge branch 'feature'"));
+        assert_eq!(fs::read_to_string(temp.path().join("f.txt")).unwrap(), "both\n");
+        assert_eq!(list_conflicts(temp.path()).unwrap().state, RepoState::Clean);
+    }
+
+    #[test]
+    fn test_cherry_pick_resolve_theirs_keeps_author() {
+        let temp = tempdir().unwrap();
+        conflict_fixture(temp.path());
+        git(temp.path(), &["checkout", "feature"]);
+        git(temp.path(), &["commit", "--amend", "--no-edit", "--author", "Picked <picked@test.com>"]);
+        git(temp.path(), &["checkout", "main"]);
+        git(temp.path(), &["cherry-pick", "feature"]);
+        assert_eq!(list_conflicts(temp.path()).unwrap().state, RepoState::CherryPick);
+
+        resolve_conflict(temp.path(), "f.txt", &ConflictResolution::Theirs).unwrap();
+        continue_operation(temp.path(), None).unwrap();
+        assert_eq!(git(temp.path(), &["log", "-1", "--format=%ae %s"]), "picked@test.com feature change");
+        assert_eq!(fs::read_to_string(temp.path().join("f.txt")).unwrap(), "feature\n");
+    }
+
+    #[test]
+    fn test_resolve_with_deleting_side_and_abort_merge() {
+        let temp = tempdir().unwrap();
+        conflict_fixture(temp.path());
+        let main_tip = head_oid(temp.path());
+        git(temp.path(), &["checkout", "feature"]);
+        git(temp.path(), &["rm", "-q", "f.txt"]);
+        git(temp.path(), &["commit", "-m", "delete f"]);
+        git(temp.path(), &["checkout", "main"]);
+        git(temp.path(), &["merge", "feature"]);
+
+        let report = list_conflicts(temp.path()).unwrap();
+        assert!(report.conflicts[0].theirs.is_none());
+        resolve_conflict(temp.path(), "f.txt", &ConflictResolution::Theirs).unwrap();
+        assert!(!temp.path().join("f.txt").exists());
+        assert!(list_conflicts(temp.path()).unwrap().conflicts.is_empty());
+
+        abort_operation(temp.path()).unwrap();
+        assert_eq!(head_oid(temp.path()), main_tip);
+        assert_eq!(fs::read_to_string(temp.path().join("f.txt")).unwrap(), "main\n");
+        assert_eq!(list_conflicts(temp.path()).unwrap().state, RepoState::Clean);
+        assert!(abort_operation(temp.path()).is_err());
+    }
+
+    #[test]
+    fn test_continue_and_abort_rebase() {
+        let temp = tempdir().unwrap();
+        conflict_fixture(temp.path());
+        let feature_tip = git(temp.path(), &["rev-parse", "feature"]);
+        let start_rebase = || {
+            let repo = git2::Repository::open(temp.path()).unwrap();
+            let branch = repo
+                .reference_to_annotated_commit(&repo.find_reference("refs/heads/feature").unwrap())
+                .unwrap();
+            let onto = repo
+                .reference_to_annotated_commit(&repo.find_reference("refs/heads/main").unwrap())
+                .unwrap();
+            let mut rebase = repo.rebase(Some(&branch), Some(&onto), None, None).unwrap();
+            rebase.next().unwrap().unwrap();
+        };
+
+        start_rebase();
+        assert_eq!(list_conflicts(temp.path()).unwrap().conflicts.len(), 1);
+        abort_operation(temp.path()).unwrap();
+        assert_eq!(git(temp.path(), &["rev-parse", "feature"]), feature_tip);
+        assert_eq!(list_conflicts(temp.path()).unwrap().state, RepoState::Clean);
+
+        start_rebase();
+        resolve_conflict(temp.path(), "f.txt", &ConflictResolution::Ours).unwrap();
+        resolve_conflict(temp.path(), "f.txt", &ConflictResolution::Ours).unwrap_err();
+        fs::write(temp.path().join("f.txt"), "rebased\n").unwrap();
+        git(temp.path(), &["add", "f.txt"]);
+        let outcome = continue_operation(temp.path(), None).unwrap();
+        assert!(matches!(outcome, ContinueOutcome::Completed { .. }));
+        assert_eq!(git(temp.path(), &["rev-parse", "feature~1"]), git(temp.path(), &["rev-parse", "main"]));
+        assert_eq!(git(temp.path(), &["show", "feature:f.txt"]), "rebased");
+    }
+
+    #[test]
+    fn test_cli_rebase_cannot_be_continued() {
+        let temp = tempdir().unwrap();
+        conflict_fixture(temp.path());
+        git(temp.path(), &["checkout", "feature"]);
+        git(temp.path(), &["rebase", "main"]);
+        assert_eq!(list_conflicts(temp.path()).unwrap().conflicts.len(), 1);
+
+        resolve_conflict(temp.path(), "f.txt", &ConflictResolution::Theirs).unwrap();
+        match continue_operation(temp.path(), None) {
+            Err(crate::error::DevBaseError::Config { message }) => assert!(message.contains("git rebase")),
+            other => panic!("expected a config error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_abort_cli_rebase_restores_branch() {
+        let temp = tempdir().unwrap();
+        conflict_fixture(temp.path());
+        git(temp.path(), &["checkout", "feature"]);
+        let feature_tip = head_oid(temp.path());
+        git(temp.path(), &["rebase", "main"]);
+
+        abort_operation(temp.path()).unwrap();
+
+        let repo = git2::Repository::open(temp.path()).unwrap();
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+        assert_eq!(repo.head().unwrap().shorthand(), Some("feature"));
+        assert_eq!(head_oid(temp.path()), feature_tip);
+        assert!(repo.statuses(None).unwrap().is_empty());
+        assert_eq!(fs::read_to_string(temp.path().join("f.txt")).unwrap(), "feature\n");
+    }
 }
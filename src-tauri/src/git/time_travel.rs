@@ -0,0 +1,315 @@
+//! Time-travel browsing of trees and files at any revision.
+//!
+//! Everything here reads from the object database only; the working tree
+//! and index are never touched.
+
+use git2::{ObjectType, Oid, Repository, Sort, Tree};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use super::diff::{detect_mime_type, has_utf16_bom, is_binary_content, read_blob_at};
+use super::history::CommitLogEntry;
+use crate::error::{DevBaseError, Result};
+
+/// Default cap for file reads.
+pub const DEFAULT_MAX_READ_BYTES: usize = 1024 * 1024;
+
+/// Commits walked while looking for the last commit touching each entry.
+const LAST_COMMIT_WALK_LIMIT: usize = 5000;
+
+/// Kind of a tree entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TreeEntryKind {
+    File,
+    Executable,
+    Symlink,
+    Directory,
+    Submodule,
+}
+
+/// One entry of a directory listing at a revision.
+#[derive(Debug, Clone, Serialize)]
+pub struct TreeEntryInfo {
+    /// Entry name
+    pub name: String,
+    /// Path relative to the repository root
+    pub path: String,
+    pub kind: TreeEntryKind,
+    /// Git file mode in octal (e.g. `100644`)
+    pub mode: String,
+    pub oid: String,
+    /// Blob size in bytes (files and symlinks only)
+    pub size: Option<u64>,
+    /// Most recent commit that changed this entry
+    pub last_commit: Option<CommitLogEntry>,
+}
+
+/// File contents read at a revision.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileAtRevision {
+    pub path: String,
+    /// Commit the file was read from
+    pub commit_oid: String,
+    pub blob_oid: String,
+    /// Full blob size in bytes
+    pub size: u64,
+    pub is_binary: bool,
+    pub mime_type: String,
+    /// Detected text encoding (`None` for binary content)
+    pub encoding: Option<String>,
+    /// Decoded text (`None` for binary content)
+    pub content: Option<String>,
+    /// Content was cut at the size cap
+    pub truncated: bool,
+}
+
+/// List one directory of the tree at a revision.
+///
+/// `dir_path` of `None` (or empty) lists the repository root.
+pub fn list_tree_at(path: &Path, rev: &str, dir_path: Option<&str>) -> Result<Vec<TreeEntryInfo>> {
+    let repo = Repository::open(path)?;
+    let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+    let dir_path = dir_path.unwrap_or("").trim_matches('/');
+
+    let tree = subtree(&repo, &commit.tree()?, dir_path)?.ok_or_else(|| DevBaseError::Config {
+        message: format!("Directory not found at {rev}: {dir_path}"),
+    })?;
+
+    let odb = repo.odb()?;
+    let mut entries = Vec::with_capacity(tree.len());
+
+    for entry in &tree {
+        let name = entry.name().unwrap_or("").to_string();
+        let kind = entry_kind(entry.kind(), entry.filemode());
+        let size = match kind {
+            TreeEntryKind::File | TreeEntryKind::Executable | TreeEntryKind::Symlink => odb
+                .read_header(entry.id())
+                .ok()
+                .map(|(size, _)| size as u64),
+            TreeEntryKind::Directory | TreeEntryKind::Submodule => None,
+        };
+
+        entries.push(TreeEntryInfo {
+            path: join_path(dir_path, &name),
+            name,
+            kind,
+            mode: format!("{:06o}", entry.filemode()),
+            oid: entry.id().to_string(),
+            size,
+            last_commit: None,
+        });
+    }
+
+    let last_commits = find_last_commits(&repo, commit.id(), dir_path, &entries)?;
+    for entry in &mut entries {
+        entry.last_commit = last_commits.get(&entry.name).cloned();
+    }
+
+    // Directories first, then by name
+    entries.sort_by(|a, b| {
+        (b.kind == TreeEntryKind::Directory)
+            .cmp(&(a.kind == TreeEntryKind::Directory))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    Ok(entries)
+}
+
+/// Read a file at a revision, capped at `max_bytes`.
+pub fn read_file_at(
+    path: &Path,
+    rev: &str,
+    file_path: &str,
+    max_bytes: usize,
+) -> Result<FileAtRevision> {
+    let repo = Repository::open(path)?;
+    let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+
+    let (blob_oid, data) =
+        read_blob_at(&repo, &commit.id().to_string(), file_path)?.ok_or_else(|| {
+            DevBaseError::Config {
+                message: format!("File not found at {rev}: {file_path}"),
+            }
+        })?;
+
+    // UTF-16 text is full of NUL bytes, so look for its BOM first
+    let is_binary = !has_utf16_bom(&data) && is_binary_content(&data);
+    let truncated = data.len() > max_bytes;
+
+    let (encoding, content) = if is_binary {
+        (None, None)
+    } else {
+        let (encoding, text) = decode_text(&data[..data.len().min(max_bytes)]);
+        (Some(encoding.to_string()), Some(text))
+    };
+
+    Ok(FileAtRevision {
+        path: file_path.to_string(),
+        commit_oid: commit.id().to_string(),
+        blob_oid: blob_oid.to_string(),
+        size: data.len() as u64,
+        is_binary,
+        mime_type: detect_mime_type(file_path, &data).to_string(),
+        encoding,
+        content,
+        truncated,
+    })
+}
+
+/// Resolve a directory inside a tree. Returns `None` if it does not exist.
+fn subtree<'r>(repo: &'r Repository, root: &Tree<'r>, dir_path: &str) -> Result<Option<Tree<'r>>> {
+    if dir_path.is_empty() {
+        return Ok(Some(root.clone()));
+    }
+
+    match root.get_path(Path::new(dir_path)) {
+        Ok(entry) if entry.kind() == Some(ObjectType::Tree) => {
+            Ok(Some(repo.find_tree(entry.id())?))
+        }
+        Ok(_) => Ok(None),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Map entry ids by name for the directory at `dir_path` in a commit.
+fn dir_entries(
+    repo: &Repository,
+    commit: &git2::Commit,
+    dir_path: &str,
+) -> Result<Option<(Oid, HashMap<String, Oid>)>> {
+    let Some(tree) = subtree(repo, &commit.tree()?, dir_path)? else {
+        return Ok(None);
+    };
+
+    let entries = tree
+        .iter()
+        .map(|e| (e.name().unwrap_or("").to_string(), e.id()))
+        .collect();
+    Ok(Some((tree.id(), entries)))
+}
+
+/// Walk history from `start` and find the newest commit that changed each entry.
+///
+/// A commit changed an entry when the entry differs from every parent, so
+/// merges only count when they introduced the change themselves.
+fn find_last_commits(
+    repo: &Repository,
+    start: Oid,
+    dir_path: &str,
+    entries: &[TreeEntryInfo],
+) -> Result<HashMap<String, CommitLogEntry>> {
+    let mut pending: HashSet<String> = entries.iter().map(|e| e.name.clone()).collect();
+    let mut found = HashMap::new();
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    revwalk.push(start)?;
+
+    for oid in revwalk.take(LAST_COMMIT_WALK_LIMIT) {
+        if pending.is_empty() {
+            break;
+        }
+
+        let commit = repo.find_commit(oid?)?;
+        let Some((tree_id, current)) = dir_entries(repo, &commit, dir_path)? else {
+            continue;
+        };
+
+        let mut parents = Vec::new();
+        for parent in commit.parents() {
+            parents.push(dir_entries(repo, &parent, dir_path)?);
+        }
+
+        // Directory unchanged relative to some parent: nothing here was touched
+        if parents
+            .iter()
+            .flatten()
+            .any(|(parent_tree, _)| *parent_tree == tree_id)
+        {
+            continue;
+        }
+
+        let touched: Vec<String> = pending
+            .iter()
+            .filter(|name| {
+                let id = current.get(*name);
+                id.is_some()
+                    && parents.iter().all(|parent| {
+                        parent.as_ref().and_then(|(_, entries)| entries.get(*name)) != id
+                    })
+            })
+            .cloned()
+            .collect();
+
+        for name in touched {
+            pending.remove(&name);
+            found.insert(name, CommitLogEntry::from_commit(&commit));
+        }
+    }
+
+    Ok(found)
+}
+
+/// Classify an entry from its object type and file mode.
+fn entry_kind(kind: Option<ObjectType>, filemode: i32) -> TreeEntryKind {
+    match (kind, filemode) {
+        (Some(ObjectType::Tree), _) => TreeEntryKind::Directory,
+        (Some(ObjectType::Commit), _) => TreeEntryKind::Submodule,
+        (_, 0o120_000) => TreeEntryKind::Symlink,
+        (_, 0o100_755) => TreeEntryKind::Executable,
+        _ => TreeEntryKind::File,
+    }
+}
+
+fn join_path(dir_path: &str, name: &str) -> String {
+    if dir_path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{dir_path}/{name}")
+    }
+}
+
+/// Detect the text encoding and decode.
+///
+/// Recognises UTF-8 and UTF-16 byte order marks, plain UTF-8, and falls back
+/// to Latin-1 so that any byte sequence can be displayed.
+fn decode_text(data: &[u8]) -> (&'static str, String) {
+    if let Some(rest) = data.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return ("utf-8-bom", decode_utf8(rest));
+    }
+    if let Some(rest) = data.strip_prefix(&[0xFF, 0xFE]) {
+        return ("utf-16le", decode_utf16(rest, u16::from_le_bytes));
+    }
+    if let Some(rest) = data.strip_prefix(&[0xFE, 0xFF]) {
+        return ("utf-16be", decode_utf16(rest, u16::from_be_bytes));
+    }
+
+    match std::str::from_utf8(data) {
+        Ok(text) => ("utf-8", text.to_string()),
+        // Only the last character was cut by the size cap
+        Err(e) if e.error_len().is_none() => ("utf-8", decode_utf8(data)),
+        Err(_) => ("latin-1", data.iter().map(|&b| char::from(b)).collect()),
+    }
+}
+
+/// Decode UTF-8, dropping an incomplete trailing character.
+fn decode_utf8(data: &[u8]) -> String {
+    match std::str::from_utf8(data) {
+        Ok(text) => text.to_string(),
+        Err(e) if e.error_len().is_none() => {
+            String::from_utf8_lossy(&data[..e.valid_up_to()]).into_owned()
+        }
+        Err(_) => String::from_utf8_lossy(data).into_owned(),
+    }
+}
+
+fn decode_utf16(data: &[u8], to_unit: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|pair| to_unit([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
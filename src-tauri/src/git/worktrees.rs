@@ -0,0 +1,274 @@
+//! Linked worktree management.
+//!
+//! Works from the main repository or any of its linked worktrees: the list
+//! always starts with the main worktree, followed by the linked ones.
+
+use git2::{
+    BranchType, Repository, StatusOptions, WorktreeAddOptions, WorktreeLockStatus,
+    WorktreePruneOptions,
+};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::branch_ops::create_branch;
+use super::status::{check_repo_health, HealthOptions, RepoHealth};
+use crate::error::{DevBaseError, Result};
+
+/// A working tree of a repository.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorktreeInfo {
+    /// Worktree name (`None` for the main worktree)
+    pub name: Option<String>,
+    pub path: PathBuf,
+    pub is_main: bool,
+    /// Checked out branch (`None` when detached or unreadable)
+    pub branch: Option<String>,
+    pub head_oid: Option<String>,
+    pub is_locked: bool,
+    pub lock_reason: Option<String>,
+    /// The working tree is gone and its administrative files can be pruned
+    pub is_prunable: bool,
+    /// Health of the working tree, when requested and it exists
+    pub health: Option<RepoHealth>,
+}
+
+/// Open the main repository of `path`, which may be a linked worktree.
+pub fn open_main_repository(path: &Path) -> Result<Repository> {
+    let repo = Repository::open(path)?;
+    if !repo.is_worktree() {
+        return Ok(repo);
+    }
+    // A linked worktree's git dir is `<main git dir>/worktrees/<name>/`
+    let common_dir =
+        repo.path()
+            .parent()
+            .and_then(Path::parent)
+            .ok_or_else(|| DevBaseError::Internal {
+                message: format!("Unexpected worktree git dir {}", repo.path().display()),
+            })?;
+    Ok(Repository::open(common_dir)?)
+}
+
+/// List the main worktree and all linked worktrees.
+pub fn list_worktrees(path: &Path, with_health: bool) -> Result<Vec<WorktreeInfo>> {
+    let repo = open_main_repository(path)?;
+    let health = |path: &Path| {
+        (with_health && path.exists()).then(|| check_repo_health(path, &HealthOptions::default()))
+    };
+
+    let mut worktrees = Vec::new();
+    if let Some(workdir) = repo.workdir() {
+        let (branch, head_oid) = head_of(&repo);
+        worktrees.push(WorktreeInfo {
+            name: None,
+            path: workdir.to_path_buf(),
+            is_main: true,
+            branch,
+            head_oid,
+            is_locked: false,
+            lock_reason: None,
+            is_prunable: false,
+            health: health(workdir),
+        });
+    }
+
+    for name in repo.worktrees()?.iter().flatten() {
+        let worktree = repo.find_worktree(name)?;
+        let (is_locked, lock_reason) = match worktree.is_locked()? {
+            WorktreeLockStatus::Locked(reason) => (
+                true,
+                reason
+                    .map(|r| r.trim().to_string())
+                    .filter(|r| !r.is_empty()),
+            ),
+            WorktreeLockStatus::Unlocked => (false, None),
+        };
+        let (branch, head_oid) = Repository::open_from_worktree(&worktree)
+            .map(|linked| head_of(&linked))
+            .unwrap_or_default();
+        let path = worktree.path().to_path_buf();
+
+        worktrees.push(WorktreeInfo {
+            name: Some(name.to_string()),
+            is_main: false,
+            branch,
+            head_oid,
+            is_locked,
+            lock_reason,
+            is_prunable: worktree.is_prunable(None)?,
+            health: health(&path),
+            path,
+        });
+    }
+
+    Ok(worktrees)
+}
+
+fn head_of(repo: &Repository) -> (Option<String>, Option<String>) {
+    match repo.head() {
+        Ok(head) => (
+            head.is_branch()
+                .then(|| head.shorthand().map(String::from))
+                .flatten(),
+            head.target().map(|oid| oid.to_string()),
+        ),
+        Err(_) => (None, None),
+    }
+}
+
+/// Add a worktree at `worktree_path` with `branch` checked out.
+///
+/// A missing local branch is created from `start_point`, or else from the
+/// remote branch of the same name, or else from HEAD.
+pub fn add_worktree(
+    path: &Path,
+    branch: &str,
+    worktree_path: &Path,
+    start_point: Option<&str>,
+) -> Result<WorktreeInfo> {
+    let repo = open_main_repository(path)?;
+    let main_path = repo.workdir().unwrap_or_else(|| repo.path()).to_path_buf();
+
+    if worktree_path.exists() && fs::read_dir(worktree_path)?.next().is_some() {
+        return Err(DevBaseError::Config {
+            message: format!(
+                "{} already exists and is not empty",
+                worktree_path.display()
+            ),
+        });
+    }
+    if let Some(existing) = list_worktrees(&main_path, false)?
+        .into_iter()
+        .find(|w| w.branch.as_deref() == Some(branch))
+    {
+        return Err(DevBaseError::Config {
+            message: format!(
+                "Branch '{branch}' is already checked out at {}",
+                existing.path.display()
+            ),
+        });
+    }
+
+    if repo.find_branch(branch, BranchType::Local).is_err() {
+        let remote = start_point
+            .is_none()
+            .then(|| remote_branch_named(&repo, branch))
+            .flatten();
+        create_branch(&main_path, branch, start_point.or(remote.as_deref()))?;
+    }
+    let reference = repo
+        .find_branch(branch, BranchType::Local)?
+        .into_reference();
+
+    let name = unique_worktree_name(&repo, worktree_path);
+    let mut options = WorktreeAddOptions::new();
+    options.reference(Some(&reference));
+    repo.worktree(&name, worktree_path, Some(&options))?;
+
+    list_worktrees(&main_path, true)?
+        .into_iter()
+        .find(|w| w.name.as_deref() == Some(name.as_str()))
+        .ok_or_else(|| DevBaseError::Internal {
+            message: format!("Worktree {name} was not created"),
+        })
+}
+
+/// `origin/<branch>`, or the same branch on another remote.
+fn remote_branch_named(repo: &Repository, branch: &str) -> Option<String> {
+    let mut remotes: Vec<String> = repo
+        .remotes()
+        .ok()?
+        .iter()
+        .flatten()
+        .map(String::from)
+        .collect();
+    remotes.sort_by_key(|remote| remote != "origin");
+    remotes
+        .into_iter()
+        .map(|remote| format!("{remote}/{branch}"))
+        .find(|name| repo.find_branch(name, BranchType::Remote).is_ok())
+}
+
+/// Name for the worktree's administrative directory, from its path.
+fn unique_worktree_name(repo: &Repository, worktree_path: &Path) -> String {
+    let base: String = worktree_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "worktree".to_string())
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let taken = |name: &str| repo.path().join("worktrees").join(name).exists();
+    if !taken(&base) {
+        return base;
+    }
+    (2..)
+        .map(|n| format!("{base}-{n}"))
+        .find(|name| !taken(name))
+        .unwrap_or(base)
+}
+
+/// Remove a linked worktree and its working tree. Without `force`, refuses
+/// when it is locked or has uncommitted changes.
+pub fn remove_worktree(path: &Path, name: &str, force: bool) -> Result<()> {
+    let repo = open_main_repository(path)?;
+    let worktree = repo.find_worktree(name).map_err(|_| DevBaseError::Config {
+        message: format!("Worktree '{name}' not found"),
+    })?;
+
+    if !force {
+        if let WorktreeLockStatus::Locked(reason) = worktree.is_locked()? {
+            return Err(DevBaseError::Config {
+                message: match reason
+                    .map(|r| r.trim().to_string())
+                    .filter(|r| !r.is_empty())
+                {
+                    Some(reason) => format!("Worktree '{name}' is locked: {reason}"),
+                    None => format!("Worktree '{name}' is locked"),
+                },
+            });
+        }
+        if let Ok(linked) = Repository::open_from_worktree(&worktree) {
+            let mut opts = StatusOptions::new();
+            opts.include_untracked(true).include_ignored(false);
+            let changed: Vec<String> = linked
+                .statuses(Some(&mut opts))?
+                .iter()
+                .filter_map(|entry| entry.path().map(String::from))
+                .collect();
+            if !changed.is_empty() {
+                return Err(DevBaseError::LocalChanges { files: changed });
+            }
+        }
+    }
+
+    worktree.prune(Some(
+        WorktreePruneOptions::new()
+            .valid(true)
+            .locked(force)
+            .working_tree(true),
+    ))?;
+    Ok(())
+}
+
+/// Prune the administrative files of worktrees whose working tree is gone.
+/// Returns the names of the pruned worktrees.
+pub fn prune_worktrees(path: &Path) -> Result<Vec<String>> {
+    let repo = open_main_repository(path)?;
+    let mut pruned = Vec::new();
+    for name in repo.worktrees()?.iter().flatten() {
+        let worktree = repo.find_worktree(name)?;
+        if worktree.is_prunable(None)? {
+            worktree.prune(None)?;
+            pruned.push(name.to_string());
+        }
+    }
+    Ok(pruned)
+}
@@ -0,0 +1,90 @@
+//! Cheap change detection for cached health.
+//!
+//! A fingerprint captures the HEAD commit plus the modification times of the
+//! index and the refs, which together change whenever a commit, checkout,
+//! stage, fetch or branch update happens.
+
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::error::Result;
+
+/// Inputs a cached health result was computed from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthFingerprint {
+    /// Commit HEAD points to (`None` for an unborn branch)
+    pub head_oid: Option<String>,
+    /// Modification time of `.git/index` (milliseconds since the epoch)
+    pub index_mtime: Option<i64>,
+    /// Latest modification time of `HEAD`, `packed-refs` and `refs/`
+    pub refs_mtime: Option<i64>,
+}
+
+/// Compute the fingerprint of a repository without reading its working tree.
+pub fn compute_fingerprint(path: &Path) -> Result<HealthFingerprint> {
+    let repo = Repository::open(path)?;
+    let git_dir = repo.path();
+    let common_dir = common_dir(git_dir);
+
+    let head_oid = repo
+        .head()
+        .ok()
+        .and_then(|head| head.target())
+        .map(|oid| oid.to_string());
+
+    let refs_mtime = [
+        mtime_millis(&git_dir.join("HEAD")),
+        mtime_millis(&common_dir.join("packed-refs")),
+        newest_mtime(&common_dir.join("refs")),
+    ]
+    .into_iter()
+    .flatten()
+    .max();
+
+    Ok(HealthFingerprint {
+        head_oid,
+        index_mtime: mtime_millis(&git_dir.join("index")),
+        refs_mtime,
+    })
+}
+
+/// Directory holding the shared refs.
+///
+/// Linked worktrees keep their own `HEAD` and index but point at the main
+/// repository's git directory through a `commondir` file.
+fn common_dir(git_dir: &Path) -> PathBuf {
+    match fs::read_to_string(git_dir.join("commondir")) {
+        Ok(content) => git_dir.join(content.trim()),
+        Err(_) => git_dir.to_path_buf(),
+    }
+}
+
+fn mtime_millis(path: &Path) -> Option<i64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let millis = modified.duration_since(UNIX_EPOCH).ok()?.as_millis();
+    i64::try_from(millis).ok()
+}
+
+/// Newest modification time of a directory tree, including the directories
+/// themselves so that deleted refs are noticed.
+fn newest_mtime(dir: &Path) -> Option<i64> {
+    let mut newest = mtime_millis(dir);
+    let Ok(entries) = fs::read_dir(dir) else {
+        return newest;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let mtime = if path.is_dir() {
+            newest_mtime(&path)
+        } else {
+            mtime_millis(&path)
+        };
+        newest = newest.max(mtime);
+    }
+
+    newest
+}
@@ -0,0 +1,18 @@
+//! Dashboard health module.
+//!
+//! Caches repository health keyed by the inputs that invalidate it,
+//! recomputes stale entries in parallel, keeps a history of changes and
+//! scores repositories against policy rules.
+
+mod fingerprint;
+mod policy;
+mod refresh;
+mod snapshots;
+
+#[cfg(test)]
+mod tests;
+
+pub use fingerprint::*;
+pub use policy::*;
+pub use refresh::*;
+pub use snapshots::*;
@@ -0,0 +1,373 @@
+//! Policy rules and composite health score.
+//!
+//! Rules are stored as JSON in the `policy_rules` setting and evaluated per
+//! repository. Each rule can be limited to repositories with a tag or under
+//! a scan path.
+
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use super::snapshots::HealthCondition;
+use crate::db::{Database, StoredRepo};
+use crate::error::{DevBaseError, Result};
+use crate::git::{resolve_default_branch, RepoHealth};
+
+/// Setting key holding the rule set.
+pub const POLICY_RULES_SETTING: &str = "policy_rules";
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// How bad a violation is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// Weight of a rule of this severity in the score.
+    fn weight(self) -> u32 {
+        match self {
+            Self::Info => 1,
+            Self::Warning => 3,
+            Self::Error => 5,
+        }
+    }
+}
+
+/// What a rule checks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PolicyCheck {
+    /// Uncommitted changes may not be older than `days`
+    MaxUncommittedAgeDays { days: u32 },
+    /// At most `max` stashes
+    MaxStashCount { max: u32 },
+    /// Each name must exist at the top of the working tree, with any extension
+    RequiredFiles { files: Vec<String> },
+    /// The default branch must not be called `name`
+    DefaultBranchNot { name: String },
+    /// `user.email` must end in `@domain`
+    EmailDomain { domain: String },
+    /// HEAD must point at a branch
+    NoDetachedHead,
+}
+
+/// Which repositories a rule applies to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PolicyScope {
+    #[default]
+    All,
+    /// Repositories with this tag
+    Tag { name: String },
+    /// Repositories under this scan path
+    ScanPath { path: String },
+}
+
+/// A configurable rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyRule {
+    /// Unique identifier
+    pub id: String,
+    pub check: PolicyCheck,
+    pub severity: Severity,
+    #[serde(default)]
+    pub scope: PolicyScope,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// A rule a repository does not satisfy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleViolation {
+    pub rule_id: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Policy evaluation result for one repository.
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyReport {
+    pub repo_id: i64,
+    /// 0-100, the severity-weighted share of applicable rules that pass
+    pub score: u32,
+    /// Number of applicable rules that could be checked
+    pub rules_evaluated: u32,
+    /// Violations, most severe first
+    pub violations: Vec<RuleViolation>,
+    /// Applicable rules that could not be checked because the repository or
+    /// its health could not be read; they do not count towards the score
+    pub unknown_rules: Vec<String>,
+}
+
+/// Result of checking one rule.
+enum RuleOutcome {
+    Pass,
+    Violation(String),
+    /// The repository could not be read
+    Unknown,
+}
+
+impl From<Option<String>> for RuleOutcome {
+    fn from(violation: Option<String>) -> Self {
+        violation.map_or(Self::Pass, Self::Violation)
+    }
+}
+
+/// Everything a rule may look at.
+#[derive(Debug, Clone)]
+pub struct PolicyContext<'a> {
+    pub repo_path: &'a Path,
+    pub health: &'a RepoHealth,
+    pub tags: &'a [String],
+    /// Start of the current dirty streak (Unix timestamp)
+    pub dirty_since: Option<i64>,
+    pub now: i64,
+}
+
+/// Rules used until the user saves their own.
+pub fn default_policy_rules() -> Vec<PolicyRule> {
+    let rule = |id: &str, check, severity| PolicyRule {
+        id: id.to_string(),
+        check,
+        severity,
+        scope: PolicyScope::All,
+        enabled: true,
+    };
+
+    vec![
+        rule(
+            "stale-uncommitted",
+            PolicyCheck::MaxUncommittedAgeDays { days: 7 },
+            Severity::Warning,
+        ),
+        rule("stash-count", PolicyCheck::MaxStashCount { max: 5 }, Severity::Info),
+        rule(
+            "license-readme",
+            PolicyCheck::RequiredFiles {
+                files: vec!["LICENSE".to_string(), "README".to_string()],
+            },
+            Severity::Info,
+        ),
+        rule(
+            "default-branch-name",
+            PolicyCheck::DefaultBranchNot {
+                name: "master".to_string(),
+            },
+            Severity::Info,
+        ),
+        rule("no-detached-head", PolicyCheck::NoDetachedHead, Severity::Warning),
+    ]
+}
+
+/// Load the stored rule set, falling back to the defaults.
+pub fn load_policy_rules(db: &Database) -> Result<Vec<PolicyRule>> {
+    match db.get_setting(POLICY_RULES_SETTING)? {
+        Some(json) => serde_json::from_str(&json).map_err(|e| DevBaseError::Config {
+            message: format!("Invalid policy rules: {e}"),
+        }),
+        None => Ok(default_policy_rules()),
+    }
+}
+
+/// Validate and store a rule set.
+pub fn save_policy_rules(db: &Database, rules: &[PolicyRule]) -> Result<()> {
+    let mut ids = HashSet::new();
+    for rule in rules {
+        if rule.id.trim().is_empty() {
+            return Err(DevBaseError::Config {
+                message: "Policy rule id must not be empty".to_string(),
+            });
+        }
+        if !ids.insert(rule.id.as_str()) {
+            return Err(DevBaseError::Config {
+                message: format!("Duplicate policy rule id: {}", rule.id),
+            });
+        }
+    }
+
+    let json = serde_json::to_string(rules).map_err(|e| DevBaseError::Internal {
+        message: format!("Failed to serialize policy rules: {e}"),
+    })?;
+    db.set_setting(POLICY_RULES_SETTING, &json)
+}
+
+/// Evaluate the rules for stored repositories and their health.
+pub fn evaluate_repos(
+    db: &Database,
+    repos: &[(StoredRepo, RepoHealth)],
+    rules: &[PolicyRule],
+    now: i64,
+) -> Result<Vec<PolicyReport>> {
+    let dirty_since: HashMap<i64, i64> = db
+        .get_condition_streaks(HealthCondition::Dirty)?
+        .into_iter()
+        .map(|streak| (streak.repo_id, streak.since))
+        .collect();
+
+    let mut reports = Vec::with_capacity(repos.len());
+    for (repo, health) in repos {
+        let tags = db.get_repo_tags(repo.id)?;
+        let context = PolicyContext {
+            repo_path: Path::new(&repo.path),
+            health,
+            tags: &tags,
+            dirty_since: dirty_since.get(&repo.id).copied(),
+            now,
+        };
+        reports.push(PolicyReport {
+            repo_id: repo.id,
+            ..evaluate_policies(rules, &context)
+        });
+    }
+    Ok(reports)
+}
+
+/// Evaluate rules against a repository. `repo_id` of the result is `0`.
+pub fn evaluate_policies(rules: &[PolicyRule], context: &PolicyContext) -> PolicyReport {
+    let repo = Repository::open(context.repo_path).ok();
+
+    let mut total_weight = 0;
+    let mut passed_weight = 0;
+    let mut rules_evaluated = 0;
+    let mut violations = Vec::new();
+    let mut unknown_rules = Vec::new();
+
+    for rule in rules
+        .iter()
+        .filter(|rule| rule.enabled && in_scope(&rule.scope, context))
+    {
+        match check_rule(&rule.check, context, repo.as_ref()) {
+            RuleOutcome::Unknown => {
+                unknown_rules.push(rule.id.clone());
+                continue;
+            }
+            RuleOutcome::Pass => passed_weight += rule.severity.weight(),
+            RuleOutcome::Violation(message) => violations.push(RuleViolation {
+                rule_id: rule.id.clone(),
+                severity: rule.severity,
+                message,
+            }),
+        }
+        rules_evaluated += 1;
+        total_weight += rule.severity.weight();
+    }
+
+    violations.sort_by_key(|v| std::cmp::Reverse(v.severity));
+
+    // No applicable rules means nothing is violated
+    let score = (passed_weight * 100).checked_div(total_weight).unwrap_or(100);
+
+    PolicyReport {
+        repo_id: 0,
+        score,
+        rules_evaluated,
+        violations,
+        unknown_rules,
+    }
+}
+
+fn in_scope(scope: &PolicyScope, context: &PolicyContext) -> bool {
+    match scope {
+        PolicyScope::All => true,
+        PolicyScope::Tag { name } => context.tags.iter().any(|tag| tag == name),
+        PolicyScope::ScanPath { path } => context.repo_path.starts_with(path),
+    }
+}
+
+/// Check one rule.
+fn check_rule(
+    check: &PolicyCheck,
+    context: &PolicyContext,
+    repo: Option<&Repository>,
+) -> RuleOutcome {
+    let health = context.health;
+    // A failed health check leaves the fields at their defaults
+    let health_known = health.error.is_none();
+
+    match check {
+        PolicyCheck::MaxUncommittedAgeDays { .. }
+        | PolicyCheck::MaxStashCount { .. }
+        | PolicyCheck::NoDetachedHead
+            if !health_known =>
+        {
+            RuleOutcome::Unknown
+        }
+        PolicyCheck::MaxUncommittedAgeDays { days } => {
+            let Some(since) = context.dirty_since.filter(|_| health.is_dirty) else {
+                return RuleOutcome::Pass;
+            };
+            let age_days = (context.now - since) / SECONDS_PER_DAY;
+            (age_days >= i64::from(*days))
+                .then(|| format!("Uncommitted changes for {age_days} days (limit {days})"))
+                .into()
+        }
+        PolicyCheck::MaxStashCount { max } => (health.stash_count > *max)
+            .then(|| format!("{} stashes (limit {max})", health.stash_count))
+            .into(),
+        PolicyCheck::RequiredFiles { files } => {
+            let Some(workdir) = repo.and_then(Repository::workdir) else {
+                return RuleOutcome::Unknown;
+            };
+            let missing: Vec<&str> = files
+                .iter()
+                .filter(|name| !has_top_level_file(workdir, name))
+                .map(String::as_str)
+                .collect();
+            (!missing.is_empty())
+                .then(|| format!("Missing {}", missing.join(", ")))
+                .into()
+        }
+        PolicyCheck::DefaultBranchNot { name } => {
+            let Some(repo) = repo else {
+                return RuleOutcome::Unknown;
+            };
+            resolve_default_branch(repo)
+                .filter(|default| default.branch_name == *name)
+                .map(|default| format!("Default branch is `{}`", default.branch_name))
+                .into()
+        }
+        PolicyCheck::EmailDomain { domain } => {
+            let Some(config) = repo.and_then(|repo| repo.config().ok()) else {
+                return RuleOutcome::Unknown;
+            };
+            let suffix = format!("@{}", domain.trim_start_matches('@')).to_lowercase();
+            match config.get_string("user.email").ok() {
+                Some(email) if email.to_lowercase().ends_with(&suffix) => RuleOutcome::Pass,
+                Some(email) => {
+                    RuleOutcome::Violation(format!("user.email `{email}` is not in {domain}"))
+                }
+                None => RuleOutcome::Violation("user.email is not set".to_string()),
+            }
+        }
+        PolicyCheck::NoDetachedHead => health
+            .is_detached
+            .then(|| "HEAD is detached".to_string())
+            .into(),
+    }
+}
+
+/// Check for `name` or `name.<ext>` at the top of the working tree, ignoring case.
+fn has_top_level_file(workdir: &Path, name: &str) -> bool {
+    let Ok(entries) = std::fs::read_dir(workdir) else {
+        return false;
+    };
+    let name = name.to_lowercase();
+
+    entries.flatten().any(|entry| {
+        let file_name = entry.file_name().to_string_lossy().to_lowercase();
+        file_name == name
+            || file_name
+                .strip_prefix(&name)
+                .is_some_and(|rest| rest.starts_with('.'))
+    })
+}
@@ -0,0 +1,234 @@
+//! Parallel health recomputation backed by the SQLite cache.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+
+use super::fingerprint::{compute_fingerprint, HealthFingerprint};
+use crate::db::{CachedHealth, Database, StoredRepo};
+use crate::error::Result;
+use crate::git::{self, HealthOptions, RepoHealth};
+
+/// Cached entries older than this are recomputed even when the fingerprint
+/// matches, since editing a tracked file touches neither the index nor refs.
+pub const HEALTH_CACHE_TTL_SECS: i64 = 60;
+
+/// Time budget used when the `health_budget_ms` setting is missing or invalid.
+pub const DEFAULT_HEALTH_BUDGET_MS: u64 = 5000;
+
+/// Upper bound for the health worker pool.
+const MAX_HEALTH_WORKERS: usize = 8;
+
+/// A repository whose health should be computed.
+#[derive(Debug, Clone)]
+pub struct HealthJob {
+    pub repo_id: i64,
+    pub path: PathBuf,
+    pub options: HealthOptions,
+}
+
+/// Which repositories a refresh recomputes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshMode {
+    /// Only entries that are missing, expired or whose fingerprint changed
+    Stale,
+    /// Every repository
+    All,
+}
+
+/// Health jobs waiting for the single background refresh.
+///
+/// Jobs submitted while a refresh runs are merged per repository (`All` wins
+/// over `Stale`) and picked up by the running refresh when its pass ends.
+#[derive(Debug, Default)]
+pub struct RefreshQueue {
+    running: bool,
+    pending: HashMap<i64, (HealthJob, RefreshMode)>,
+}
+
+impl RefreshQueue {
+    /// Queue jobs. Returns `true` when no refresh is running and the caller
+    /// must start one that drains the queue with `next_batch`.
+    pub fn submit(&mut self, jobs: Vec<HealthJob>, mode: RefreshMode) -> bool {
+        for job in jobs {
+            let mode = match self.pending.get(&job.repo_id) {
+                Some((_, RefreshMode::All)) => RefreshMode::All,
+                _ => mode,
+            };
+            self.pending.insert(job.repo_id, (job, mode));
+        }
+        if self.running || self.pending.is_empty() {
+            return false;
+        }
+        self.running = true;
+        true
+    }
+
+    /// Take the pending jobs, grouped by mode. Returns `None`, and marks the
+    /// refresh finished, once nothing is pending.
+    pub fn next_batch(&mut self) -> Option<Vec<(RefreshMode, Vec<HealthJob>)>> {
+        if self.pending.is_empty() {
+            self.running = false;
+            return None;
+        }
+        let mut all = Vec::new();
+        let mut stale = Vec::new();
+        for (job, mode) in self.pending.drain().map(|(_, pending)| pending) {
+            match mode {
+                RefreshMode::All => all.push(job),
+                RefreshMode::Stale => stale.push(job),
+            }
+        }
+        Some(
+            [(RefreshMode::All, all), (RefreshMode::Stale, stale)]
+                .into_iter()
+                .filter(|(_, jobs)| !jobs.is_empty())
+                .collect(),
+        )
+    }
+
+    /// Mark the refresh finished without running the pending jobs, e.g. after
+    /// it panicked. They run with the next refresh.
+    pub fn abandon(&mut self) {
+        self.running = false;
+    }
+}
+
+/// Number of health workers: one per core, capped at `MAX_HEALTH_WORKERS`.
+pub fn default_worker_count() -> usize {
+    thread::available_parallelism()
+        .map_or(1, std::num::NonZeroUsize::get)
+        .min(MAX_HEALTH_WORKERS)
+}
+
+/// Build health jobs for repositories with their stored options.
+///
+/// Repositories without their own budget use the `health_budget_ms` setting.
+/// A budget of `0` means unlimited.
+pub fn health_jobs(db: &Database, repos: &[StoredRepo]) -> Result<Vec<HealthJob>> {
+    let default_budget = db
+        .get_setting("health_budget_ms")?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_HEALTH_BUDGET_MS);
+    let stored: HashMap<i64, HealthOptions> = db.get_all_health_options()?.into_iter().collect();
+
+    Ok(repos
+        .iter()
+        .map(|repo| {
+            let mut options = stored.get(&repo.id).copied().unwrap_or_default();
+            options.budget_ms = options
+                .budget_ms
+                .or(Some(default_budget))
+                .filter(|&ms| ms > 0);
+            HealthJob {
+                repo_id: repo.id,
+                path: PathBuf::from(&repo.path),
+                options,
+            }
+        })
+        .collect())
+}
+
+/// Check whether a cached entry can be reused.
+///
+/// Partial and failed results are always recomputed.
+pub fn is_fresh(cached: &CachedHealth, fingerprint: &HealthFingerprint, now: i64) -> bool {
+    cached.fingerprint == *fingerprint
+        && !cached.health.is_partial
+        && cached.health.error.is_none()
+        && now - cached.computed_at < HEALTH_CACHE_TTL_SECS
+}
+
+/// Recompute health for `jobs` on a pool of `workers` threads.
+///
+/// Each result is written to the cache, recorded as a snapshot if it changed,
+/// and passed to `on_update` as soon as it is ready. Repositories that cannot
+/// be read are reported through `RepoHealth::error` and not recorded. Returns
+/// the recomputed entries in job order.
+pub fn refresh_health<F>(
+    db: &Database,
+    jobs: &[HealthJob],
+    mode: RefreshMode,
+    workers: usize,
+    on_update: F,
+) -> Vec<(i64, RepoHealth)>
+where
+    F: Fn(i64, &RepoHealth) + Sync,
+{
+    run_parallel(jobs, workers, |job| {
+        let health = refresh_one(db, job, mode)?;
+        on_update(job.repo_id, &health);
+        Some((job.repo_id, health))
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+fn refresh_one(db: &Database, job: &HealthJob, mode: RefreshMode) -> Option<RepoHealth> {
+    // Taken before computing so that changes made meanwhile invalidate the entry.
+    // Unreadable repositories get an empty fingerprint; their health carries the error.
+    let fingerprint = compute_fingerprint(&job.path).unwrap_or_default();
+
+    if mode == RefreshMode::Stale {
+        let now = chrono::Utc::now().timestamp();
+        let cached = db.get_cached_health(job.repo_id).ok().flatten();
+        if cached.is_some_and(|cached| is_fresh(&cached, &fingerprint, now)) {
+            return None;
+        }
+    }
+
+    let health = git::check_repo_health(&job.path, &job.options);
+    if let Some(error) = &health.error {
+        tracing::warn!(path = ?job.path, ?error, "Repository health check failed");
+    }
+
+    if let Err(e) = db.save_cached_health(job.repo_id, &fingerprint, &health) {
+        tracing::warn!(repo_id = job.repo_id, ?e, "Failed to cache repository health");
+    }
+
+    // Partial and failed results would show up as spurious changes in the history
+    if !health.is_partial && health.error.is_none() {
+        let now = chrono::Utc::now().timestamp();
+        if let Err(e) = db.record_health_snapshot(job.repo_id, &health, now) {
+            tracing::warn!(repo_id = job.repo_id, ?e, "Failed to record health snapshot");
+        }
+    }
+
+    Some(health)
+}
+
+/// Run `work` over `items` on at most `workers` threads, keeping item order.
+pub fn run_parallel<T, R, F>(items: &[T], workers: usize, work: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, items.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                let result = work(item);
+                if let Ok(mut results) = results.lock() {
+                    results[index] = Some(result);
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap_or_else(PoisonError::into_inner)
+        .into_iter()
+        .flatten()
+        .collect()
+}
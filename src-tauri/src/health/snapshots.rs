@@ -0,0 +1,89 @@
+//! Health history: snapshots recorded on change, streaks and retention.
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::Database;
+use crate::error::Result;
+use crate::git::{RepoHealth, RepoStatus};
+
+/// Retention used when the `health_snapshot_retention_days` setting is missing or invalid.
+pub const DEFAULT_SNAPSHOT_RETENTION_DAYS: u32 = 90;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// A condition whose duration is tracked across snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthCondition {
+    /// Uncommitted or staged changes
+    Dirty,
+    /// Current branch is behind its upstream
+    Behind,
+    /// Commits that exist on no remote
+    Unpushed,
+}
+
+impl HealthCondition {
+    /// Snapshot column holding this condition.
+    pub(crate) fn column(self) -> &'static str {
+        match self {
+            Self::Dirty => "is_dirty",
+            Self::Behind => "is_behind",
+            Self::Unpushed => "has_unpushed",
+        }
+    }
+
+    pub fn holds(self, health: &RepoHealth) -> bool {
+        match self {
+            Self::Dirty => health.is_dirty,
+            Self::Behind => health.commits_behind > 0,
+            Self::Unpushed => health.commits_ahead > 0 || health.unpushed_branch_count > 0,
+        }
+    }
+}
+
+/// Health of a repository at one point in time.
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthSnapshot {
+    pub id: i64,
+    pub repo_id: i64,
+    pub status: RepoStatus,
+    pub health: RepoHealth,
+    /// When the change was observed (Unix timestamp)
+    pub recorded_at: i64,
+}
+
+/// A condition that has held continuously since `since`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConditionStreak {
+    pub repo_id: i64,
+    pub condition: HealthCondition,
+    /// First snapshot of the current streak (Unix timestamp)
+    pub since: i64,
+}
+
+/// Repositories in which `condition` has held for at least `min_days`.
+pub fn repos_in_condition(
+    db: &Database,
+    condition: HealthCondition,
+    min_days: u32,
+    now: i64,
+) -> Result<Vec<ConditionStreak>> {
+    let cutoff = now - i64::from(min_days) * SECONDS_PER_DAY;
+    let mut streaks = db.get_condition_streaks(condition)?;
+    streaks.retain(|streak| streak.since <= cutoff);
+    streaks.sort_by_key(|streak| streak.since);
+    Ok(streaks)
+}
+
+/// Delete snapshots older than the configured retention.
+///
+/// The newest snapshot of each repository is always kept.
+pub fn prune_snapshots(db: &Database, now: i64) -> Result<usize> {
+    let retention_days = db
+        .get_setting("health_snapshot_retention_days")?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_SNAPSHOT_RETENTION_DAYS);
+
+    db.prune_health_snapshots(now - i64::from(retention_days) * SECONDS_PER_DAY)
+}
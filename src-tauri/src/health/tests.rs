@@ -0,0 +1,464 @@
+//! Tests for health module.
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use crate::db::Database;
+    use crate::scanner::DiscoveredRepo;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::tempdir;
+
+    fn git(path: &Path, args: &[&str]) {
+        Command::new("git")
+            .args(args)
+            .current_dir(path)
+            .output()
+            .expect("Failed to run git");
+    }
+
+    fn init_repo_with_commit(path: &Path) {
+        fs::create_dir_all(path).unwrap();
+        git(path, &["init"]);
+        git(path, &["config", "user.email", "test@test.com"]);
+        git(path, &["config", "user.name", "Test User"]);
+        fs::write(path.join("README.md"), "# Test").unwrap();
+        git(path, &["add", "-A"]);
+        git(path, &["commit", "-m", "initial"]);
+    }
+
+    fn register(db: &Database, path: &Path) -> HealthJob {
+        let repo_id = db
+            .upsert_repository(&DiscoveredRepo {
+                path: path.to_path_buf(),
+                name: path.file_name().unwrap().to_string_lossy().to_string(),
+                remote_url: None,
+                default_branch: None,
+                current_branch: None,
+            })
+            .unwrap();
+        HealthJob {
+            repo_id,
+            path: path.to_path_buf(),
+            options: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_changes_on_commit_and_stage() {
+        let temp = tempdir().unwrap();
+        init_repo_with_commit(temp.path());
+
+        let initial = compute_fingerprint(temp.path()).unwrap();
+        assert!(initial.head_oid.is_some());
+        assert!(initial.index_mtime.is_some());
+        assert!(initial.refs_mtime.is_some());
+        assert_eq!(compute_fingerprint(temp.path()).unwrap(), initial);
+
+        fs::write(temp.path().join("new.txt"), "new").unwrap();
+        git(temp.path(), &["add", "new.txt"]);
+        git(temp.path(), &["commit", "-m", "second"]);
+
+        let after = compute_fingerprint(temp.path()).unwrap();
+        assert_ne!(after.head_oid, initial.head_oid);
+    }
+
+    #[test]
+    fn test_run_parallel_keeps_order() {
+        let items: Vec<u32> = (0..50).collect();
+        let results = run_parallel(&items, 4, |n| n * 2);
+        assert_eq!(results, items.iter().map(|n| n * 2).collect::<Vec<_>>());
+
+        assert!(run_parallel(&[] as &[u32], 4, |n| *n).is_empty());
+    }
+
+    #[test]
+    fn test_refresh_queue_merges_requests_while_running() {
+        let job = |repo_id| HealthJob {
+            repo_id,
+            path: format!("/tmp/{repo_id}").into(),
+            options: crate::git::HealthOptions::default(),
+        };
+        let mut queue = RefreshQueue::default();
+
+        assert!(!queue.submit(Vec::new(), RefreshMode::Stale));
+        assert!(queue.submit(vec![job(1)], RefreshMode::Stale));
+        let first = queue.next_batch().unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].0, RefreshMode::Stale);
+
+        // Requests made during the pass wait for it instead of being dropped
+        assert!(!queue.submit(vec![job(2)], RefreshMode::All));
+        assert!(!queue.submit(vec![job(2), job(3)], RefreshMode::Stale));
+        let second = queue.next_batch().unwrap();
+        let ids = |mode| -> Vec<i64> {
+            let mut ids: Vec<i64> = second
+                .iter()
+                .filter(|(m, _)| *m == mode)
+                .flat_map(|(_, jobs)| jobs.iter().map(|j| j.repo_id))
+                .collect();
+            ids.sort_unstable();
+            ids
+        };
+        assert_eq!(ids(RefreshMode::All), vec![2]);
+        assert_eq!(ids(RefreshMode::Stale), vec![3]);
+
+        assert!(queue.next_batch().is_none());
+        assert!(queue.submit(vec![job(4)], RefreshMode::Stale));
+
+        // An abandoned refresh keeps its jobs for the next one
+        queue.abandon();
+        assert!(queue.submit(Vec::new(), RefreshMode::Stale));
+        assert_eq!(queue.next_batch().unwrap()[0].1[0].repo_id, 4);
+    }
+
+    #[test]
+    fn test_refresh_reuses_fresh_cache() {
+        let temp = tempdir().unwrap();
+        let db = Database::new(temp.path().join("test.db")).unwrap();
+        let jobs: Vec<HealthJob> = ["a", "b", "c"]
+            .iter()
+            .map(|name| {
+                let path = temp.path().join(name);
+                init_repo_with_commit(&path);
+                register(&db, &path)
+            })
+            .collect();
+
+        let updates = AtomicUsize::new(0);
+        let first = refresh_health(&db, &jobs, RefreshMode::Stale, 2, |_, _| {
+            updates.fetch_add(1, Ordering::Relaxed);
+        });
+        assert_eq!(first.len(), 3);
+        assert_eq!(updates.load(Ordering::Relaxed), 3);
+        assert_eq!(db.get_all_cached_health().unwrap().len(), 3);
+
+        // Nothing changed: everything is served from the cache
+        let second = refresh_health(&db, &jobs, RefreshMode::Stale, 2, |_, _| {});
+        assert!(second.is_empty());
+
+        // A commit changes the fingerprint of one repository only
+        fs::write(jobs[1].path.join("new.txt"), "new").unwrap();
+        git(&jobs[1].path, &["add", "new.txt"]);
+        git(&jobs[1].path, &["commit", "-m", "second"]);
+        let third = refresh_health(&db, &jobs, RefreshMode::Stale, 2, |_, _| {});
+        assert_eq!(third.len(), 1);
+        assert_eq!(third[0].0, jobs[1].repo_id);
+
+        let forced = refresh_health(&db, &jobs, RefreshMode::All, 2, |_, _| {});
+        assert_eq!(forced.len(), 3);
+    }
+
+    #[test]
+    fn test_expired_cache_entry_is_not_fresh() {
+        let fingerprint = HealthFingerprint::default();
+        let cached = crate::db::CachedHealth {
+            repo_id: 1,
+            fingerprint: fingerprint.clone(),
+            health: Default::default(),
+            computed_at: 1_000,
+        };
+
+        assert!(is_fresh(&cached, &fingerprint, 1_000 + HEALTH_CACHE_TTL_SECS - 1));
+        assert!(!is_fresh(&cached, &fingerprint, 1_000 + HEALTH_CACHE_TTL_SECS));
+
+        let changed = HealthFingerprint {
+            head_oid: Some("abc".to_string()),
+            ..fingerprint
+        };
+        assert!(!is_fresh(&cached, &changed, 1_000));
+    }
+
+
+    #[test]
+    fn test_health_jobs_apply_default_budget() {
+        let temp = tempdir().unwrap();
+        let db = Database::new(temp.path().join("test.db")).unwrap();
+        for name in ["a", "b"] {
+            init_repo_with_commit(&temp.path().join(name));
+            register(&db, &temp.path().join(name));
+        }
+        let repos = db.get_all_repositories().unwrap();
+        let light_id = repos[0].id;
+        db.set_health_options(
+            light_id,
+            &crate::git::HealthOptions {
+                light: true,
+                budget_ms: None,
+            },
+        )
+        .unwrap();
+
+        let jobs = health_jobs(&db, &repos).unwrap();
+        assert!(jobs.iter().all(|job| job.options.budget_ms == Some(5000)));
+        assert!(jobs.iter().any(|job| job.repo_id == light_id && job.options.light));
+
+        db.set_setting("health_budget_ms", "0").unwrap();
+        let jobs = health_jobs(&db, &repos).unwrap();
+        assert!(jobs.iter().all(|job| job.options.budget_ms.is_none()));
+    }
+
+
+    #[test]
+    fn test_refresh_reports_unavailable_repository() {
+        let temp = tempdir().unwrap();
+        let db = Database::new(temp.path().join("test.db")).unwrap();
+        let path = temp.path().join("removed");
+        init_repo_with_commit(&path);
+        let jobs = vec![register(&db, &path)];
+        fs::remove_dir_all(&path).unwrap();
+
+        for _ in 0..2 {
+            let refreshed = refresh_health(&db, &jobs, RefreshMode::Stale, 1, |_, _| {});
+            assert_eq!(refreshed.len(), 1, "failed health is never served from the cache");
+            assert_eq!(refreshed[0].1.status(), crate::git::RepoStatus::Unavailable);
+        }
+        assert!(db.get_health_timeline(jobs[0].repo_id, None, 10).unwrap().is_empty());
+    }
+
+
+    #[test]
+    fn test_condition_streaks_track_current_run() {
+        let temp = tempdir().unwrap();
+        let db = Database::new(temp.path().join("test.db")).unwrap();
+        let day = 24 * 60 * 60;
+        let repo_a = register(&db, &temp.path().join("a")).repo_id;
+        let repo_b = register(&db, &temp.path().join("b")).repo_id;
+
+        let clean = crate::git::RepoHealth::default();
+        let dirty = |count| crate::git::RepoHealth {
+            is_dirty: true,
+            uncommitted_count: count,
+            ..Default::default()
+        };
+
+        // a: dirty, clean, then dirty again for ten days with an extra change in between
+        db.record_health_snapshot(repo_a, &dirty(1), 0).unwrap();
+        db.record_health_snapshot(repo_a, &clean, day).unwrap();
+        db.record_health_snapshot(repo_a, &dirty(1), 2 * day).unwrap();
+        db.record_health_snapshot(repo_a, &dirty(2), 5 * day).unwrap();
+        // b: dirty for one day, then clean
+        db.record_health_snapshot(repo_b, &dirty(1), 10 * day).unwrap();
+        db.record_health_snapshot(repo_b, &clean, 11 * day).unwrap();
+
+        let now = 12 * day;
+        let streaks = repos_in_condition(&db, HealthCondition::Dirty, 7, now).unwrap();
+        assert_eq!(
+            streaks,
+            vec![ConditionStreak {
+                repo_id: repo_a,
+                condition: HealthCondition::Dirty,
+                since: 2 * day,
+            }]
+        );
+        assert!(repos_in_condition(&db, HealthCondition::Dirty, 11, now)
+            .unwrap()
+            .is_empty());
+        assert!(repos_in_condition(&db, HealthCondition::Unpushed, 0, now)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_refresh_records_snapshot_on_change() {
+        let temp = tempdir().unwrap();
+        let db = Database::new(temp.path().join("test.db")).unwrap();
+        let path = temp.path().join("repo");
+        init_repo_with_commit(&path);
+        let jobs = vec![register(&db, &path)];
+
+        refresh_health(&db, &jobs, RefreshMode::All, 1, |_, _| {});
+        refresh_health(&db, &jobs, RefreshMode::All, 1, |_, _| {});
+        assert_eq!(db.get_health_timeline(jobs[0].repo_id, None, 10).unwrap().len(), 1);
+
+        fs::write(path.join("README.md"), "changed").unwrap();
+        refresh_health(&db, &jobs, RefreshMode::All, 1, |_, _| {});
+        let timeline = db.get_health_timeline(jobs[0].repo_id, None, 10).unwrap();
+        assert_eq!(timeline.len(), 2);
+        assert!(timeline[0].health.is_dirty);
+
+        db.set_setting("health_snapshot_retention_days", "0").unwrap();
+        let now = chrono::Utc::now().timestamp() + 1;
+        assert_eq!(prune_snapshots(&db, now).unwrap(), 1);
+    }
+
+
+    fn rule(id: &str, check: PolicyCheck, severity: Severity) -> PolicyRule {
+        PolicyRule {
+            id: id.to_string(),
+            check,
+            severity,
+            scope: PolicyScope::All,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_policy_violations_and_score() {
+        let temp = tempdir().unwrap();
+        init_repo_with_commit(temp.path());
+        git(temp.path(), &["branch", "-M", "master"]);
+        fs::write(temp.path().join("LICENSE"), "MIT").unwrap();
+
+        let rules = vec![
+            rule(
+                "files",
+                PolicyCheck::RequiredFiles {
+                    files: vec!["LICENSE".to_string(), "readme".to_string(), "CONTRIBUTING".to_string()],
+                },
+                Severity::Warning,
+            ),
+            rule(
+                "branch",
+                PolicyCheck::DefaultBranchNot {
+                    name: "master".to_string(),
+                },
+                Severity::Error,
+            ),
+            rule(
+                "email",
+                PolicyCheck::EmailDomain {
+                    domain: "test.com".to_string(),
+                },
+                Severity::Error,
+            ),
+            rule("stash", PolicyCheck::MaxStashCount { max: 0 }, Severity::Info),
+            rule("detached", PolicyCheck::NoDetachedHead, Severity::Warning),
+        ];
+
+        let health = crate::git::RepoHealth {
+            stash_count: 2,
+            ..Default::default()
+        };
+        let context = PolicyContext {
+            repo_path: temp.path(),
+            health: &health,
+            tags: &[],
+            dirty_since: None,
+            now: 0,
+        };
+        let report = evaluate_policies(&rules, &context);
+
+        let ids: Vec<&str> = report.violations.iter().map(|v| v.rule_id.as_str()).collect();
+        assert_eq!(ids, vec!["branch", "files", "stash"]);
+        assert_eq!(report.violations[1].message, "Missing CONTRIBUTING");
+        assert_eq!(report.rules_evaluated, 5);
+        // Passed: email (5) + detached (3) out of 5 + 3 + 5 + 1 + 3
+        assert_eq!(report.score, 8 * 100 / 17);
+    }
+
+    #[test]
+    fn test_policy_scope_and_uncommitted_age() {
+        let temp = tempdir().unwrap();
+        init_repo_with_commit(temp.path());
+        let day = 24 * 60 * 60;
+
+        let mut scoped = rule(
+            "stale",
+            PolicyCheck::MaxUncommittedAgeDays { days: 3 },
+            Severity::Warning,
+        );
+        scoped.scope = PolicyScope::Tag {
+            name: "work".to_string(),
+        };
+        let rules = vec![scoped];
+
+        let health = crate::git::RepoHealth {
+            is_dirty: true,
+            ..Default::default()
+        };
+        let tags = vec!["work".to_string()];
+        let mut context = PolicyContext {
+            repo_path: temp.path(),
+            health: &health,
+            tags: &tags,
+            dirty_since: Some(0),
+            now: 4 * day,
+        };
+        assert_eq!(evaluate_policies(&rules, &context).violations.len(), 1);
+
+        context.now = 2 * day;
+        assert!(evaluate_policies(&rules, &context).violations.is_empty());
+
+        // Out of scope: nothing evaluated, perfect score
+        context.tags = &[];
+        context.now = 4 * day;
+        let report = evaluate_policies(&rules, &context);
+        assert_eq!(report.rules_evaluated, 0);
+        assert_eq!(report.score, 100);
+    }
+
+    #[test]
+    fn test_policy_rules_unknown_without_repository() {
+        let temp = tempdir().unwrap();
+        let rules = vec![
+            rule(
+                "files",
+                PolicyCheck::RequiredFiles {
+                    files: vec!["README".to_string()],
+                },
+                Severity::Error,
+            ),
+            rule(
+                "email",
+                PolicyCheck::EmailDomain {
+                    domain: "test.com".to_string(),
+                },
+                Severity::Error,
+            ),
+            rule("stash", PolicyCheck::MaxStashCount { max: 0 }, Severity::Info),
+        ];
+
+        let health = crate::git::RepoHealth {
+            stash_count: 1,
+            ..Default::default()
+        };
+        let context = PolicyContext {
+            repo_path: &temp.path().join("missing"),
+            health: &health,
+            tags: &[],
+            dirty_since: None,
+            now: 0,
+        };
+        let report = evaluate_policies(&rules, &context);
+
+        assert_eq!(report.unknown_rules, vec!["files", "email"]);
+        assert_eq!(report.rules_evaluated, 1);
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(report.score, 0);
+
+        // Health-based rules cannot pass on a failed health check either
+        let failed = crate::git::RepoHealth {
+            error: Some(crate::git::HealthError {
+                kind: crate::git::HealthErrorKind::MissingPath,
+                message: "gone".to_string(),
+            }),
+            ..Default::default()
+        };
+        let context = PolicyContext {
+            health: &failed,
+            ..context
+        };
+        let report = evaluate_policies(&rules, &context);
+        assert_eq!(report.unknown_rules, vec!["files", "email", "stash"]);
+        assert_eq!(report.rules_evaluated, 0);
+        assert_eq!(report.score, 100);
+    }
+
+    #[test]
+    fn test_policy_rules_roundtrip_and_validation() {
+        let temp = tempdir().unwrap();
+        let db = Database::new(temp.path().join("test.db")).unwrap();
+
+        assert_eq!(load_policy_rules(&db).unwrap(), default_policy_rules());
+
+        let rules = vec![rule("detached", PolicyCheck::NoDetachedHead, Severity::Error)];
+        save_policy_rules(&db, &rules).unwrap();
+        assert_eq!(load_policy_rules(&db).unwrap(), rules);
+
+        let duplicate = vec![rules[0].clone(), rules[0].clone()];
+        assert!(save_policy_rules(&db, &duplicate).is_err());
+    }
+}
@@ -0,0 +1,10 @@
+//! Disk hygiene module.
+//!
+//! Cleanup helpers for stale repository state, such as merged branches.
+
+mod sweeper;
+
+#[cfg(test)]
+mod tests;
+
+pub use sweeper::*;
@@ -0,0 +1,231 @@
+//! Branch sweeper.
+//!
+//! Finds local branches that are merged, whose upstream is gone, or that
+//! have not moved in a while, and deletes them after a dry-run plan.
+
+use git2::{BranchType, Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::error::{DevBaseError, Result};
+use crate::git::branches::list_branches;
+
+/// Branch patterns that are always protected, in addition to the criteria's own.
+pub const DEFAULT_PROTECTED_PATTERNS: &[&str] = &["main", "master", "develop", "release/*"];
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Which branches the sweeper should pick up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SweepCriteria {
+    /// Branches fully merged into the default branch
+    pub merged: bool,
+    /// Branches whose configured upstream no longer exists
+    pub upstream_gone: bool,
+    /// Branches whose tip is older than this many days
+    pub older_than_days: Option<u32>,
+    /// Extra glob patterns (`*` wildcard) that are never deleted, on top of
+    /// `DEFAULT_PROTECTED_PATTERNS`
+    pub protected_patterns: Vec<String>,
+}
+
+impl Default for SweepCriteria {
+    fn default() -> Self {
+        Self {
+            merged: true,
+            upstream_gone: true,
+            older_than_days: None,
+            protected_patterns: Vec::new(),
+        }
+    }
+}
+
+/// Why a branch was selected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SweepReason {
+    Merged,
+    UpstreamGone,
+    Stale,
+}
+
+/// A branch the sweeper would delete.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepCandidate {
+    pub branch: String,
+    pub tip_oid: String,
+    pub tip_date: i64,
+    pub reasons: Vec<SweepReason>,
+}
+
+/// A matching branch that is kept anyway.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedBranch {
+    pub branch: String,
+    pub reason: String,
+}
+
+/// Dry-run plan for one repository.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepPlan {
+    pub repo_path: String,
+    pub candidates: Vec<SweepCandidate>,
+    pub skipped: Vec<SkippedBranch>,
+}
+
+/// Result of deleting one planned branch.
+#[derive(Debug, Clone, Serialize)]
+pub struct SweepOutcome {
+    pub branch: String,
+    pub tip_oid: String,
+    pub deleted: bool,
+    /// Why the branch was not deleted
+    pub error: Option<String>,
+}
+
+/// Build a dry-run plan for a repository. Nothing is modified.
+pub fn plan_sweep(path: &Path, criteria: &SweepCriteria) -> Result<SweepPlan> {
+    let now = chrono::Utc::now().timestamp();
+    let stale_before = criteria
+        .older_than_days
+        .map(|days| now - i64::from(days) * SECONDS_PER_DAY);
+
+    let mut plan = SweepPlan {
+        repo_path: path.to_string_lossy().to_string(),
+        candidates: Vec::new(),
+        skipped: Vec::new(),
+    };
+
+    for branch in list_branches(path)? {
+        if branch.is_remote {
+            continue;
+        }
+
+        let mut reasons = Vec::new();
+        if criteria.merged && branch.is_merged && !branch.is_default {
+            reasons.push(SweepReason::Merged);
+        }
+        if criteria.upstream_gone && branch.upstream_gone {
+            reasons.push(SweepReason::UpstreamGone);
+        }
+        if stale_before.is_some_and(|cutoff| branch.tip_date < cutoff) {
+            reasons.push(SweepReason::Stale);
+        }
+
+        if reasons.is_empty() {
+            continue;
+        }
+
+        let skip_reason = if branch.is_head {
+            Some("current branch".to_string())
+        } else if branch.is_default {
+            Some("default branch".to_string())
+        } else {
+            DEFAULT_PROTECTED_PATTERNS
+                .iter()
+                .copied()
+                .chain(criteria.protected_patterns.iter().map(String::as_str))
+                .find(|pattern| matches_pattern(pattern, &branch.name))
+                .map(|pattern| format!("protected by pattern `{pattern}`"))
+        };
+
+        match skip_reason {
+            Some(reason) => plan.skipped.push(SkippedBranch {
+                branch: branch.name,
+                reason,
+            }),
+            None => plan.candidates.push(SweepCandidate {
+                branch: branch.name,
+                tip_oid: branch.tip_oid,
+                tip_date: branch.tip_date,
+                reasons,
+            }),
+        }
+    }
+
+    Ok(plan)
+}
+
+/// Delete the branches of a previously computed plan.
+///
+/// The plan is re-validated first: a branch is only deleted if it is still a
+/// candidate under `criteria` and its tip has not moved since planning.
+pub fn execute_sweep(
+    path: &Path,
+    plan: &SweepPlan,
+    criteria: &SweepCriteria,
+) -> Result<Vec<SweepOutcome>> {
+    let fresh = plan_sweep(path, criteria)?;
+    let repo = Repository::open(path)?;
+    let mut outcomes = Vec::with_capacity(plan.candidates.len());
+
+    for candidate in &plan.candidates {
+        let still_valid = fresh
+            .candidates
+            .iter()
+            .any(|c| c.branch == candidate.branch && c.tip_oid == candidate.tip_oid);
+
+        let result = if still_valid {
+            repo.find_branch(&candidate.branch, BranchType::Local)
+                .and_then(|mut branch| branch.delete())
+                .map_err(|e| e.message().to_string())
+        } else {
+            Err("branch changed since the plan was made".to_string())
+        };
+
+        if let Err(e) = &result {
+            tracing::warn!(branch = %candidate.branch, error = %e, "Skipped branch deletion");
+        }
+
+        outcomes.push(SweepOutcome {
+            branch: candidate.branch.clone(),
+            tip_oid: candidate.tip_oid.clone(),
+            deleted: result.is_ok(),
+            error: result.err(),
+        });
+    }
+
+    Ok(outcomes)
+}
+
+/// Recreate a deleted branch at its recorded tip.
+pub fn restore_branch(path: &Path, branch: &str, tip_oid: &str) -> Result<()> {
+    let repo = Repository::open(path)?;
+
+    if repo.find_branch(branch, BranchType::Local).is_ok() {
+        return Err(DevBaseError::Config {
+            message: format!("Branch already exists: {branch}"),
+        });
+    }
+
+    let commit = repo.find_commit(Oid::from_str(tip_oid)?)?;
+    repo.branch(branch, &commit, false)?;
+    Ok(())
+}
+
+/// Match a branch name against a pattern where `*` matches any run of characters.
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(first) = parts.next() else {
+        return false;
+    };
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard: exact match
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
@@ -0,0 +1,153 @@
+//! Tests for hygiene module.
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    fn git(path: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(path)
+            .output()
+            .expect("Failed to run git");
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    fn init_repo_with_commit(path: &Path) {
+        git(path, &["init"]);
+        git(path, &["config", "user.email", "test@test.com"]);
+        git(path, &["config", "user.name", "Test User"]);
+        fs::write(path.join("README.md"), "# Test").unwrap();
+        git(path, &["add", "-A"]);
+        git(path, &["commit", "-m", "initial"]);
+        git(path, &["branch", "-M", "main"]);
+    }
+
+    fn commit_file(path: &Path, file: &str, message: &str) {
+        fs::write(path.join(file), message).unwrap();
+        git(path, &["add", "-A"]);
+        git(path, &["commit", "-m", message]);
+    }
+
+    #[test]
+    fn test_pattern_matching() {
+        assert!(matches_pattern("main", "main"));
+        assert!(!matches_pattern("main", "main2"));
+        assert!(matches_pattern("release/*", "release/1.0"));
+        assert!(!matches_pattern("release/*", "feature/release"));
+        assert!(matches_pattern("*-wip", "fix-wip"));
+        assert!(matches_pattern("a*b*c", "a-x-b-y-c"));
+        assert!(!matches_pattern("a*b*c", "a-x-c"));
+    }
+
+    #[test]
+    fn test_merged_branches_plan_is_dry_run() {
+        let temp = tempdir().unwrap();
+        init_repo_with_commit(temp.path());
+
+        git(temp.path(), &["branch", "merged-feature"]);
+        git(temp.path(), &["branch", "release/1.0"]);
+        git(temp.path(), &["checkout", "-b", "unmerged"]);
+        commit_file(temp.path(), "new.txt", "unmerged work");
+        git(temp.path(), &["checkout", "-b", "current", "main"]);
+
+        let plan = plan_sweep(temp.path(), &SweepCriteria::default()).unwrap();
+
+        let names: Vec<&str> = plan.candidates.iter().map(|c| c.branch.as_str()).collect();
+        assert_eq!(names, vec!["merged-feature"]);
+        assert_eq!(plan.candidates[0].reasons, vec![SweepReason::Merged]);
+
+        let skipped: Vec<&str> = plan.skipped.iter().map(|s| s.branch.as_str()).collect();
+        assert!(skipped.contains(&"current"));
+        assert!(skipped.contains(&"release/1.0"));
+        assert!(!skipped.contains(&"main"));
+
+        // Planning does not delete anything
+        assert!(
+            git(temp.path(), &["branch", "--list", "merged-feature"]).contains("merged-feature")
+        );
+    }
+
+    #[test]
+    fn test_custom_patterns_keep_default_protection() {
+        let temp = tempdir().unwrap();
+        init_repo_with_commit(temp.path());
+        git(temp.path(), &["branch", "develop"]);
+        git(temp.path(), &["branch", "wip/spike"]);
+        git(temp.path(), &["branch", "merged-feature"]);
+
+        let criteria = SweepCriteria {
+            protected_patterns: vec!["wip/*".to_string()],
+            ..SweepCriteria::default()
+        };
+        let plan = plan_sweep(temp.path(), &criteria).unwrap();
+
+        let names: Vec<&str> = plan.candidates.iter().map(|c| c.branch.as_str()).collect();
+        assert_eq!(names, vec!["merged-feature"]);
+        let skipped: Vec<&str> = plan.skipped.iter().map(|s| s.branch.as_str()).collect();
+        assert!(skipped.contains(&"develop"));
+        assert!(skipped.contains(&"wip/spike"));
+    }
+
+    #[test]
+    fn test_stale_branches_threshold() {
+        let temp = tempdir().unwrap();
+        init_repo_with_commit(temp.path());
+
+        git(temp.path(), &["checkout", "-b", "ancient"]);
+        Command::new("git")
+            .args(["commit", "--allow-empty", "-m", "old work"])
+            .env("GIT_AUTHOR_DATE", "2001-01-01T00:00:00Z")
+            .env("GIT_COMMITTER_DATE", "2001-01-01T00:00:00Z")
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+        git(temp.path(), &["checkout", "main"]);
+
+        let criteria = SweepCriteria {
+            merged: false,
+            upstream_gone: false,
+            older_than_days: Some(90),
+            ..SweepCriteria::default()
+        };
+        let plan = plan_sweep(temp.path(), &criteria).unwrap();
+
+        assert_eq!(plan.candidates.len(), 1);
+        assert_eq!(plan.candidates[0].branch, "ancient");
+        assert_eq!(plan.candidates[0].reasons, vec![SweepReason::Stale]);
+    }
+
+    #[test]
+    fn test_execute_and_restore_branch() {
+        let temp = tempdir().unwrap();
+        init_repo_with_commit(temp.path());
+        git(temp.path(), &["branch", "done"]);
+        git(temp.path(), &["branch", "moved"]);
+
+        let criteria = SweepCriteria::default();
+        let plan = plan_sweep(temp.path(), &criteria).unwrap();
+        assert_eq!(plan.candidates.len(), 2);
+
+        // `moved` gets a new commit after planning and must survive
+        git(temp.path(), &["checkout", "moved"]);
+        commit_file(temp.path(), "moved.txt", "moved on");
+        git(temp.path(), &["checkout", "main"]);
+        git(temp.path(), &["merge", "--ff-only", "moved"]);
+
+        let outcomes = execute_sweep(temp.path(), &plan, &criteria).unwrap();
+        let done = outcomes.iter().find(|o| o.branch == "done").unwrap();
+        let moved = outcomes.iter().find(|o| o.branch == "moved").unwrap();
+        assert!(done.deleted);
+        assert!(!moved.deleted);
+        assert!(moved.error.is_some());
+        assert!(git(temp.path(), &["branch", "--list", "done"]).is_empty());
+
+        restore_branch(temp.path(), "done", &done.tip_oid).unwrap();
+        assert_eq!(git(temp.path(), &["rev-parse", "done"]), done.tip_oid);
+        assert!(restore_branch(temp.path(), "done", &done.tip_oid).is_err());
+    }
+}
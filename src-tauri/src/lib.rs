@@ -9,28 +9,43 @@
 pub mod db;
 pub mod error;
 pub mod git;
+pub mod health;
+pub mod hygiene;
 pub mod scanner;
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tauri::Manager;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::db::Database;
+use crate::git::BlameCache;
+use crate::health::RefreshQueue;
 
 /// Application state managed by Tauri.
 pub struct AppState {
     pub db: Arc<Database>,
+    pub blame_cache: BlameCache,
+    /// Jobs for the background health refresh
+    pub health_refresh: Arc<Mutex<RefreshQueue>>,
 }
 
 // ============= Commands defined inline for Tauri macro compatibility =============
 
 mod commands {
+    use std::collections::HashMap;
     use std::path::PathBuf;
+    use std::sync::{Arc, Mutex, PoisonError};
     use serde::{Deserialize, Serialize};
-    use tauri::State;
+    use tauri::{AppHandle, Emitter, State};
 
+    use crate::db::{Database, StoredRepo};
     use crate::error::{DevBaseError, Result};
     use crate::git::{self, RepoHealth, RepoStatus};
+    use crate::health::{
+        self, ConditionStreak, HealthCondition, HealthJob, HealthSnapshot, PolicyReport, PolicyRule,
+        RefreshMode, RefreshQueue,
+    };
+    use crate::hygiene::{self, SweepCriteria, SweepOutcome, SweepPlan};
     use crate::scanner::{self, DiscoveredRepo};
     use crate::AppState;
 
@@ -141,31 +156,142 @@ mod commands {
         pub health: RepoHealth,
         pub status: RepoStatus,
         pub tags: Vec<String>,
+        /// Last fully successful fetch
+        pub last_fetched_at: Option<String>,
+        /// Main repository if this is a linked worktree
+        pub parent_id: Option<i64>,
+    }
+
+    /// Event carrying a `RepoInfo` whose health was recomputed in the background.
+    pub const REPO_HEALTH_UPDATED_EVENT: &str = "repo-health-updated";
+
+    fn repo_info(db: &Database, repo: StoredRepo, health: RepoHealth) -> Result<RepoInfo> {
+        let status = health.status();
+        let tags = db.get_repo_tags(repo.id)?;
+        let last_fetched_at = db.get_fetch_state(repo.id)?.last_fetched_at;
+
+        Ok(RepoInfo {
+            id: repo.id,
+            path: repo.path,
+            name: repo.name,
+            remote_url: repo.remote_url,
+            default_branch: repo.default_branch,
+            current_branch: health.current_branch.clone(),
+            health,
+            status,
+            tags,
+            last_fetched_at,
+            parent_id: repo.parent_id,
+        })
+    }
+
+    /// Recompute health on the worker pool and emit an event per updated repository.
+    ///
+    /// Only one background refresh runs at a time; requests made meanwhile are
+    /// queued and run when the current pass ends.
+    fn spawn_health_refresh(app: AppHandle, state: &AppState, jobs: Vec<HealthJob>, mode: RefreshMode) {
+        let queue = Arc::clone(&state.health_refresh);
+        if !lock_queue(&queue).submit(jobs, mode) {
+            return;
+        }
+
+        let db = Arc::clone(&state.db);
+        tauri::async_runtime::spawn_blocking(move || {
+            let _guard = RefreshGuard(Arc::clone(&queue));
+            let workers = health::default_worker_count();
+            let on_update = |repo_id, health: &RepoHealth| {
+                let info = db
+                    .get_repository(repo_id)
+                    .and_then(|repo| repo_info(&db, repo, health.clone()));
+                match info {
+                    Ok(info) => {
+                        if let Err(e) = app.emit(REPO_HEALTH_UPDATED_EVENT, &info) {
+                            tracing::warn!(repo_id, ?e, "Failed to emit health update");
+                        }
+                    }
+                    Err(e) => tracing::warn!(repo_id, ?e, "Failed to load refreshed repository"),
+                }
+            };
+
+            let mut count = 0;
+            loop {
+                // A `let` statement releases the lock before the pass runs
+                let Some(batches) = lock_queue(&queue).next_batch() else {
+                    break;
+                };
+                for (mode, jobs) in batches {
+                    count += health::refresh_health(&db, &jobs, mode, workers, &on_update).len();
+                }
+            }
+            tracing::debug!(count, "Background health refresh finished");
+            if let Err(e) = health::prune_snapshots(&db, chrono::Utc::now().timestamp()) {
+                tracing::warn!(?e, "Failed to prune health snapshots");
+            }
+        });
     }
 
+    fn lock_queue(queue: &Mutex<RefreshQueue>) -> std::sync::MutexGuard<'_, RefreshQueue> {
+        queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Lets a later request start a new refresh if this one panics.
+    struct RefreshGuard(Arc<Mutex<RefreshQueue>>);
+
+    impl Drop for RefreshGuard {
+        fn drop(&mut self) {
+            if std::thread::panicking() {
+                lock_queue(&self.0).abandon();
+            }
+        }
+    }
+
+    /// List repositories with their cached health.
+    ///
+    /// Repositories without a cache entry are computed before returning; all
+    /// others are refreshed in the background and pushed as
+    /// `repo-health-updated` events.
     #[tauri::command]
-    pub async fn get_repositories(state: State<'_, AppState>) -> Result<Vec<RepoInfo>> {
+    pub async fn get_repositories(
+        app: AppHandle,
+        state: State<'_, AppState>,
+        force_refresh: Option<bool>,
+    ) -> Result<Vec<RepoInfo>> {
         let repos = state.db.get_all_repositories()?;
+        let mut healths: HashMap<i64, RepoHealth> = state
+            .db
+            .get_all_cached_health()?
+            .into_iter()
+            .map(|cached| (cached.repo_id, cached.health))
+            .collect();
+
+        let (cached_jobs, missing_jobs): (Vec<HealthJob>, Vec<HealthJob>) =
+            health::health_jobs(&state.db, &repos)?
+                .into_iter()
+                .partition(|job| healths.contains_key(&job.repo_id));
+
+        healths.extend(health::refresh_health(
+            &state.db,
+            &missing_jobs,
+            RefreshMode::All,
+            health::default_worker_count(),
+            |_, _| {},
+        ));
+
         let mut result = Vec::with_capacity(repos.len());
-        
         for repo in repos {
-            let health = git::get_repo_health(&PathBuf::from(&repo.path)).unwrap_or_default();
-            let status = health.status();
-            let tags = state.db.get_repo_tags(repo.id)?;
-            
-            result.push(RepoInfo {
-                id: repo.id,
-                path: repo.path,
-                name: repo.name,
-                remote_url: repo.remote_url,
-                default_branch: repo.default_branch,
-                current_branch: health.current_branch.clone(),
-                health,
-                status,
-                tags,
+            let health = healths.remove(&repo.id).unwrap_or_else(|| {
+                git::check_repo_health(&PathBuf::from(&repo.path), &git::HealthOptions::default())
             });
+            result.push(repo_info(&state.db, repo, health)?);
         }
-        
+
+        let mode = if force_refresh.unwrap_or(false) {
+            RefreshMode::All
+        } else {
+            RefreshMode::Stale
+        };
+        spawn_health_refresh(app, &state, cached_jobs, mode);
+
         Ok(result)
     }
 
@@ -175,24 +301,501 @@ mod commands {
         git::get_repo_health(&path_utils)
     }
 
+    #[tauri::command]
+    pub fn get_file_statuses(
+        path: String,
+        include_ignored: Option<bool>,
+    ) -> Result<Vec<git::FileStatusEntry>> {
+        let path_buf = expand_path(&path);
+        git::get_file_statuses(&path_buf, include_ignored.unwrap_or(false))
+    }
+
     #[tauri::command]
     pub async fn refresh_repo(state: State<'_, AppState>, repo_id: i64) -> Result<RepoInfo> {
         let repo = state.db.get_repository(repo_id)?;
-        let health = git::get_repo_health(&PathBuf::from(&repo.path)).unwrap_or_default();
-        let status = health.status();
-        let tags = state.db.get_repo_tags(repo.id)?;
-        
-        Ok(RepoInfo {
-            id: repo.id,
-            path: repo.path,
-            name: repo.name,
-            remote_url: repo.remote_url,
-            default_branch: repo.default_branch,
-            current_branch: health.current_branch.clone(),
-            health,
-            status,
-            tags,
+        let jobs = health::health_jobs(&state.db, std::slice::from_ref(&repo))?;
+        let health = health::refresh_health(&state.db, &jobs, RefreshMode::All, 1, |_, _| {})
+            .pop()
+            .map(|(_, health)| health)
+            .unwrap_or_else(|| {
+                git::check_repo_health(&PathBuf::from(&repo.path), &git::HealthOptions::default())
+            });
+
+        repo_info(&state.db, repo, health)
+    }
+
+    #[tauri::command]
+    pub async fn get_repo_health_options(
+        state: State<'_, AppState>,
+        repo_id: i64,
+    ) -> Result<git::HealthOptions> {
+        state.db.get_health_options(repo_id)
+    }
+
+    #[tauri::command]
+    pub async fn set_repo_health_options(
+        state: State<'_, AppState>,
+        repo_id: i64,
+        options: git::HealthOptions,
+    ) -> Result<()> {
+        state.db.get_repository(repo_id)?;
+        state.db.set_health_options(repo_id, &options)
+    }
+
+    // ========== Health History Commands ==========
+
+    #[tauri::command]
+    pub async fn get_health_timeline(
+        state: State<'_, AppState>,
+        repo_id: i64,
+        since: Option<i64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<HealthSnapshot>> {
+        state.db.get_health_timeline(repo_id, since, limit.unwrap_or(500))
+    }
+
+    #[tauri::command]
+    pub async fn get_repos_in_condition(
+        state: State<'_, AppState>,
+        condition: HealthCondition,
+        min_days: Option<u32>,
+    ) -> Result<Vec<ConditionStreak>> {
+        let now = chrono::Utc::now().timestamp();
+        health::repos_in_condition(&state.db, condition, min_days.unwrap_or(0), now)
+    }
+
+    // ========== Policy Commands ==========
+
+    #[tauri::command]
+    pub async fn get_policy_rules(state: State<'_, AppState>) -> Result<Vec<PolicyRule>> {
+        health::load_policy_rules(&state.db)
+    }
+
+    #[tauri::command]
+    pub async fn set_policy_rules(state: State<'_, AppState>, rules: Vec<PolicyRule>) -> Result<()> {
+        health::save_policy_rules(&state.db, &rules)
+    }
+
+    /// Score repositories (all when `repo_ids` is omitted) using cached health.
+    #[tauri::command]
+    pub async fn evaluate_policies(
+        state: State<'_, AppState>,
+        repo_ids: Option<Vec<i64>>,
+    ) -> Result<Vec<PolicyReport>> {
+        let rules = health::load_policy_rules(&state.db)?;
+        let mut healths: HashMap<i64, RepoHealth> = state
+            .db
+            .get_all_cached_health()?
+            .into_iter()
+            .map(|cached| (cached.repo_id, cached.health))
+            .collect();
+        let now = chrono::Utc::now().timestamp();
+
+        let mut repos = Vec::new();
+        for repo in state.db.get_all_repositories()? {
+            if repo_ids.as_ref().is_some_and(|ids| !ids.contains(&repo.id)) {
+                continue;
+            }
+            let health = healths.remove(&repo.id).unwrap_or_else(|| {
+                git::check_repo_health(&PathBuf::from(&repo.path), &git::HealthOptions::default())
+            });
+            repos.push((repo, health));
+        }
+
+        health::evaluate_repos(&state.db, &repos, &rules, now)
+    }
+
+    // ========== Fetch Commands ==========
+
+    /// Event carrying a `RepoFetchProgress`.
+    pub const FETCH_PROGRESS_EVENT: &str = "fetch-progress";
+    /// Event carrying a `RepoFetchResult` as soon as one repository is done.
+    pub const FETCH_FINISHED_EVENT: &str = "fetch-finished";
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct RepoFetchProgress {
+        pub repo_id: i64,
+        pub progress: git::FetchProgress,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct RepoFetchResult {
+        pub repo_id: i64,
+        pub result: Option<git::FetchResult>,
+        pub error: Option<String>,
+    }
+
+    /// Fetch repositories (all when `repo_ids` is omitted) on a bounded pool.
+    #[tauri::command]
+    pub async fn fetch_repositories(
+        app: AppHandle,
+        state: State<'_, AppState>,
+        repo_ids: Option<Vec<i64>>,
+        options: Option<git::FetchOptions>,
+        concurrency: Option<usize>,
+    ) -> Result<Vec<RepoFetchResult>> {
+        let repos: Vec<StoredRepo> = state
+            .db
+            .get_all_repositories()?
+            .into_iter()
+            .filter(|repo| repo_ids.as_ref().is_none_or(|ids| ids.contains(&repo.id)))
+            .collect();
+        let jobs = health::health_jobs(&state.db, &repos)?;
+        let options = options.unwrap_or_default();
+        let workers = concurrency.unwrap_or(git::DEFAULT_FETCH_CONCURRENCY);
+
+        let db = Arc::clone(&state.db);
+        let events = app.clone();
+        let results = tauri::async_runtime::spawn_blocking(move || {
+            health::run_parallel(&repos, workers, |repo| {
+                let fetched = git::fetch_repo(&PathBuf::from(&repo.path), &options, |progress| {
+                    let payload = RepoFetchProgress {
+                        repo_id: repo.id,
+                        progress: progress.clone(),
+                    };
+                    let _ = events.emit(FETCH_PROGRESS_EVENT, payload);
+                });
+
+                let outcome = match fetched {
+                    Ok(result) => RepoFetchResult {
+                        repo_id: repo.id,
+                        error: result.error(),
+                        result: Some(result),
+                    },
+                    Err(e) => RepoFetchResult {
+                        repo_id: repo.id,
+                        result: None,
+                        error: Some(e.to_string()),
+                    },
+                };
+
+                if let Err(e) = db.record_fetch(repo.id, outcome.error.as_deref()) {
+                    tracing::warn!(repo_id = repo.id, ?e, "Failed to record fetch");
+                }
+                let _ = events.emit(FETCH_FINISHED_EVENT, &outcome);
+                outcome
+            })
+        })
+        .await
+        .map_err(|e| DevBaseError::Internal {
+            message: format!("Fetch task failed: {e}"),
+        })?;
+
+        // Remote-tracking refs moved, so ahead/behind needs recomputing
+        spawn_health_refresh(app, &state, jobs, RefreshMode::Stale);
+
+        Ok(results)
+    }
+
+    // ========== Sync Commands ==========
+
+    /// Event carrying a `RepoSyncResult` as soon as one repository is done.
+    pub const SYNC_FINISHED_EVENT: &str = "sync-finished";
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct RepoSyncResult {
+        pub repo_id: i64,
+        #[serde(flatten)]
+        pub outcome: git::SyncOutcome,
+    }
+
+    #[derive(Debug, Clone, Default, Serialize)]
+    pub struct SyncReport {
+        pub results: Vec<RepoSyncResult>,
+        pub updated: usize,
+        pub up_to_date: usize,
+        pub skipped: usize,
+        pub failed: usize,
+    }
+
+    /// Fetch and fast-forward repositories (all when `repo_ids` is omitted).
+    /// Only clean repositories whose branch can fast-forward are touched.
+    #[tauri::command]
+    pub async fn sync_repositories(
+        app: AppHandle,
+        state: State<'_, AppState>,
+        repo_ids: Option<Vec<i64>>,
+        options: Option<git::SyncOptions>,
+        concurrency: Option<usize>,
+    ) -> Result<SyncReport> {
+        let repos: Vec<StoredRepo> = state
+            .db
+            .get_all_repositories()?
+            .into_iter()
+            .filter(|repo| repo_ids.as_ref().is_none_or(|ids| ids.contains(&repo.id)))
+            .collect();
+        let jobs = health::health_jobs(&state.db, &repos)?;
+        let options = options.unwrap_or_default();
+        let workers = concurrency.unwrap_or(git::DEFAULT_FETCH_CONCURRENCY);
+
+        let db = Arc::clone(&state.db);
+        let events = app.clone();
+        let results = tauri::async_runtime::spawn_blocking(move || {
+            health::run_parallel(&repos, workers, |repo| {
+                let path = PathBuf::from(&repo.path);
+                let fetch_error = if options.fetch {
+                    let error = git::fetch_error(&path, &options.fetch_options);
+                    if let Err(e) = db.record_fetch(repo.id, error.as_deref()) {
+                        tracing::warn!(repo_id = repo.id, ?e, "Failed to record fetch");
+                    }
+                    error
+                } else {
+                    None
+                };
+
+                let outcome = match fetch_error {
+                    Some(error) => git::SyncOutcome::Failed { error },
+                    None => git::fast_forward_repo(&path, options.autostash),
+                };
+                let result = RepoSyncResult {
+                    repo_id: repo.id,
+                    outcome,
+                };
+                let _ = events.emit(SYNC_FINISHED_EVENT, &result);
+                result
+            })
+        })
+        .await
+        .map_err(|e| DevBaseError::Internal {
+            message: format!("Sync task failed: {e}"),
+        })?;
+
+        let mut report = SyncReport::default();
+        for result in &results {
+            match result.outcome {
+                git::SyncOutcome::Updated { .. } => report.updated += 1,
+                git::SyncOutcome::UpToDate { .. } => report.up_to_date += 1,
+                git::SyncOutcome::Skipped { .. } => report.skipped += 1,
+                git::SyncOutcome::Failed { .. } => report.failed += 1,
+            }
+        }
+        report.results = results;
+
+        spawn_health_refresh(app, &state, jobs, RefreshMode::Stale);
+
+        Ok(report)
+    }
+
+    // ========== Push Commands ==========
+
+    /// Event carrying a `RepoPushResult` as soon as one repository is done.
+    pub const PUSH_FINISHED_EVENT: &str = "push-finished";
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct RepoPushResult {
+        pub repo_id: i64,
+        pub result: Option<git::PushResult>,
+        pub error: Option<String>,
+    }
+
+    /// Push repositories (all when `repo_ids` is omitted) on a bounded pool.
+    #[tauri::command]
+    pub async fn push_repositories(
+        app: AppHandle,
+        state: State<'_, AppState>,
+        repo_ids: Option<Vec<i64>>,
+        options: Option<git::PushOptions>,
+        concurrency: Option<usize>,
+    ) -> Result<Vec<RepoPushResult>> {
+        let repos: Vec<StoredRepo> = state
+            .db
+            .get_all_repositories()?
+            .into_iter()
+            .filter(|repo| repo_ids.as_ref().is_none_or(|ids| ids.contains(&repo.id)))
+            .collect();
+        let jobs = health::health_jobs(&state.db, &repos)?;
+        let options = options.unwrap_or_default();
+        let workers = concurrency.unwrap_or(git::DEFAULT_FETCH_CONCURRENCY);
+
+        let events = app.clone();
+        let results = tauri::async_runtime::spawn_blocking(move || {
+            health::run_parallel(&repos, workers, |repo| {
+                let outcome = match git::push_repo(&PathBuf::from(&repo.path), &options) {
+                    Ok(result) => RepoPushResult {
+                        repo_id: repo.id,
+                        error: result.error(),
+                        result: Some(result),
+                    },
+                    Err(e) => RepoPushResult {
+                        repo_id: repo.id,
+                        result: None,
+                        error: Some(e.to_string()),
+                    },
+                };
+                let _ = events.emit(PUSH_FINISHED_EVENT, &outcome);
+                outcome
+            })
         })
+        .await
+        .map_err(|e| DevBaseError::Internal {
+            message: format!("Push task failed: {e}"),
+        })?;
+
+        spawn_health_refresh(app, &state, jobs, RefreshMode::Stale);
+
+        Ok(results)
+    }
+
+    // ========== Stash Commands ==========
+
+    #[tauri::command]
+    pub fn list_stashes(path: String) -> Result<Vec<git::StashEntry>> {
+        git::list_stashes(&expand_path(&path))
+    }
+
+    #[tauri::command]
+    pub fn get_stash_diff(
+        path: String,
+        index: usize,
+        oid: Option<String>,
+    ) -> Result<Vec<git::StashFilePatch>> {
+        git::get_stash_diff(&expand_path(&path), index, oid.as_deref())
+    }
+
+    #[tauri::command]
+    pub fn create_stash(
+        path: String,
+        options: Option<git::StashCreateOptions>,
+    ) -> Result<git::StashEntry> {
+        git::create_stash(&expand_path(&path), &options.unwrap_or_default())
+    }
+
+    #[tauri::command]
+    pub fn apply_stash(
+        path: String,
+        index: usize,
+        oid: Option<String>,
+        reinstate_index: Option<bool>,
+    ) -> Result<git::StashApplyResult> {
+        git::apply_stash(
+            &expand_path(&path),
+            index,
+            oid.as_deref(),
+            reinstate_index.unwrap_or(false),
+        )
+    }
+
+    #[tauri::command]
+    pub fn pop_stash(
+        path: String,
+        index: usize,
+        oid: Option<String>,
+        reinstate_index: Option<bool>,
+    ) -> Result<git::StashApplyResult> {
+        git::pop_stash(
+            &expand_path(&path),
+            index,
+            oid.as_deref(),
+            reinstate_index.unwrap_or(false),
+        )
+    }
+
+    #[tauri::command]
+    pub fn drop_stash(path: String, index: usize, oid: Option<String>) -> Result<()> {
+        git::drop_stash(&expand_path(&path), index, oid.as_deref())
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct RepoStash {
+        pub repo_id: i64,
+        pub repo_name: String,
+        pub repo_path: String,
+        pub stash: git::StashEntry,
+    }
+
+    /// Stashes older than `min_days` across all repositories, oldest first.
+    #[tauri::command]
+    pub async fn get_old_stashes(
+        state: State<'_, AppState>,
+        min_days: u32,
+    ) -> Result<Vec<RepoStash>> {
+        let repos = state.db.get_all_repositories()?;
+        let cutoff = chrono::Utc::now().timestamp() - i64::from(min_days) * 86_400;
+
+        let found = tauri::async_runtime::spawn_blocking(move || {
+            health::run_parallel(&repos, health::default_worker_count(), |repo| {
+                match git::stashes_older_than(&PathBuf::from(&repo.path), cutoff) {
+                    Ok(stashes) => stashes
+                        .into_iter()
+                        .map(|stash| RepoStash {
+                            repo_id: repo.id,
+                            repo_name: repo.name.clone(),
+                            repo_path: repo.path.clone(),
+                            stash,
+                        })
+                        .collect(),
+                    Err(e) => {
+                        tracing::warn!(repo_id = repo.id, ?e, "Failed to list stashes");
+                        Vec::new()
+                    }
+                }
+            })
+        })
+        .await
+        .map_err(|e| DevBaseError::Internal {
+            message: format!("Stash listing failed: {e}"),
+        })?;
+
+        let mut stashes: Vec<RepoStash> = found.into_iter().flatten().collect();
+        stashes.sort_by_key(|s| s.stash.date);
+        Ok(stashes)
+    }
+
+    // ========== Staging Commands ==========
+
+    #[tauri::command]
+    pub fn get_file_diff(path: String, file: String, staged: Option<bool>) -> Result<git::FileDiff> {
+        git::get_file_diff(&expand_path(&path), &file, staged.unwrap_or(false))
+    }
+
+    #[tauri::command]
+    pub fn stage_files(path: String, files: Vec<String>) -> Result<()> {
+        git::stage_files(&expand_path(&path), &files)
+    }
+
+    #[tauri::command]
+    pub fn unstage_files(path: String, files: Vec<String>) -> Result<()> {
+        git::unstage_files(&expand_path(&path), &files)
+    }
+
+    #[tauri::command]
+    pub fn stage_selection(path: String, file: String, selection: git::PatchSelection) -> Result<()> {
+        git::stage_selection(&expand_path(&path), &file, &selection)
+    }
+
+    #[tauri::command]
+    pub fn unstage_selection(
+        path: String,
+        file: String,
+        selection: git::PatchSelection,
+    ) -> Result<()> {
+        git::unstage_selection(&expand_path(&path), &file, &selection)
+    }
+
+    #[tauri::command]
+    pub fn discard_changes(path: String, files: Vec<String>) -> Result<git::DiscardBackup> {
+        git::discard_changes(&expand_path(&path), &files)
+    }
+
+    #[tauri::command]
+    pub fn list_discard_backups(path: String) -> Result<Vec<git::DiscardBackup>> {
+        git::list_discard_backups(&expand_path(&path))
+    }
+
+    #[tauri::command]
+    pub fn restore_discard_backup(path: String, ref_name: String) -> Result<Vec<String>> {
+        git::restore_discard_backup(&expand_path(&path), &ref_name)
+    }
+
+    // ========== Commit Commands ==========
+
+    #[tauri::command]
+    pub fn create_commit(
+        path: String,
+        message: String,
+        options: Option<git::CommitOptions>,
+    ) -> Result<git::CommitLogEntry> {
+        git::create_commit(&expand_path(&path), &message, &options.unwrap_or_default())
     }
 
     // ========== Settings Commands ==========
@@ -308,6 +911,627 @@ mod commands {
         let limit = limit.unwrap_or(100);
         crate::git::history::get_repo_history(&path_buf, limit)
     }
+
+    // ========== Diff Commands ==========
+
+    #[tauri::command]
+    pub fn get_binary_diff(
+        path: String,
+        file_path: String,
+        old_rev: Option<String>,
+        new_rev: Option<String>,
+    ) -> Result<crate::git::diff::BinaryDiff> {
+        let path_buf = expand_path(&path);
+        let old_rev = old_rev.unwrap_or_else(|| "HEAD".to_string());
+        crate::git::diff::get_binary_diff(&path_buf, &file_path, &old_rev, new_rev.as_deref())
+    }
+
+    // ========== Blame Commands ==========
+
+    #[tauri::command]
+    pub fn get_file_blame(
+        state: State<'_, AppState>,
+        path: String,
+        file_path: String,
+        rev: Option<String>,
+        options: Option<crate::git::blame::BlameFileOptions>,
+    ) -> Result<crate::git::blame::FileBlame> {
+        let path_buf = expand_path(&path);
+        let rev = rev.unwrap_or_else(|| "HEAD".to_string());
+        let options = options.unwrap_or_default();
+        crate::git::blame::get_file_blame(&path_buf, &file_path, &rev, &options, &state.blame_cache)
+    }
+
+    // ========== Time-Travel Commands ==========
+
+    #[tauri::command]
+    pub fn list_tree_at(
+        path: String,
+        rev: Option<String>,
+        dir_path: Option<String>,
+    ) -> Result<Vec<crate::git::time_travel::TreeEntryInfo>> {
+        let path_buf = expand_path(&path);
+        let rev = rev.unwrap_or_else(|| "HEAD".to_string());
+        crate::git::time_travel::list_tree_at(&path_buf, &rev, dir_path.as_deref())
+    }
+
+    #[tauri::command]
+    pub fn read_file_at(
+        path: String,
+        rev: String,
+        file_path: String,
+        max_bytes: Option<usize>,
+    ) -> Result<crate::git::time_travel::FileAtRevision> {
+        let path_buf = expand_path(&path);
+        let max_bytes = max_bytes.unwrap_or(crate::git::time_travel::DEFAULT_MAX_READ_BYTES);
+        crate::git::time_travel::read_file_at(&path_buf, &rev, &file_path, max_bytes)
+    }
+
+    // ========== Branch Commands ==========
+
+    #[tauri::command]
+    pub fn list_branches(path: String) -> Result<Vec<crate::git::branches::BranchInfo>> {
+        let path_buf = expand_path(&path);
+        crate::git::branches::list_branches(&path_buf)
+    }
+
+    // ========== Branch Sweeper Commands ==========
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RepoSweepPlan {
+        pub repo_id: i64,
+        pub plan: SweepPlan,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct RepoSweepResult {
+        pub repo_id: i64,
+        pub outcomes: Vec<SweepOutcome>,
+    }
+
+    #[tauri::command]
+    pub async fn plan_branch_sweep(
+        state: State<'_, AppState>,
+        repo_ids: Vec<i64>,
+        criteria: Option<SweepCriteria>,
+    ) -> Result<Vec<RepoSweepPlan>> {
+        let criteria = criteria.unwrap_or_default();
+        let mut plans = Vec::with_capacity(repo_ids.len());
+
+        for repo_id in repo_ids {
+            let repo = state.db.get_repository(repo_id)?;
+            let plan = hygiene::plan_sweep(&PathBuf::from(&repo.path), &criteria)?;
+            plans.push(RepoSweepPlan { repo_id, plan });
+        }
+
+        Ok(plans)
+    }
+
+    #[tauri::command]
+    pub async fn execute_branch_sweep(
+        state: State<'_, AppState>,
+        plans: Vec<RepoSweepPlan>,
+        criteria: Option<SweepCriteria>,
+    ) -> Result<Vec<RepoSweepResult>> {
+        let criteria = criteria.unwrap_or_default();
+        let mut results = Vec::with_capacity(plans.len());
+
+        for RepoSweepPlan { repo_id, plan } in plans {
+            let repo = state.db.get_repository(repo_id)?;
+            let outcomes = hygiene::execute_sweep(&PathBuf::from(&repo.path), &plan, &criteria)?;
+
+            // The branches are already gone; a failed record must not hide that
+            for outcome in outcomes.iter().filter(|o| o.deleted) {
+                let recorded =
+                    state.db.record_deleted_branch(repo_id, &outcome.branch, &outcome.tip_oid);
+                if let Err(e) = recorded {
+                    tracing::warn!(repo_id, branch = %outcome.branch, ?e, "Failed to record deleted branch");
+                }
+            }
+
+            results.push(RepoSweepResult { repo_id, outcomes });
+        }
+
+        Ok(results)
+    }
+
+    #[tauri::command]
+    pub async fn get_deleted_branches(
+        state: State<'_, AppState>,
+        repo_id: Option<i64>,
+    ) -> Result<Vec<crate::db::DeletedBranch>> {
+        state.db.get_deleted_branches(repo_id)
+    }
+
+    #[tauri::command]
+    pub async fn restore_deleted_branch(state: State<'_, AppState>, id: i64) -> Result<()> {
+        let deleted = state.db.get_deleted_branch(id)?;
+        let repo = state.db.get_repository(deleted.repo_id)?;
+        let path = PathBuf::from(&repo.path);
+        hygiene::restore_branch(&path, &deleted.branch_name, &deleted.tip_oid)?;
+        state.db.mark_branch_restored(id)
+    }
+
+    // ========== Branch Management Commands ==========
+
+    /// Recompute health of one repository after its branches changed.
+    fn refresh_after_branch_change(app: AppHandle, state: &AppState, repo: StoredRepo) -> Result<()> {
+        let jobs = health::health_jobs(&state.db, &[repo])?;
+        spawn_health_refresh(app, state, jobs, RefreshMode::All);
+        Ok(())
+    }
+
+    #[tauri::command]
+    pub async fn checkout_branch(
+        app: AppHandle,
+        state: State<'_, AppState>,
+        repo_id: i64,
+        branch: String,
+        options: Option<git::CheckoutOptions>,
+    ) -> Result<git::CheckoutResult> {
+        let repo = state.db.get_repository(repo_id)?;
+        let result = git::checkout_branch(
+            &PathBuf::from(&repo.path),
+            &branch,
+            &options.unwrap_or_default(),
+        )?;
+        refresh_after_branch_change(app, &state, repo)?;
+        Ok(result)
+    }
+
+    #[tauri::command]
+    pub async fn create_branch(
+        app: AppHandle,
+        state: State<'_, AppState>,
+        repo_id: i64,
+        name: String,
+        start_point: Option<String>,
+    ) -> Result<git::BranchInfo> {
+        let repo = state.db.get_repository(repo_id)?;
+        let info = git::create_branch(&PathBuf::from(&repo.path), &name, start_point.as_deref())?;
+        refresh_after_branch_change(app, &state, repo)?;
+        Ok(info)
+    }
+
+    #[tauri::command]
+    pub async fn rename_branch(
+        app: AppHandle,
+        state: State<'_, AppState>,
+        repo_id: i64,
+        old_name: String,
+        new_name: String,
+    ) -> Result<git::BranchInfo> {
+        let repo = state.db.get_repository(repo_id)?;
+        let info = git::rename_branch(&PathBuf::from(&repo.path), &old_name, &new_name)?;
+        refresh_after_branch_change(app, &state, repo)?;
+        Ok(info)
+    }
+
+    /// Delete a branch, recording its tip so it can be restored.
+    #[tauri::command]
+    pub async fn delete_branch(
+        app: AppHandle,
+        state: State<'_, AppState>,
+        repo_id: i64,
+        name: String,
+        force: Option<bool>,
+    ) -> Result<git::DeletedBranchTip> {
+        let repo = state.db.get_repository(repo_id)?;
+        let deleted = git::delete_branch(&PathBuf::from(&repo.path), &name, force.unwrap_or(false))?;
+        if let Err(e) = state.db.record_deleted_branch(repo_id, &deleted.branch, &deleted.tip_oid) {
+            tracing::warn!(repo_id, branch = %deleted.branch, ?e, "Failed to record deleted branch");
+        }
+        refresh_after_branch_change(app, &state, repo)?;
+        Ok(deleted)
+    }
+
+    #[tauri::command]
+    pub async fn set_branch_upstream(
+        app: AppHandle,
+        state: State<'_, AppState>,
+        repo_id: i64,
+        name: String,
+        upstream: Option<String>,
+    ) -> Result<git::BranchInfo> {
+        let repo = state.db.get_repository(repo_id)?;
+        let info = git::set_branch_upstream(&PathBuf::from(&repo.path), &name, upstream.as_deref())?;
+        refresh_after_branch_change(app, &state, repo)?;
+        Ok(info)
+    }
+
+    // ========== Cross-Repo Branch Commands ==========
+
+    /// Repositories picked by id and/or tag (all when neither is given).
+    fn select_repos(
+        db: &Database,
+        repo_ids: Option<&[i64]>,
+        tag: Option<&str>,
+    ) -> Result<Vec<StoredRepo>> {
+        let mut repos = match tag {
+            Some(tag) => db.get_repositories_with_tag(tag)?,
+            None => db.get_all_repositories()?,
+        };
+        if let Some(ids) = repo_ids {
+            repos.retain(|repo| ids.contains(&repo.id));
+        }
+        Ok(repos)
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct RepoBranchPresence {
+        pub repo_id: i64,
+        pub repo_name: String,
+        #[serde(flatten)]
+        pub presence: git::BranchPresence,
+        pub error: Option<String>,
+    }
+
+    /// Which repositories have branch `name`, locally or on a remote.
+    /// Repositories that could not be read are listed with an error.
+    #[tauri::command]
+    pub async fn find_branch_across_repos(
+        state: State<'_, AppState>,
+        name: String,
+        repo_ids: Option<Vec<i64>>,
+        tag: Option<String>,
+    ) -> Result<Vec<RepoBranchPresence>> {
+        let repos = select_repos(&state.db, repo_ids.as_deref(), tag.as_deref())?;
+
+        let found = tauri::async_runtime::spawn_blocking(move || {
+            health::run_parallel(&repos, health::default_worker_count(), |repo| {
+                let (presence, error) =
+                    match git::branch_presence(&PathBuf::from(&repo.path), &name) {
+                        Ok(presence) if presence.exists() => (presence, None),
+                        Ok(_) => return None,
+                        Err(e) => (git::BranchPresence::default(), Some(e.to_string())),
+                    };
+                Some(RepoBranchPresence {
+                    repo_id: repo.id,
+                    repo_name: repo.name.clone(),
+                    presence,
+                    error,
+                })
+            })
+        })
+        .await
+        .map_err(|e| DevBaseError::Internal {
+            message: format!("Branch lookup failed: {e}"),
+        })?;
+
+        Ok(found.into_iter().flatten().collect())
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct RepoBranchOperationResult {
+        pub repo_id: i64,
+        pub repo_name: String,
+        #[serde(flatten)]
+        pub outcome: git::BranchOperationOutcome,
+    }
+
+    #[derive(Debug, Clone, Default, Serialize)]
+    pub struct BranchOperationReport {
+        pub results: Vec<RepoBranchOperationResult>,
+        pub done: usize,
+        pub skipped: usize,
+        pub failed: usize,
+    }
+
+    /// Create, check out or delete a branch name across a group of repositories,
+    /// picked by id and/or tag.
+    #[tauri::command]
+    pub async fn bulk_branch_operation(
+        app: AppHandle,
+        state: State<'_, AppState>,
+        operation: git::BranchOperation,
+        repo_ids: Option<Vec<i64>>,
+        tag: Option<String>,
+    ) -> Result<BranchOperationReport> {
+        if repo_ids.is_none() && tag.is_none() {
+            return Err(DevBaseError::Config {
+                message: "Choose repositories or a tag for the branch operation".to_string(),
+            });
+        }
+        let repos = select_repos(&state.db, repo_ids.as_deref(), tag.as_deref())?;
+
+        let db = Arc::clone(&state.db);
+        let (results, changed) = tauri::async_runtime::spawn_blocking(move || {
+            let results = health::run_parallel(&repos, health::default_worker_count(), |repo| {
+                let outcome = git::apply_branch_operation(&PathBuf::from(&repo.path), &operation);
+                if let (
+                    git::BranchOperation::Delete { name, .. },
+                    git::BranchOperationOutcome::Done { tip_oid },
+                ) = (&operation, &outcome)
+                {
+                    if let Err(e) = db.record_deleted_branch(repo.id, name, tip_oid) {
+                        tracing::warn!(repo_id = repo.id, ?e, "Failed to record deleted branch");
+                    }
+                }
+                RepoBranchOperationResult {
+                    repo_id: repo.id,
+                    repo_name: repo.name.clone(),
+                    outcome,
+                }
+            });
+            let changed: Vec<StoredRepo> = repos
+                .into_iter()
+                .zip(&results)
+                .filter(|(_, r)| matches!(r.outcome, git::BranchOperationOutcome::Done { .. }))
+                .map(|(repo, _)| repo)
+                .collect();
+            (results, changed)
+        })
+        .await
+        .map_err(|e| DevBaseError::Internal {
+            message: format!("Branch operation failed: {e}"),
+        })?;
+
+        let mut report = BranchOperationReport::default();
+        for result in &results {
+            match result.outcome {
+                git::BranchOperationOutcome::Done { .. } => report.done += 1,
+                git::BranchOperationOutcome::Skipped { .. } => report.skipped += 1,
+                git::BranchOperationOutcome::Failed { .. } => report.failed += 1,
+            }
+        }
+        report.results = results;
+
+        let jobs = health::health_jobs(&state.db, &changed)?;
+        spawn_health_refresh(app, &state, jobs, RefreshMode::All);
+
+        Ok(report)
+    }
+
+    // ========== Conflict Commands ==========
+
+    #[tauri::command]
+    pub fn list_conflicts(path: String) -> Result<git::ConflictReport> {
+        git::list_conflicts(&expand_path(&path))
+    }
+
+    #[tauri::command]
+    pub fn resolve_conflict(
+        path: String,
+        file: String,
+        resolution: git::ConflictResolution,
+    ) -> Result<()> {
+        git::resolve_conflict(&expand_path(&path), &file, &resolution)
+    }
+
+    #[tauri::command]
+    pub fn continue_operation(path: String, message: Option<String>) -> Result<git::ContinueOutcome> {
+        git::continue_operation(&expand_path(&path), message.as_deref())
+    }
+
+    #[tauri::command]
+    pub fn abort_operation(path: String) -> Result<()> {
+        git::abort_operation(&expand_path(&path))
+    }
+
+    // ========== Reflog Commands ==========
+
+    #[tauri::command]
+    pub fn list_reflog(
+        path: String,
+        reference: Option<String>,
+        limit: Option<usize>,
+    ) -> Result<Vec<git::ReflogEntry>> {
+        git::list_reflog(&expand_path(&path), reference.as_deref(), limit)
+    }
+
+    #[tauri::command]
+    pub fn restore_reflog_entry(
+        path: String,
+        reference: Option<String>,
+        index: usize,
+    ) -> Result<git::ReflogRestore> {
+        git::restore_reflog_entry(&expand_path(&path), reference.as_deref(), index)
+    }
+
+    // ========== Worktree Commands ==========
+
+    /// The main repository of `repo_id`, which may be a linked worktree.
+    fn main_repository(db: &Database, repo_id: i64) -> Result<StoredRepo> {
+        let repo = db.get_repository(repo_id)?;
+        match repo.parent_id {
+            Some(parent_id) => db.get_repository(parent_id),
+            None => Ok(repo),
+        }
+    }
+
+    /// Register live linked worktrees as children of `parent` and unlink the
+    /// children whose worktree is gone. Returns the registered children.
+    fn register_worktrees(
+        db: &Database,
+        parent: &StoredRepo,
+        worktrees: &[git::WorktreeInfo],
+    ) -> Result<Vec<StoredRepo>> {
+        let live: Vec<&git::WorktreeInfo> = worktrees
+            .iter()
+            .filter(|w| !w.is_main && !w.is_prunable && w.path.exists())
+            .collect();
+
+        for worktree in &live {
+            let path = worktree.path.to_string_lossy().to_string();
+            let child_id = match db.find_repository_by_path(&path)? {
+                Some(child) => child.id,
+                None => {
+                    db.upsert_repository(&scanner::extract_repo_info(&worktree.path)?)?;
+                    db.find_repository_by_path(&path)?
+                        .ok_or_else(|| DevBaseError::Internal {
+                            message: format!("Failed to register worktree {path}"),
+                        })?
+                        .id
+                }
+            };
+            db.set_worktree_parent(child_id, parent.id)?;
+        }
+
+        // A missing worktree may only be unmounted, so keep its repository
+        let mut children = Vec::new();
+        for child in db.get_worktree_children(parent.id)? {
+            if live.iter().any(|w| w.path == PathBuf::from(&child.path)) {
+                children.push(child);
+            } else {
+                db.clear_worktree_parent(child.id)?;
+            }
+        }
+        Ok(children)
+    }
+
+    /// Drop the repositories of worktrees that were removed or pruned.
+    fn forget_worktrees(db: &Database, removed: &[git::WorktreeInfo]) -> Result<()> {
+        for worktree in removed {
+            if let Some(repo) = db.find_repository_by_path(&worktree.path.to_string_lossy())? {
+                db.delete_repository(repo.id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Worktrees of a repository; linked ones are registered as its children.
+    #[tauri::command]
+    pub async fn list_worktrees(
+        state: State<'_, AppState>,
+        repo_id: i64,
+        with_health: Option<bool>,
+    ) -> Result<Vec<git::WorktreeInfo>> {
+        let parent = main_repository(&state.db, repo_id)?;
+        let worktrees =
+            git::list_worktrees(&PathBuf::from(&parent.path), with_health.unwrap_or(true))?;
+        register_worktrees(&state.db, &parent, &worktrees)?;
+        Ok(worktrees)
+    }
+
+    #[tauri::command]
+    pub async fn add_worktree(
+        app: AppHandle,
+        state: State<'_, AppState>,
+        repo_id: i64,
+        branch: String,
+        path: String,
+        start_point: Option<String>,
+    ) -> Result<git::WorktreeInfo> {
+        let parent = main_repository(&state.db, repo_id)?;
+        let parent_path = PathBuf::from(&parent.path);
+        let added =
+            git::add_worktree(&parent_path, &branch, &expand_path(&path), start_point.as_deref())?;
+
+        let children =
+            register_worktrees(&state.db, &parent, &git::list_worktrees(&parent_path, false)?)?;
+        let jobs = health::health_jobs(&state.db, &children)?;
+        spawn_health_refresh(app, &state, jobs, RefreshMode::All);
+        Ok(added)
+    }
+
+    #[tauri::command]
+    pub async fn remove_worktree(
+        state: State<'_, AppState>,
+        repo_id: i64,
+        name: String,
+        force: Option<bool>,
+    ) -> Result<()> {
+        let parent = main_repository(&state.db, repo_id)?;
+        let parent_path = PathBuf::from(&parent.path);
+        let removed: Vec<git::WorktreeInfo> = git::list_worktrees(&parent_path, false)?
+            .into_iter()
+            .filter(|w| w.name.as_deref() == Some(name.as_str()))
+            .collect();
+        git::remove_worktree(&parent_path, &name, force.unwrap_or(false))?;
+        forget_worktrees(&state.db, &removed)?;
+        register_worktrees(&state.db, &parent, &git::list_worktrees(&parent_path, false)?)?;
+        Ok(())
+    }
+
+    #[tauri::command]
+    pub async fn prune_worktrees(state: State<'_, AppState>, repo_id: i64) -> Result<Vec<String>> {
+        let parent = main_repository(&state.db, repo_id)?;
+        let parent_path = PathBuf::from(&parent.path);
+        let before = git::list_worktrees(&parent_path, false)?;
+        let pruned = git::prune_worktrees(&parent_path)?;
+        let removed: Vec<git::WorktreeInfo> = before
+            .into_iter()
+            .filter(|w| w.name.as_ref().is_some_and(|name| pruned.contains(name)))
+            .collect();
+        forget_worktrees(&state.db, &removed)?;
+        register_worktrees(&state.db, &parent, &git::list_worktrees(&parent_path, false)?)?;
+        Ok(pruned)
+    }
+
+    // ========== Release Commands ==========
+
+    #[tauri::command]
+    pub fn list_git_tags(path: String) -> Result<Vec<git::TagInfo>> {
+        git::list_tags(&expand_path(&path))
+    }
+
+    #[tauri::command]
+    pub fn get_release_summary(
+        path: String,
+        include_prereleases: Option<bool>,
+    ) -> Result<git::ReleaseSummary> {
+        git::get_release_summary(&expand_path(&path), include_prereleases.unwrap_or(false))
+    }
+
+    #[tauri::command]
+    pub fn get_release_notes(
+        path: String,
+        from: Option<String>,
+        to: Option<String>,
+    ) -> Result<git::ReleaseNotes> {
+        git::generate_release_notes(&expand_path(&path), from.as_deref(), to.as_deref())
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct RepoRelease {
+        pub repo_id: i64,
+        pub repo_name: String,
+        #[serde(flatten)]
+        pub summary: git::ReleaseSummary,
+        pub error: Option<String>,
+    }
+
+    /// Latest release of each repository, most unreleased commits first.
+    #[tauri::command]
+    pub async fn get_release_inventory(
+        state: State<'_, AppState>,
+        repo_ids: Option<Vec<i64>>,
+        tag: Option<String>,
+        include_prereleases: Option<bool>,
+    ) -> Result<Vec<RepoRelease>> {
+        let repos = select_repos(&state.db, repo_ids.as_deref(), tag.as_deref())?;
+        let include_prereleases = include_prereleases.unwrap_or(false);
+
+        let mut releases = tauri::async_runtime::spawn_blocking(move || {
+            health::run_parallel(&repos, health::default_worker_count(), |repo| {
+                let (summary, error) = match git::get_release_summary(
+                    &PathBuf::from(&repo.path),
+                    include_prereleases,
+                ) {
+                    Ok(summary) => (summary, None),
+                    Err(e) => (git::ReleaseSummary::default(), Some(e.to_string())),
+                };
+                RepoRelease {
+                    repo_id: repo.id,
+                    repo_name: repo.name.clone(),
+                    summary,
+                    error,
+                }
+            })
+        })
+        .await
+        .map_err(|e| DevBaseError::Internal {
+            message: format!("Release inventory failed: {e}"),
+        })?;
+
+        releases.sort_by(|a, b| {
+            b.summary
+                .commits_since
+                .cmp(&a.summary.commits_since)
+                .then_with(|| a.repo_name.cmp(&b.repo_name))
+        });
+        Ok(releases)
+    }
 }
 
 /// Initialize the tracing subscriber for logging.
@@ -339,7 +1563,11 @@ pub fn run() {
         }
     };
 
-    let app_state = AppState { db };
+    let app_state = AppState {
+        db,
+        blame_cache: BlameCache::new(),
+        health_refresh: Arc::new(Mutex::new(RefreshQueue::default())),
+    };
 
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
@@ -355,7 +1583,42 @@ pub fn run() {
             // Repositories
             commands::get_repositories,
             commands::get_repo_health,
+            commands::get_file_statuses,
             commands::refresh_repo,
+            commands::get_repo_health_options,
+            commands::set_repo_health_options,
+            // Fetch
+            commands::fetch_repositories,
+            // Sync
+            commands::sync_repositories,
+            // Push
+            commands::push_repositories,
+            // Stash
+            commands::list_stashes,
+            commands::get_stash_diff,
+            commands::create_stash,
+            commands::apply_stash,
+            commands::pop_stash,
+            commands::drop_stash,
+            commands::get_old_stashes,
+            // Staging
+            commands::get_file_diff,
+            commands::stage_files,
+            commands::unstage_files,
+            commands::stage_selection,
+            commands::unstage_selection,
+            commands::discard_changes,
+            commands::list_discard_backups,
+            commands::restore_discard_backup,
+            // Commit
+            commands::create_commit,
+            // Health history
+            commands::get_health_timeline,
+            commands::get_repos_in_condition,
+            // Policies
+            commands::get_policy_rules,
+            commands::set_policy_rules,
+            commands::evaluate_policies,
             // Settings
             commands::get_scan_paths,
             commands::add_scan_path,
@@ -371,6 +1634,47 @@ pub fn run() {
             commands::remove_tag,
             // History
             commands::get_commit_log,
+            // Diff
+            commands::get_binary_diff,
+            // Blame
+            commands::get_file_blame,
+            // Time-travel
+            commands::list_tree_at,
+            commands::read_file_at,
+            // Branches
+            commands::list_branches,
+            // Branch sweeper
+            commands::plan_branch_sweep,
+            commands::execute_branch_sweep,
+            commands::get_deleted_branches,
+            commands::restore_deleted_branch,
+            // Branch management
+            commands::checkout_branch,
+            commands::create_branch,
+            commands::rename_branch,
+            commands::delete_branch,
+            commands::set_branch_upstream,
+            // Cross-repo branches
+            commands::find_branch_across_repos,
+            commands::bulk_branch_operation,
+            // Conflicts
+            commands::list_conflicts,
+            commands::resolve_conflict,
+            commands::continue_operation,
+            commands::abort_operation,
+            // Reflog
+            commands::list_reflog,
+            commands::restore_reflog_entry,
+            // Worktrees
+            commands::list_worktrees,
+            commands::add_worktree,
+            commands::remove_worktree,
+            commands::prune_worktrees,
+            // Releases
+            commands::list_git_tags,
+            commands::get_release_summary,
+            commands::get_release_notes,
+            commands::get_release_inventory,
         ])
         .setup(|app| {
             let window = app.get_webview_window("main");
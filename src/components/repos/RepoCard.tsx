@@ -4,7 +4,7 @@ import {
     ExternalLink,
     RefreshCw
 } from 'lucide-react';
-import { RepoInfo } from '../../services/tauri';
+import { RepoInfo, RepoState } from '../../services/tauri';
 import { refreshRepo } from '../../services/tauri';
 import { useAppStore } from '../../stores/appStore';
 import { useRepoStore } from '../../stores/repoStore';
@@ -14,6 +14,21 @@ interface RepoCardProps {
     repo: RepoInfo;
 }
 
+const OPERATION_LABELS: Record<RepoState, string> = {
+    clean: '',
+    merge: 'Merging',
+    revert: 'Reverting',
+    revert_sequence: 'Reverting',
+    cherry_pick: 'Cherry-picking',
+    cherry_pick_sequence: 'Cherry-picking',
+    bisect: 'Bisecting',
+    rebase: 'Rebasing',
+    rebase_interactive: 'Rebasing',
+    rebase_merge: 'Rebasing',
+    apply_mailbox: 'Applying patches',
+    apply_mailbox_or_rebase: 'Rebasing',
+};
+
 
 export function RepoCard({ repo }: RepoCardProps) {
     const fetchRepositories = useRepoStore(state => state.fetchRepositories);
@@ -50,11 +65,80 @@ export function RepoCard({ repo }: RepoCardProps) {
     // const statusInfo = statusConfig[repo.status];
     const { health } = repo;
 
+    // Operation name with its step, e.g. "Rebasing 2/5"
+    const describeOperation = () => {
+        const label = OPERATION_LABELS[health.state];
+        const progress = health.operation_progress;
+        return progress ? `${label} ${progress.current}/${progress.total}` : label;
+    };
+
     const getProgressClass = () => {
-        if (health.is_dirty) return 'status-fill-dirty';
-        if (health.commits_behind > 0) return 'status-fill-behind';
-        if (health.commits_ahead > 0) return 'status-fill-ahead';
-        return 'status-fill-clean';
+        switch (repo.status) {
+            case 'conflicted':
+                return 'status-fill-conflicted';
+            case 'in_progress':
+                return 'status-fill-in-progress';
+            case 'unavailable':
+            case 'error':
+            case 'behind':
+            case 'diverged':
+                return 'status-fill-behind';
+            case 'dirty':
+                return 'status-fill-dirty';
+            case 'ahead':
+                return 'status-fill-ahead';
+            case 'partial':
+                return 'status-fill-partial';
+            default:
+                return 'status-fill-clean';
+        }
+    };
+
+    const renderStatusBadge = () => {
+        switch (repo.status) {
+            case 'unavailable':
+            case 'error':
+                return (
+                    <span style={{ color: 'var(--error)' }} title={health.error?.message}>
+                        {repo.status === 'unavailable' ? 'Unavailable' : 'Error'}
+                    </span>
+                );
+            case 'conflicted': {
+                const operation = describeOperation();
+                return (
+                    <span style={{ color: 'var(--error)' }} title={health.conflicted_files.join('\n')}>
+                        {operation && `${operation} · `}{health.conflicted_count} conflicted
+                    </span>
+                );
+            }
+            case 'in_progress':
+                return (
+                    <span style={{ color: 'var(--warning)' }}>
+                        {describeOperation()}
+                    </span>
+                );
+            case 'dirty':
+                return (
+                    <span style={{ color: 'var(--status-dirty)' }}>
+                        {health.uncommitted_count} uncommitted
+                    </span>
+                );
+            case 'partial':
+                return (
+                    <span style={{ color: 'var(--text-muted)' }} title="Health check ran out of time">
+                        Partial
+                    </span>
+                );
+            case 'behind':
+            case 'diverged':
+                return (
+                    <span style={{ color: 'var(--error)' }}>
+                        {health.commits_behind} behind
+                    </span>
+                );
+            default:
+                return <span style={{ color: 'var(--success)' }}>Active</span>;
+        }
     };
 
     return (
@@ -90,17 +174,7 @@ export function RepoCard({ repo }: RepoCardProps) {
 
                     {/* Status Text */}
                     <div className="status-badge-minimal">
-                        {health.is_dirty ? (
-                            <span style={{ color: 'var(--status-dirty)' }}>
-                                {health.uncommitted_count} uncommitted
-                            </span>
-                        ) : health.commits_behind > 0 ? (
-                            <span style={{ color: 'var(--error)' }}>
-                                {health.commits_behind} behind
-                            </span>
-                        ) : (
-                            <span style={{ color: 'var(--success)' }}>Active</span>
-                        )}
+                        {renderStatusBadge()}
                     </div>
                 </div>
             </div>
@@ -1,6 +1,7 @@
 import { useEffect } from 'react';
 import { FolderGit2, RefreshCw, Search, Filter, X, ChevronDown } from 'lucide-react';
 import { useRepoStore, useFilteredRepos } from '../../stores/repoStore';
+import { onRepoHealthUpdated } from '../../services/tauri';
 import { RepoCard } from './RepoCard';
 import './RepoGrid.css';
 
@@ -13,6 +14,7 @@ export function RepoGrid() {
         selectedStatus,
         fetchRepositories,
         fetchScanPaths,
+        applyRepoUpdate,
         runScan,
         setSearchQuery,
         setSelectedStatus,
@@ -27,13 +29,25 @@ export function RepoGrid() {
         fetchScanPaths();
     }, [fetchRepositories, fetchScanPaths]);
 
+    useEffect(() => {
+        const unlisten = onRepoHealthUpdated(applyRepoUpdate);
+        return () => {
+            unlisten.then(fn => fn());
+        };
+    }, [applyRepoUpdate]);
+
     const handleScan = async () => {
         await runScan();
     };
 
     const statusOptions = [
+        { value: 'unavailable', label: 'Unavailable', color: 'var(--color-error)' },
+        { value: 'error', label: 'Error', color: 'var(--color-error)' },
+        { value: 'conflicted', label: 'Conflicted', color: 'var(--color-error)' },
+        { value: 'in_progress', label: 'In Progress', color: 'var(--color-warning)' },
         { value: 'clean', label: 'Clean', color: 'var(--color-success)' },
         { value: 'dirty', label: 'Dirty', color: 'var(--color-warning)' },
+        { value: 'partial', label: 'Partial', color: 'var(--text-muted)' },
         { value: 'ahead', label: 'Ahead', color: 'var(--color-info)' },
         { value: 'behind', label: 'Behind', color: 'var(--color-error)' },
         { value: 'diverged', label: 'Diverged', color: 'var(--color-error)' },
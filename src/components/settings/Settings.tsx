@@ -2,7 +2,7 @@ import { useEffect, useState } from 'react';
 import { FolderPlus, Trash2, ChevronDown, ChevronRight, RefreshCw, FolderSearch } from 'lucide-react';
 import { open } from '@tauri-apps/plugin-dialog';
 import { useRepoStore } from '../../stores/repoStore';
-import { addScanPath, removeScanPath, updateScanPath } from '../../services/tauri';
+import { addScanPath, removeScanPath, updateScanPath, errorMessage } from '../../services/tauri';
 import './Settings.css';
 
 export function Settings() {
@@ -45,7 +45,7 @@ export function Settings() {
             await fetchScanPaths();
             setNewPath('');
         } catch (e) {
-            setError(String(e));
+            setError(errorMessage(e));
         } finally {
             setIsAdding(false);
         }
@@ -56,7 +56,7 @@ export function Settings() {
             await removeScanPath(id);
             await fetchScanPaths();
         } catch (e) {
-            setError(String(e));
+            setError(errorMessage(e));
         }
     };
 
@@ -65,7 +65,7 @@ export function Settings() {
             await updateScanPath(id, !currentEnabled);
             await fetchScanPaths();
         } catch (e) {
-            setError(String(e));
+            setError(errorMessage(e));
         }
     };
 
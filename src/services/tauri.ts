@@ -3,6 +3,7 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
 
 // ========== Types ==========
 
@@ -29,9 +30,143 @@ export interface RepoHealth {
     stash_count: number;
     current_branch: string | null;
     is_detached: boolean;
+    state: RepoState;
+    operation_progress: OperationProgress | null;
+    conflicted_count: number;
+    conflicted_files: string[];
+    breakdown: StatusBreakdown;
+    default_branch: string | null;
+    ahead_of_default: number;
+    behind_default: number;
+    unpushed_branch_count: number;
+    unpushed_branches: string[];
+    is_light: boolean;
+    is_partial: boolean;
+    error: HealthError | null;
+}
+
+export type HealthErrorKind =
+    | 'missing_path'
+    | 'permission_denied'
+    | 'corrupt_repository'
+    | 'locked_index'
+    | 'other';
+
+export interface HealthError {
+    kind: HealthErrorKind;
+    message: string;
+}
+
+export type HealthCondition = 'dirty' | 'behind' | 'unpushed';
+
+export interface HealthSnapshot {
+    id: number;
+    repo_id: number;
+    status: RepoStatus;
+    health: RepoHealth;
+    recorded_at: number;
 }
 
-export type RepoStatus = 'clean' | 'dirty' | 'ahead' | 'behind' | 'diverged';
+export interface ConditionStreak {
+    repo_id: number;
+    condition: HealthCondition;
+    since: number;
+}
+
+export type Severity = 'info' | 'warning' | 'error';
+
+export type PolicyCheck =
+    | { kind: 'max_uncommitted_age_days'; days: number }
+    | { kind: 'max_stash_count'; max: number }
+    | { kind: 'required_files'; files: string[] }
+    | { kind: 'default_branch_not'; name: string }
+    | { kind: 'email_domain'; domain: string }
+    | { kind: 'no_detached_head' };
+
+export type PolicyScope =
+    | { kind: 'all' }
+    | { kind: 'tag'; name: string }
+    | { kind: 'scan_path'; path: string };
+
+export interface PolicyRule {
+    id: string;
+    check: PolicyCheck;
+    severity: Severity;
+    scope: PolicyScope;
+    enabled: boolean;
+}
+
+export interface RuleViolation {
+    rule_id: string;
+    severity: Severity;
+    message: string;
+}
+
+export interface PolicyReport {
+    repo_id: number;
+    score: number;
+    rules_evaluated: number;
+    violations: RuleViolation[];
+    unknown_rules: string[];
+}
+
+export interface HealthOptions {
+    light: boolean;
+    budget_ms: number | null;
+}
+
+export interface StatusBreakdown {
+    untracked: number;
+    added: number;
+    modified: number;
+    deleted: number;
+    renamed: number;
+    type_changed: number;
+    conflicted: number;
+    ignored_large: number;
+}
+
+export type FileChange = 'new' | 'modified' | 'deleted' | 'renamed' | 'type_change';
+
+export interface FileStatusEntry {
+    path: string;
+    old_path: string | null;
+    index_status: FileChange | null;
+    worktree_status: FileChange | null;
+    is_conflicted: boolean;
+    is_ignored: boolean;
+}
+
+export type RepoState =
+    | 'clean'
+    | 'merge'
+    | 'revert'
+    | 'revert_sequence'
+    | 'cherry_pick'
+    | 'cherry_pick_sequence'
+    | 'bisect'
+    | 'rebase'
+    | 'rebase_interactive'
+    | 'rebase_merge'
+    | 'apply_mailbox'
+    | 'apply_mailbox_or_rebase';
+
+export interface OperationProgress {
+    current: number;
+    total: number;
+}
+
+export type RepoStatus =
+    | 'unavailable'
+    | 'error'
+    | 'conflicted'
+    | 'in_progress'
+    | 'clean'
+    | 'dirty'
+    | 'partial'
+    | 'ahead'
+    | 'behind'
+    | 'diverged';
 
 export interface RepoInfo {
     id: number;
@@ -43,6 +178,371 @@ export interface RepoInfo {
     health: RepoHealth;
     status: RepoStatus;
     tags: string[];
+    last_fetched_at: string | null;
+    parent_id: number | null;
+}
+
+export interface FetchOptions {
+    prune: boolean;
+    timeout_secs: number | null;
+    remotes: string[];
+}
+
+export interface FetchProgress {
+    remote: string;
+    received_objects: number;
+    indexed_objects: number;
+    total_objects: number;
+    received_bytes: number;
+}
+
+export interface RefUpdate {
+    ref_name: string;
+    old_oid: string | null;
+    new_oid: string | null;
+}
+
+export interface RemoteFetchResult {
+    remote: string;
+    url: string | null;
+    updated_refs: RefUpdate[];
+    received_objects: number;
+    received_bytes: number;
+    error: string | null;
+}
+
+export interface FetchResult {
+    remotes: RemoteFetchResult[];
+    timed_out: boolean;
+    duration_ms: number;
+}
+
+export interface RepoFetchProgress {
+    repo_id: number;
+    progress: FetchProgress;
+}
+
+export interface RepoFetchResult {
+    repo_id: number;
+    result: FetchResult | null;
+    error: string | null;
+}
+
+export interface PushOptions {
+    branches: string[];
+    remote: string | null;
+    set_upstream: boolean;
+    force_with_lease: boolean;
+}
+
+export type RefPushStatus =
+    | 'pushed'
+    | 'created'
+    | 'up_to_date'
+    | 'rejected_non_fast_forward'
+    | 'rejected_stale_lease'
+    | 'hook_declined'
+    | 'rejected'
+    | 'failed';
+
+export interface RefPushResult {
+    branch: string;
+    remote: string;
+    remote_ref: string;
+    old_oid: string | null;
+    new_oid: string;
+    status: RefPushStatus;
+    message: string | null;
+    upstream_set: boolean;
+}
+
+export interface PushResult {
+    refs: RefPushResult[];
+    duration_ms: number;
+}
+
+export interface RepoPushResult {
+    repo_id: number;
+    result: PushResult | null;
+    error: string | null;
+}
+
+export interface StashFileChange {
+    path: string;
+    old_path: string | null;
+    status: 'added' | 'modified' | 'deleted' | 'renamed' | 'typechange' | 'untracked';
+}
+
+export interface StashEntry {
+    index: number;
+    oid: string;
+    message: string;
+    branch: string | null;
+    date: number;
+    files: StashFileChange[];
+    additions: number;
+    deletions: number;
+    has_untracked: boolean;
+}
+
+export interface StashFilePatch extends StashFileChange {
+    additions: number;
+    deletions: number;
+    is_binary: boolean;
+    patch: string;
+}
+
+export interface StashCreateOptions {
+    message: string | null;
+    include_untracked: boolean;
+    keep_index: boolean;
+}
+
+export interface StashApplyResult {
+    applied: boolean;
+    conflicts: string[];
+    dropped: boolean;
+    message: string | null;
+}
+
+export interface RepoStash {
+    repo_id: number;
+    repo_name: string;
+    repo_path: string;
+    stash: StashEntry;
+}
+
+export interface DiffLineInfo {
+    origin: '+' | '-' | ' ';
+    old_lineno: number | null;
+    new_lineno: number | null;
+    content: string;
+}
+
+export interface DiffHunkInfo {
+    index: number;
+    header: string;
+    old_start: number;
+    old_lines: number;
+    new_start: number;
+    new_lines: number;
+    lines: DiffLineInfo[];
+}
+
+export interface FileDiff {
+    path: string;
+    is_binary: boolean;
+    hunks: DiffHunkInfo[];
+}
+
+export interface LineRef {
+    old_lineno: number | null;
+    new_lineno: number | null;
+}
+
+export type PatchSelection =
+    | { kind: 'hunks'; hunks: number[] }
+    | { kind: 'lines'; lines: LineRef[] };
+
+export interface DiscardBackup {
+    ref_name: string;
+    oid: string;
+    files: string[];
+    created_at: number;
+}
+
+export interface CommitOptions {
+    skip_hooks: boolean;
+    allow_empty: boolean;
+}
+
+export interface CheckoutOptions {
+    autostash: boolean;
+}
+
+export interface CheckoutResult {
+    branch: string;
+    previous: string | null;
+    head_oid: string;
+    created_tracking: boolean;
+    stashed: boolean;
+    stash_conflict: boolean;
+}
+
+export interface DeletedBranchTip {
+    branch: string;
+    tip_oid: string;
+}
+
+export interface BranchPresence {
+    local_tip: string | null;
+    is_head: boolean;
+    remote_branches: string[];
+    upstream: string | null;
+    ahead_upstream: number;
+    behind_upstream: number;
+    default_branch: string | null;
+    ahead_default: number;
+    behind_default: number;
+}
+
+export type RepoBranchPresence = BranchPresence & { repo_id: number; repo_name: string; error: string | null };
+
+export type BranchOperation =
+    | { op: 'create'; name: string; start_point?: string | null }
+    | { op: 'checkout'; name: string; autostash?: boolean }
+    | { op: 'delete'; name: string; force?: boolean };
+
+export type BranchOperationOutcome =
+    | { outcome: 'done'; tip_oid: string }
+    | { outcome: 'skipped'; reason: string }
+    | { outcome: 'failed'; error: string };
+
+export type RepoBranchOperationResult = BranchOperationOutcome & { repo_id: number; repo_name: string };
+
+export interface BranchOperationReport {
+    results: RepoBranchOperationResult[];
+    done: number;
+    skipped: number;
+    failed: number;
+}
+
+export interface ConflictSide {
+    oid: string;
+    mode: number;
+    is_binary: boolean;
+    content: string | null;
+}
+
+export interface ConflictEntry {
+    path: string;
+    ancestor: ConflictSide | null;
+    ours: ConflictSide | null;
+    theirs: ConflictSide | null;
+}
+
+export interface ConflictReport {
+    state: RepoState;
+    conflicts: ConflictEntry[];
+}
+
+export type ConflictResolution = { kind: 'ours' } | { kind: 'theirs' } | { kind: 'content'; content: string };
+
+export type ContinueOutcome =
+    | { outcome: 'completed'; head_oid: string }
+    | { outcome: 'conflicts'; files: string[] };
+
+export interface ReflogEntry {
+    index: number;
+    old_oid: string;
+    new_oid: string;
+    message: string;
+    committer_name: string;
+    committer_email: string;
+    date: number;
+}
+
+export interface ReflogRestore {
+    reference: string;
+    previous_oid: string;
+    restored_oid: string;
+    backup_ref: string;
+}
+
+export interface WorktreeInfo {
+    name: string | null;
+    path: string;
+    is_main: boolean;
+    branch: string | null;
+    head_oid: string | null;
+    is_locked: boolean;
+    lock_reason: string | null;
+    is_prunable: boolean;
+    health: RepoHealth | null;
+}
+
+export interface TagInfo {
+    name: string;
+    target_oid: string;
+    is_annotated: boolean;
+    tagger_name: string | null;
+    tagger_email: string | null;
+    date: number;
+    message: string | null;
+    version: string | null;
+    is_prerelease: boolean;
+}
+
+export interface AuthorCount {
+    name: string;
+    email: string;
+    commits: number;
+}
+
+export interface ReleaseSummary {
+    latest_release: TagInfo | null;
+    branch: string | null;
+    commits_since: number;
+    authors_since: AuthorCount[];
+}
+
+export interface CommitFooter {
+    token: string;
+    value: string;
+}
+
+export type ConventionalCommit = CommitLogEntry & {
+    type: string;
+    scope: string | null;
+    breaking: boolean;
+    description: string;
+    body: string | null;
+    footers: CommitFooter[];
+};
+
+export type VersionBump = 'none' | 'patch' | 'minor' | 'major';
+
+export interface ReleaseNotes {
+    from: string | null;
+    to: string;
+    previous_version: string | null;
+    bump: VersionBump;
+    next_version: string;
+    commits: ConventionalCommit[];
+    other_commits: CommitLogEntry[];
+    markdown: string;
+}
+
+export type RepoRelease = ReleaseSummary & { repo_id: number; repo_name: string; error: string | null };
+
+export interface SyncOptions {
+    fetch: boolean;
+    autostash: boolean;
+    fetch_options: Partial<FetchOptions>;
+}
+
+export type SyncOutcome =
+    | {
+        outcome: 'updated';
+        branch: string;
+        from: string;
+        to: string;
+        commits: number;
+        stashed: boolean;
+        stash_conflict: boolean;
+    }
+    | { outcome: 'up_to_date'; branch: string }
+    | { outcome: 'skipped'; reason: string }
+    | { outcome: 'failed'; error: string };
+
+export type RepoSyncResult = SyncOutcome & { repo_id: number };
+
+export interface SyncReport {
+    results: RepoSyncResult[];
+    updated: number;
+    up_to_date: number;
+    skipped: number;
+    failed: number;
 }
 
 export interface ScanPath {
@@ -61,6 +561,23 @@ export interface Tag {
 export interface IpcError {
     code: string;
     message: string;
+    files?: string[];
+    hook?: string;
+    output?: string;
+}
+
+export function isIpcError(error: unknown): error is IpcError {
+    return (
+        typeof error === 'object' &&
+        error !== null &&
+        typeof (error as IpcError).code === 'string' &&
+        typeof (error as IpcError).message === 'string'
+    );
+}
+
+/** Message to show for an error thrown by a command. */
+export function errorMessage(error: unknown): string {
+    return isIpcError(error) ? error.message : String(error);
 }
 
 // ========== Health Commands ==========
@@ -85,18 +602,124 @@ export async function scanPath(path: string, maxDepth?: number): Promise<Discove
 
 // ========== Repository Commands ==========
 
-export async function getRepositories(): Promise<RepoInfo[]> {
-    return invoke<RepoInfo[]>('get_repositories');
+export async function getRepositories(forceRefresh?: boolean): Promise<RepoInfo[]> {
+    return invoke<RepoInfo[]>('get_repositories', { forceRefresh });
+}
+
+export async function onRepoHealthUpdated(
+    handler: (repo: RepoInfo) => void
+): Promise<UnlistenFn> {
+    return listen<RepoInfo>('repo-health-updated', (event) => handler(event.payload));
 }
 
 export async function getRepoHealth(path: string): Promise<RepoHealth> {
     return invoke<RepoHealth>('get_repo_health', { path });
 }
 
+export async function getFileStatuses(
+    path: string,
+    includeIgnored?: boolean
+): Promise<FileStatusEntry[]> {
+    return invoke<FileStatusEntry[]>('get_file_statuses', { path, includeIgnored });
+}
+
 export async function refreshRepo(repoId: number): Promise<RepoInfo> {
     return invoke<RepoInfo>('refresh_repo', { repoId });
 }
 
+export async function getRepoHealthOptions(repoId: number): Promise<HealthOptions> {
+    return invoke<HealthOptions>('get_repo_health_options', { repoId });
+}
+
+export async function setRepoHealthOptions(repoId: number, options: HealthOptions): Promise<void> {
+    return invoke('set_repo_health_options', { repoId, options });
+}
+
+// ========== Fetch Commands ==========
+
+export async function fetchRepositories(
+    repoIds?: number[],
+    options?: Partial<FetchOptions>,
+    concurrency?: number
+): Promise<RepoFetchResult[]> {
+    return invoke<RepoFetchResult[]>('fetch_repositories', { repoIds, options, concurrency });
+}
+
+export async function onFetchProgress(
+    handler: (progress: RepoFetchProgress) => void
+): Promise<UnlistenFn> {
+    return listen<RepoFetchProgress>('fetch-progress', (event) => handler(event.payload));
+}
+
+export async function onFetchFinished(
+    handler: (result: RepoFetchResult) => void
+): Promise<UnlistenFn> {
+    return listen<RepoFetchResult>('fetch-finished', (event) => handler(event.payload));
+}
+
+// ========== Sync Commands ==========
+
+export async function syncRepositories(
+    repoIds?: number[],
+    options?: Partial<SyncOptions>,
+    concurrency?: number
+): Promise<SyncReport> {
+    return invoke<SyncReport>('sync_repositories', { repoIds, options, concurrency });
+}
+
+export async function onSyncFinished(
+    handler: (result: RepoSyncResult) => void
+): Promise<UnlistenFn> {
+    return listen<RepoSyncResult>('sync-finished', (event) => handler(event.payload));
+}
+
+// ========== Push Commands ==========
+
+export async function pushRepositories(
+    repoIds?: number[],
+    options?: Partial<PushOptions>,
+    concurrency?: number
+): Promise<RepoPushResult[]> {
+    return invoke<RepoPushResult[]>('push_repositories', { repoIds, options, concurrency });
+}
+
+export async function onPushFinished(
+    handler: (result: RepoPushResult) => void
+): Promise<UnlistenFn> {
+    return listen<RepoPushResult>('push-finished', (event) => handler(event.payload));
+}
+
+// ========== Health History Commands ==========
+
+export async function getHealthTimeline(
+    repoId: number,
+    since?: number,
+    limit?: number
+): Promise<HealthSnapshot[]> {
+    return invoke<HealthSnapshot[]>('get_health_timeline', { repoId, since, limit });
+}
+
+export async function getReposInCondition(
+    condition: HealthCondition,
+    minDays?: number
+): Promise<ConditionStreak[]> {
+    return invoke<ConditionStreak[]>('get_repos_in_condition', { condition, minDays });
+}
+
+// ========== Policy Commands ==========
+
+export async function getPolicyRules(): Promise<PolicyRule[]> {
+    return invoke<PolicyRule[]>('get_policy_rules');
+}
+
+export async function setPolicyRules(rules: PolicyRule[]): Promise<void> {
+    return invoke('set_policy_rules', { rules });
+}
+
+export async function evaluatePolicies(repoIds?: number[]): Promise<PolicyReport[]> {
+    return invoke<PolicyReport[]>('evaluate_policies', { repoIds });
+}
+
 // ========== Settings Commands ==========
 
 export async function getScanPaths(): Promise<ScanPath[]> {
@@ -165,3 +788,436 @@ export interface CommitLogEntry {
 export async function getCommitLog(path: string, limit?: number): Promise<CommitLogEntry[]> {
     return invoke<CommitLogEntry[]>('get_commit_log', { path, limit });
 }
+
+// ========== Diff Commands ==========
+
+export interface LfsPointer {
+    oid: string;
+    size: number;
+}
+
+export interface ImageData {
+    width: number | null;
+    height: number | null;
+    data_base64: string | null;
+}
+
+export interface BlobInfo {
+    oid: string | null;
+    size: number;
+    is_binary: boolean;
+    mime_type: string;
+    lfs_pointer: LfsPointer | null;
+    image: ImageData | null;
+}
+
+export interface BinaryDiff {
+    file_path: string;
+    old: BlobInfo | null;
+    new: BlobInfo | null;
+    size_delta: number;
+    is_binary: boolean;
+    is_image: boolean;
+    is_lfs: boolean;
+}
+
+export async function getBinaryDiff(
+    path: string,
+    filePath: string,
+    oldRev?: string,
+    newRev?: string
+): Promise<BinaryDiff> {
+    return invoke<BinaryDiff>('get_binary_diff', { path, filePath, oldRev, newRev });
+}
+
+// ========== Blame Commands ==========
+
+export interface BlameFileOptions {
+    ignore_whitespace?: boolean;
+    use_ignore_revs?: boolean;
+    start_line?: number;
+    end_line?: number;
+}
+
+export interface BlameHunk {
+    start_line: number;
+    line_count: number;
+    commit_oid: string;
+    short_oid: string;
+    author_name: string;
+    author_email: string;
+    date: number;
+    summary: string;
+    orig_path: string;
+    orig_start_line: number;
+    is_boundary: boolean;
+}
+
+export interface FileBlame {
+    file_path: string;
+    commit_oid: string;
+    blob_oid: string;
+    total_lines: number;
+    start_line: number;
+    end_line: number;
+    hunks: BlameHunk[];
+    ignored_revs: number;
+    from_cache: boolean;
+}
+
+export async function getFileBlame(
+    path: string,
+    filePath: string,
+    rev?: string,
+    options?: BlameFileOptions
+): Promise<FileBlame> {
+    return invoke<FileBlame>('get_file_blame', { path, filePath, rev, options });
+}
+
+// ========== Time-Travel Commands ==========
+
+export type TreeEntryKind = 'file' | 'executable' | 'symlink' | 'directory' | 'submodule';
+
+export interface TreeEntryInfo {
+    name: string;
+    path: string;
+    kind: TreeEntryKind;
+    mode: string;
+    oid: string;
+    size: number | null;
+    last_commit: CommitLogEntry | null;
+}
+
+export interface FileAtRevision {
+    path: string;
+    commit_oid: string;
+    blob_oid: string;
+    size: number;
+    is_binary: boolean;
+    mime_type: string;
+    encoding: string | null;
+    content: string | null;
+    truncated: boolean;
+}
+
+export async function listTreeAt(
+    path: string,
+    rev?: string,
+    dirPath?: string
+): Promise<TreeEntryInfo[]> {
+    return invoke<TreeEntryInfo[]>('list_tree_at', { path, rev, dirPath });
+}
+
+export async function readFileAt(
+    path: string,
+    rev: string,
+    filePath: string,
+    maxBytes?: number
+): Promise<FileAtRevision> {
+    return invoke<FileAtRevision>('read_file_at', { path, rev, filePath, maxBytes });
+}
+
+// ========== Branch Commands ==========
+
+export interface BranchInfo {
+    name: string;
+    ref_name: string;
+    is_remote: boolean;
+    is_head: boolean;
+    is_default: boolean;
+    tip_oid: string;
+    tip_date: number;
+    last_author_name: string;
+    last_author_email: string;
+    upstream: string | null;
+    upstream_gone: boolean;
+    commits_ahead: number;
+    commits_behind: number;
+    is_merged: boolean;
+}
+
+export async function listBranches(path: string): Promise<BranchInfo[]> {
+    return invoke<BranchInfo[]>('list_branches', { path });
+}
+
+// ========== Stash Commands ==========
+
+export async function listStashes(path: string): Promise<StashEntry[]> {
+    return invoke<StashEntry[]>('list_stashes', { path });
+}
+
+export async function getStashDiff(path: string, index: number, oid?: string): Promise<StashFilePatch[]> {
+    return invoke<StashFilePatch[]>('get_stash_diff', { path, index, oid });
+}
+
+export async function createStash(path: string, options?: Partial<StashCreateOptions>): Promise<StashEntry> {
+    return invoke<StashEntry>('create_stash', { path, options });
+}
+
+export async function applyStash(
+    path: string,
+    index: number,
+    oid?: string,
+    reinstateIndex?: boolean
+): Promise<StashApplyResult> {
+    return invoke<StashApplyResult>('apply_stash', { path, index, oid, reinstateIndex });
+}
+
+export async function popStash(
+    path: string,
+    index: number,
+    oid?: string,
+    reinstateIndex?: boolean
+): Promise<StashApplyResult> {
+    return invoke<StashApplyResult>('pop_stash', { path, index, oid, reinstateIndex });
+}
+
+export async function dropStash(path: string, index: number, oid?: string): Promise<void> {
+    return invoke('drop_stash', { path, index, oid });
+}
+
+export async function getOldStashes(minDays: number): Promise<RepoStash[]> {
+    return invoke<RepoStash[]>('get_old_stashes', { minDays });
+}
+
+// ========== Staging Commands ==========
+
+export async function getFileDiff(path: string, file: string, staged?: boolean): Promise<FileDiff> {
+    return invoke<FileDiff>('get_file_diff', { path, file, staged });
+}
+
+export async function stageFiles(path: string, files: string[]): Promise<void> {
+    return invoke('stage_files', { path, files });
+}
+
+export async function unstageFiles(path: string, files: string[]): Promise<void> {
+    return invoke('unstage_files', { path, files });
+}
+
+export async function stageSelection(path: string, file: string, selection: PatchSelection): Promise<void> {
+    return invoke('stage_selection', { path, file, selection });
+}
+
+export async function unstageSelection(path: string, file: string, selection: PatchSelection): Promise<void> {
+    return invoke('unstage_selection', { path, file, selection });
+}
+
+export async function discardChanges(path: string, files: string[]): Promise<DiscardBackup> {
+    return invoke<DiscardBackup>('discard_changes', { path, files });
+}
+
+export async function listDiscardBackups(path: string): Promise<DiscardBackup[]> {
+    return invoke<DiscardBackup[]>('list_discard_backups', { path });
+}
+
+export async function restoreDiscardBackup(path: string, refName: string): Promise<string[]> {
+    return invoke<string[]>('restore_discard_backup', { path, refName });
+}
+
+// ========== Commit Commands ==========
+
+export async function createCommit(
+    path: string,
+    message: string,
+    options?: Partial<CommitOptions>
+): Promise<CommitLogEntry> {
+    return invoke<CommitLogEntry>('create_commit', { path, message, options });
+}
+
+// ========== Branch Sweeper Commands ==========
+
+export type SweepReason = 'merged' | 'upstream_gone' | 'stale';
+
+export interface SweepCriteria {
+    merged?: boolean;
+    upstream_gone?: boolean;
+    older_than_days?: number | null;
+    protected_patterns?: string[];
+}
+
+export interface SweepCandidate {
+    branch: string;
+    tip_oid: string;
+    tip_date: number;
+    reasons: SweepReason[];
+}
+
+export interface SkippedBranch {
+    branch: string;
+    reason: string;
+}
+
+export interface SweepPlan {
+    repo_path: string;
+    candidates: SweepCandidate[];
+    skipped: SkippedBranch[];
+}
+
+export interface RepoSweepPlan {
+    repo_id: number;
+    plan: SweepPlan;
+}
+
+export interface SweepOutcome {
+    branch: string;
+    tip_oid: string;
+    deleted: boolean;
+    error: string | null;
+}
+
+export interface RepoSweepResult {
+    repo_id: number;
+    outcomes: SweepOutcome[];
+}
+
+export interface DeletedBranch {
+    id: number;
+    repo_id: number;
+    branch_name: string;
+    tip_oid: string;
+    deleted_at: string;
+    restored_at: string | null;
+}
+
+export async function planBranchSweep(
+    repoIds: number[],
+    criteria?: SweepCriteria
+): Promise<RepoSweepPlan[]> {
+    return invoke<RepoSweepPlan[]>('plan_branch_sweep', { repoIds, criteria });
+}
+
+export async function executeBranchSweep(
+    plans: RepoSweepPlan[],
+    criteria?: SweepCriteria
+): Promise<RepoSweepResult[]> {
+    return invoke<RepoSweepResult[]>('execute_branch_sweep', { plans, criteria });
+}
+
+export async function getDeletedBranches(repoId?: number): Promise<DeletedBranch[]> {
+    return invoke<DeletedBranch[]>('get_deleted_branches', { repoId });
+}
+
+export async function restoreDeletedBranch(id: number): Promise<void> {
+    return invoke<void>('restore_deleted_branch', { id });
+}
+
+// ========== Branch Management Commands ==========
+
+export async function checkoutBranch(
+    repoId: number,
+    branch: string,
+    options?: Partial<CheckoutOptions>
+): Promise<CheckoutResult> {
+    return invoke<CheckoutResult>('checkout_branch', { repoId, branch, options });
+}
+
+export async function createBranch(repoId: number, name: string, startPoint?: string): Promise<BranchInfo> {
+    return invoke<BranchInfo>('create_branch', { repoId, name, startPoint });
+}
+
+export async function renameBranch(repoId: number, oldName: string, newName: string): Promise<BranchInfo> {
+    return invoke<BranchInfo>('rename_branch', { repoId, oldName, newName });
+}
+
+export async function deleteBranch(repoId: number, name: string, force?: boolean): Promise<DeletedBranchTip> {
+    return invoke<DeletedBranchTip>('delete_branch', { repoId, name, force });
+}
+
+export async function setBranchUpstream(
+    repoId: number,
+    name: string,
+    upstream: string | null
+): Promise<BranchInfo> {
+    return invoke<BranchInfo>('set_branch_upstream', { repoId, name, upstream });
+}
+
+// ========== Cross-Repo Branch Commands ==========
+
+export async function findBranchAcrossRepos(
+    name: string,
+    repoIds?: number[],
+    tag?: string
+): Promise<RepoBranchPresence[]> {
+    return invoke<RepoBranchPresence[]>('find_branch_across_repos', { name, repoIds, tag });
+}
+
+export async function bulkBranchOperation(
+    operation: BranchOperation,
+    repoIds?: number[],
+    tag?: string
+): Promise<BranchOperationReport> {
+    return invoke<BranchOperationReport>('bulk_branch_operation', { operation, repoIds, tag });
+}
+
+// ========== Conflict Commands ==========
+
+export async function listConflicts(path: string): Promise<ConflictReport> {
+    return invoke<ConflictReport>('list_conflicts', { path });
+}
+
+export async function resolveConflict(path: string, file: string, resolution: ConflictResolution): Promise<void> {
+    return invoke('resolve_conflict', { path, file, resolution });
+}
+
+export async function continueOperation(path: string, message?: string): Promise<ContinueOutcome> {
+    return invoke<ContinueOutcome>('continue_operation', { path, message });
+}
+
+export async function abortOperation(path: string): Promise<void> {
+    return invoke('abort_operation', { path });
+}
+
+// ========== Reflog Commands ==========
+
+export async function listReflog(path: string, reference?: string, limit?: number): Promise<ReflogEntry[]> {
+    return invoke<ReflogEntry[]>('list_reflog', { path, reference, limit });
+}
+
+export async function restoreReflogEntry(path: string, index: number, reference?: string): Promise<ReflogRestore> {
+    return invoke<ReflogRestore>('restore_reflog_entry', { path, reference, index });
+}
+
+// ========== Worktree Commands ==========
+
+export async function listWorktrees(repoId: number, withHealth?: boolean): Promise<WorktreeInfo[]> {
+    return invoke<WorktreeInfo[]>('list_worktrees', { repoId, withHealth });
+}
+
+export async function addWorktree(
+    repoId: number,
+    branch: string,
+    path: string,
+    startPoint?: string
+): Promise<WorktreeInfo> {
+    return invoke<WorktreeInfo>('add_worktree', { repoId, branch, path, startPoint });
+}
+
+export async function removeWorktree(repoId: number, name: string, force?: boolean): Promise<void> {
+    return invoke('remove_worktree', { repoId, name, force });
+}
+
+export async function pruneWorktrees(repoId: number): Promise<string[]> {
+    return invoke<string[]>('prune_worktrees', { repoId });
+}
+
+// ========== Release Commands ==========
+
+export async function listGitTags(path: string): Promise<TagInfo[]> {
+    return invoke<TagInfo[]>('list_git_tags', { path });
+}
+
+export async function getReleaseSummary(path: string, includePrereleases?: boolean): Promise<ReleaseSummary> {
+    return invoke<ReleaseSummary>('get_release_summary', { path, includePrereleases });
+}
+
+export async function getReleaseNotes(path: string, from?: string, to?: string): Promise<ReleaseNotes> {
+    return invoke<ReleaseNotes>('get_release_notes', { path, from, to });
+}
+
+export async function getReleaseInventory(
+    repoIds?: number[],
+    tag?: string,
+    includePrereleases?: boolean
+): Promise<RepoRelease[]> {
+    return invoke<RepoRelease[]>('get_release_inventory', { repoIds, tag, includePrereleases });
+}
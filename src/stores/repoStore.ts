@@ -3,7 +3,7 @@
  */
 
 import { create } from 'zustand';
-import { RepoInfo, ScanPath, Tag, CommitLogEntry, getRepositories, getScanPaths, getTags, startScan, getCommitLog } from '../services/tauri';
+import { RepoInfo, ScanPath, Tag, CommitLogEntry, getRepositories, getScanPaths, getTags, startScan, getCommitLog, errorMessage } from '../services/tauri';
 
 interface RepoState {
     // Data
@@ -24,6 +24,7 @@ interface RepoState {
 
     // Actions
     fetchRepositories: () => Promise<void>;
+    applyRepoUpdate: (repo: RepoInfo) => void;
     fetchScanPaths: () => Promise<void>;
     fetchTags: () => Promise<void>;
     runScan: () => Promise<void>;
@@ -58,16 +59,20 @@ export const useRepoStore = create<RepoState>((set, get) => ({
             const repos = await getRepositories();
             set({ repositories: repos, isLoading: false });
         } catch (e) {
-            set({ error: String(e), isLoading: false });
+            set({ error: errorMessage(e), isLoading: false });
         }
     },
 
+    applyRepoUpdate: (repo) => set({
+        repositories: get().repositories.map(r => r.id === repo.id ? repo : r)
+    }),
+
     fetchScanPaths: async () => {
         try {
             const paths = await getScanPaths();
             set({ scanPaths: paths });
         } catch (e) {
-            set({ error: String(e) });
+            set({ error: errorMessage(e) });
         }
     },
 
@@ -76,7 +81,7 @@ export const useRepoStore = create<RepoState>((set, get) => ({
             const tags = await getTags();
             set({ tags });
         } catch (e) {
-            set({ error: String(e) });
+            set({ error: errorMessage(e) });
         }
     },
 
@@ -91,7 +96,7 @@ export const useRepoStore = create<RepoState>((set, get) => ({
                 lastScanTime: new Date()
             });
         } catch (e) {
-            set({ error: String(e), isScanning: false });
+            set({ error: errorMessage(e), isScanning: false });
         }
     },
 
@@ -129,7 +134,7 @@ export const useRepoStore = create<RepoState>((set, get) => ({
             const logs = await getCommitLog(repo.path, 100); // Limit 100 for now
             set({ activeCommitLog: logs, isLoading: false });
         } catch (e) {
-            set({ error: String(e), isLoading: false });
+            set({ error: errorMessage(e), isLoading: false });
         }
     },
 }));